@@ -0,0 +1,120 @@
+use clap::Subcommand;
+use counter::{instruction, state::Counter};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+use crate::{Config, Result};
+
+#[derive(Subcommand)]
+pub enum CounterCommand {
+    /// Increments a counter, creating one if `--counter` is not given
+    Increment {
+        #[clap(long)]
+        program_id: Pubkey,
+        #[clap(long)]
+        counter: Option<Pubkey>,
+        /// Creates the counter with room for a rate limit, claimed by the payer
+        #[clap(long)]
+        rate_limited: bool,
+    },
+    /// Decrements a counter, saturating at zero
+    Decrement {
+        #[clap(long)]
+        program_id: Pubkey,
+        #[clap(long)]
+        counter: Pubkey,
+    },
+    /// Limits a counter to `max_increments` per `window` seconds, 0 removes the limit. The
+    /// payer must have created the counter with `--rate-limited`
+    SetRateLimit {
+        #[clap(long)]
+        program_id: Pubkey,
+        #[clap(long)]
+        counter: Pubkey,
+        #[clap(long)]
+        max_increments: u8,
+        #[clap(long, default_value = "0")]
+        window: i64,
+    },
+}
+
+pub fn process(config: &Config, command: CounterCommand) -> Result<()> {
+    let (program_id, counter) = match command {
+        CounterCommand::Increment {
+            program_id,
+            counter: Some(counter),
+            ..
+        } => {
+            config.send(&[instruction::increment(program_id, counter)?], &[])?;
+            (program_id, counter)
+        }
+        CounterCommand::Increment {
+            program_id,
+            counter: None,
+            rate_limited,
+        } => {
+            let counter = Keypair::new();
+            println!("Creating counter {}", counter.pubkey());
+            let space = if rate_limited {
+                Counter::RATE_LIMITED_LEN
+            } else {
+                Counter::LEN
+            };
+            let mut instructions =
+                vec![config.create_account(&counter.pubkey(), space, &program_id)?];
+            if rate_limited {
+                // The counter keypair is only around now, so claim the rate limit without a limit
+                instructions.push(instruction::claim_rate_limit(
+                    program_id,
+                    counter.pubkey(),
+                    config.payer.pubkey(),
+                    0,
+                    0,
+                )?);
+            }
+            instructions.push(instruction::increment(program_id, counter.pubkey())?);
+            config.send(&instructions, &[&counter])?;
+            (program_id, counter.pubkey())
+        }
+        CounterCommand::Decrement {
+            program_id,
+            counter,
+        } => {
+            config.send(&[instruction::decrement(program_id, counter)?], &[])?;
+            (program_id, counter)
+        }
+        CounterCommand::SetRateLimit {
+            program_id,
+            counter,
+            max_increments,
+            window,
+        } => {
+            let authority = config.payer.pubkey();
+            config.send(
+                &[instruction::set_rate_limit(
+                    program_id,
+                    counter,
+                    authority,
+                    max_increments,
+                    window,
+                )?],
+                &[],
+            )?;
+            (program_id, counter)
+        }
+    };
+
+    let account = config.client.get_account(&counter)?;
+    if account.owner != program_id {
+        return Err(format!("Counter {} is not owned by {}", counter, program_id).into());
+    }
+    let (counter, rate_limit) = Counter::unpack(&account.data)?;
+    println!("Count: {}", counter.count);
+    if let Some(rate_limit) = rate_limit.filter(|rate_limit| rate_limit.max_increments > 0) {
+        println!(
+            "Rate limit: {}/{} increments per {}s",
+            rate_limit.len, rate_limit.max_increments, rate_limit.window
+        );
+    }
+    Ok(())
+}
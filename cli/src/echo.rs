@@ -0,0 +1,62 @@
+use clap::Subcommand;
+use echo::instruction;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+use crate::{Config, Result};
+
+#[derive(Subcommand)]
+pub enum EchoCommand {
+    /// Echoes `--data` into `--buffer`, creating a buffer that fits it if none is given
+    Write {
+        #[clap(long)]
+        program_id: Pubkey,
+        #[clap(long)]
+        buffer: Option<Pubkey>,
+        #[clap(long)]
+        data: String,
+    },
+    /// Prints the contents of an echo buffer
+    Read {
+        #[clap(long)]
+        buffer: Pubkey,
+    },
+}
+
+pub fn process(config: &Config, command: EchoCommand) -> Result<()> {
+    let buffer = match command {
+        EchoCommand::Write {
+            program_id,
+            buffer: Some(buffer),
+            data,
+        } => {
+            config.send(&[instruction::echo(program_id, buffer, data.into_bytes())?], &[])?;
+            buffer
+        }
+        EchoCommand::Write {
+            program_id,
+            buffer: None,
+            data,
+        } => {
+            let buffer = Keypair::new();
+            println!("Creating buffer {}", buffer.pubkey());
+            config.send(
+                &[
+                    config.create_account(&buffer.pubkey(), data.len(), &program_id)?,
+                    instruction::echo(program_id, buffer.pubkey(), data.into_bytes())?,
+                ],
+                &[&buffer],
+            )?;
+            buffer.pubkey()
+        }
+        EchoCommand::Read { buffer } => buffer,
+    };
+
+    let data = config.client.get_account_data(&buffer)?;
+    println!("Buffer {} ({} bytes)", buffer, data.len());
+    match std::str::from_utf8(&data) {
+        Ok(echo) => println!("{}", echo.trim_end_matches('\0')),
+        Err(_) => println!("{:?}", data),
+    }
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use bootcamp_inspector::{errors::decode_program_error, Programs};
+use clap::Args;
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+use crate::Result;
+
+#[derive(Args)]
+pub struct ErrorArgs {
+    /// Id of the program that returned the error
+    program_id: Pubkey,
+    /// Custom error code, decimal or hex as in "custom program error: 0x1"
+    code: String,
+    /// Id of the lecture_1 counter program
+    #[clap(long)]
+    counter_program: Option<Pubkey>,
+    /// Id of the token program
+    #[clap(long)]
+    token_program: Option<Pubkey>,
+    /// Id of the echo program
+    #[clap(long)]
+    echo_program: Option<Pubkey>,
+    /// Id of the marketplace program
+    #[clap(long)]
+    marketplace_program: Option<Pubkey>,
+    /// Id of the uploader program
+    #[clap(long)]
+    uploader_program: Option<Pubkey>,
+}
+
+pub fn process(args: ErrorArgs) -> Result<()> {
+    let code = match args.code.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16)?,
+        None => args.code.parse()?,
+    };
+    let programs = Programs {
+        counter: args.counter_program,
+        token: args.token_program,
+        echo: args.echo_program,
+        marketplace: args.marketplace_program,
+        uploader: args.uploader_program,
+        ..Programs::default()
+    };
+    let program = programs.program(&args.program_id).ok_or_else(|| {
+        format!(
+            "Unknown program {}, pass its id with --<program>-program",
+            args.program_id
+        )
+    })?;
+    let error = ProgramError::Custom(code);
+    match decode_program_error(&args.program_id, &error, &programs) {
+        Some(error) => println!("{}", error),
+        None => println!("Unknown error code {} for {:?}", code, program),
+    }
+    Ok(())
+}
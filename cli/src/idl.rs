@@ -0,0 +1,23 @@
+use bootcamp_inspector::errors::Program;
+use clap::Args;
+
+use crate::Result;
+
+#[derive(Args)]
+pub struct IdlArgs {
+    /// counter, authorized_counter, count_tracker, token or echo
+    program: Program,
+}
+
+pub fn process(args: IdlArgs) -> Result<()> {
+    let idl = match args.program {
+        Program::Counter => counter::idl(),
+        Program::AuthorizedCounter => authorized_counter::idl(),
+        Program::CountTracker => count_tracker::idl(),
+        Program::Token => token::idl(),
+        Program::Echo => echo::idl(),
+        program => return Err(format!("{:?} has no IDL", program).into()),
+    };
+    println!("{}", serde_json::to_string_pretty(&idl.to_json())?);
+    Ok(())
+}
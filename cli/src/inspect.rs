@@ -0,0 +1,50 @@
+use bootcamp_inspector::{inspect, Programs};
+use clap::Args;
+use solana_client::rpc_client::RpcClient;
+use solana_program::pubkey::Pubkey;
+
+use std::{fs, path::PathBuf};
+
+use crate::Result;
+
+#[derive(Args)]
+pub struct InspectArgs {
+    /// Account to fetch over RPC
+    #[clap(long, conflicts_with_all = &["file", "base64"])]
+    address: Option<Pubkey>,
+    /// File holding the raw account data
+    #[clap(long, conflicts_with = "base64", requires = "owner")]
+    file: Option<PathBuf>,
+    /// Base64 encoded account data
+    #[clap(long, requires = "owner")]
+    base64: Option<String>,
+    /// Owner of the account, needed with `--file` and `--base64`
+    #[clap(long)]
+    owner: Option<Pubkey>,
+    /// Id of the lecture_1 counter program, its accounts have no header
+    #[clap(long)]
+    counter_program: Option<Pubkey>,
+    /// Id of the echo program, plain echo buffers have no header
+    #[clap(long)]
+    echo_program: Option<Pubkey>,
+}
+
+pub fn process(url: String, args: InspectArgs) -> Result<()> {
+    let (owner, data) = match (args.address, args.file, args.base64) {
+        (Some(address), _, _) => {
+            let account = RpcClient::new(url).get_account(&address)?;
+            (account.owner, account.data)
+        }
+        (_, Some(file), _) => (args.owner.unwrap(), fs::read(file)?),
+        (_, _, Some(data)) => (args.owner.unwrap(), base64::decode(data)?),
+        _ => return Err("One of --address, --file or --base64 is required".into()),
+    };
+    let programs = Programs {
+        counter: args.counter_program,
+        echo: args.echo_program,
+        ..Programs::default()
+    };
+    let account = inspect(&owner, &data, &programs)?;
+    println!("{}", serde_json::to_string_pretty(&account.to_json())?);
+    Ok(())
+}
@@ -0,0 +1,129 @@
+//! Drives the lecture programs against a cluster, e.g. `solana-test-validator`:
+//!
+//! ```text
+//! bootcamp-cli --url http://127.0.0.1:8899 counter increment --program-id <ID>
+//! ```
+
+mod counter;
+mod echo;
+mod error;
+mod idl;
+mod inspect;
+mod sharded;
+mod token;
+mod tracker;
+
+use clap::{Parser, Subcommand};
+use solana_client::rpc_client::RpcClient;
+use solana_program::{instruction::Instruction, pubkey::Pubkey, system_instruction};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    signature::{read_keypair_file, Keypair, Signature, Signer},
+    transaction::Transaction,
+};
+
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+#[derive(Parser)]
+#[clap(name = "bootcamp-cli", about = "Sends instructions to the bootcamp programs")]
+struct Cli {
+    /// JSON RPC URL of the cluster
+    #[clap(long, short, default_value = "http://127.0.0.1:8899")]
+    url: String,
+    /// Keypair that pays for and signs every transaction
+    #[clap(long, short, default_value = "~/.config/solana/id.json")]
+    keypair: String,
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// lecture_1 counter
+    #[clap(subcommand)]
+    Counter(counter::CounterCommand),
+    /// lecture_3 count_tracker
+    #[clap(subcommand)]
+    Tracker(tracker::TrackerCommand),
+    /// lecture_3 authorized_counter incremented through shards
+    #[clap(subcommand)]
+    Sharded(sharded::ShardedCommand),
+    /// lecture_4 token
+    #[clap(subcommand)]
+    Token(token::TokenCommand),
+    /// Echo program from the project specs
+    #[clap(subcommand)]
+    Echo(echo::EchoCommand),
+    /// Decodes an account of any of the programs as JSON
+    Inspect(inspect::InspectArgs),
+    /// Explains a custom program error code
+    Error(error::ErrorArgs),
+    /// Prints the Anchor compatible IDL of a program
+    Idl(idl::IdlArgs),
+}
+
+pub struct Config {
+    pub client: RpcClient,
+    pub payer: Keypair,
+}
+
+impl Config {
+    pub fn new(url: String, keypair: &str) -> Result<Self> {
+        let keypair_path = match keypair.strip_prefix("~/") {
+            Some(path) => format!("{}/{}", std::env::var("HOME")?, path),
+            None => keypair.to_string(),
+        };
+        Ok(Self {
+            client: RpcClient::new_with_commitment(url, CommitmentConfig::confirmed()),
+            payer: read_keypair_file(&keypair_path)
+                .map_err(|e| format!("Failed to read keypair {}: {}", keypair_path, e))?,
+        })
+    }
+
+    /// Sends `instructions` paid for by the payer, `signers` sign alongside it.
+    pub fn send(&self, instructions: &[Instruction], signers: &[&Keypair]) -> Result<Signature> {
+        let mut all_signers = vec![&self.payer];
+        all_signers.extend_from_slice(signers);
+        let mut tx = Transaction::new_with_payer(instructions, Some(&self.payer.pubkey()));
+        tx.sign(&all_signers, self.client.get_latest_blockhash()?);
+        let signature = self.client.send_and_confirm_transaction(&tx)?;
+        println!("Signature: {}", signature);
+        Ok(signature)
+    }
+
+    /// Instruction creating a rent exempt account of `space` bytes owned by `owner`.
+    pub fn create_account(
+        &self,
+        account: &Pubkey,
+        space: usize,
+        owner: &Pubkey,
+    ) -> Result<Instruction> {
+        let lamports = self.client.get_minimum_balance_for_rent_exemption(space)?;
+        Ok(system_instruction::create_account(
+            &self.payer.pubkey(),
+            account,
+            lamports,
+            space as u64,
+            owner,
+        ))
+    }
+}
+
+fn main() -> Result<()> {
+    let Cli {
+        url,
+        keypair,
+        command,
+    } = Cli::parse();
+    match command {
+        Command::Counter(command) => counter::process(&Config::new(url, &keypair)?, command),
+        Command::Tracker(command) => tracker::process(&Config::new(url, &keypair)?, command),
+        Command::Sharded(command) => sharded::process(&Config::new(url, &keypair)?, command),
+        Command::Token(command) => token::process(&Config::new(url, &keypair)?, command),
+        Command::Echo(command) => echo::process(&Config::new(url, &keypair)?, command),
+        // Needs neither a keypair nor, for local data, a cluster
+        Command::Inspect(args) => inspect::process(url, args),
+        Command::Error(args) => error::process(args),
+        Command::Idl(args) => idl::process(args),
+    }
+}
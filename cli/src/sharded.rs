@@ -0,0 +1,172 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use authorized_counter::{
+    instruction,
+    state::{total_count, Counter, Shard},
+};
+use bootcamp_accounts::BorshAccount;
+use clap::Subcommand;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+use crate::{Config, Result};
+
+/// Shards created or aggregated per transaction
+const SHARDS_PER_TRANSACTION: usize = 8;
+
+#[derive(Subcommand)]
+pub enum ShardedCommand {
+    /// Creates `--shards` shards of `--counter`, creating and claiming a new counter if
+    /// none is given. The payer must be the authority of the counter.
+    Init {
+        #[clap(long, default_value_t = authorized_counter::id())]
+        program_id: Pubkey,
+        #[clap(long)]
+        counter: Option<Pubkey>,
+        #[clap(long)]
+        shards: u8,
+    },
+    /// Increments `--shard`, or a shard picked at random
+    Increment {
+        #[clap(long, default_value_t = authorized_counter::id())]
+        program_id: Pubkey,
+        #[clap(long)]
+        counter: Pubkey,
+        #[clap(long)]
+        shards: u8,
+        #[clap(long)]
+        shard: Option<u8>,
+    },
+    /// Moves the counts of the shards into the counter
+    Aggregate {
+        #[clap(long, default_value_t = authorized_counter::id())]
+        program_id: Pubkey,
+        #[clap(long)]
+        counter: Pubkey,
+        #[clap(long)]
+        shards: u8,
+    },
+    /// Prints the count of `--counter` including the counts of its shards
+    Count {
+        #[clap(long, default_value_t = authorized_counter::id())]
+        program_id: Pubkey,
+        #[clap(long)]
+        counter: Pubkey,
+        #[clap(long)]
+        shards: u8,
+    },
+}
+
+fn shard_addresses(program_id: &Pubkey, counter: &Pubkey, shards: u8) -> Vec<Pubkey> {
+    (0..shards)
+        .map(|index| Shard::find_address(program_id, counter, index).0)
+        .collect()
+}
+
+pub fn process(config: &Config, command: ShardedCommand) -> Result<()> {
+    let authority = config.payer.pubkey();
+    let (program_id, counter, shards) = match command {
+        ShardedCommand::Init {
+            program_id,
+            counter,
+            shards,
+        } => {
+            let counter = match counter {
+                Some(counter) => counter,
+                None => {
+                    // Shards need a claimed counter
+                    let counter = Keypair::new();
+                    println!("Creating counter {}", counter.pubkey());
+                    config.send(
+                        &[
+                            config.create_account(&counter.pubkey(), Counter::LEN, &program_id)?,
+                            instruction::increment(
+                                program_id,
+                                counter.pubkey(),
+                                authority,
+                                instruction::CounterInstruction::Increment,
+                            )?,
+                        ],
+                        &[&counter],
+                    )?;
+                    counter.pubkey()
+                }
+            };
+            let instructions = shard_addresses(&program_id, &counter, shards)
+                .into_iter()
+                .zip(0..)
+                .map(|(shard, index)| {
+                    instruction::initialize_shard(
+                        program_id, shard, counter, authority, authority, index,
+                    )
+                })
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            for chunk in instructions.chunks(SHARDS_PER_TRANSACTION) {
+                config.send(chunk, &[])?;
+            }
+            (program_id, counter, shards)
+        }
+        ShardedCommand::Increment {
+            program_id,
+            counter,
+            shards,
+            shard,
+        } => {
+            let index = match shard {
+                Some(index) => index,
+                None if shards == 0 => return Err("--shards must be at least 1".into()),
+                None => {
+                    let nanos = SystemTime::now().duration_since(UNIX_EPOCH)?.subsec_nanos();
+                    (nanos % shards as u32) as u8
+                }
+            };
+            println!("Incrementing shard {}", index);
+            let (shard, _) = Shard::find_address(&program_id, &counter, index);
+            config.send(
+                &[instruction::increment_shard(program_id, shard, authority)?],
+                &[],
+            )?;
+            (program_id, counter, shards)
+        }
+        ShardedCommand::Aggregate {
+            program_id,
+            counter,
+            shards,
+        } => {
+            let addresses = shard_addresses(&program_id, &counter, shards);
+            for chunk in addresses.chunks(SHARDS_PER_TRANSACTION) {
+                config.send(&[instruction::aggregate(program_id, counter, chunk)?], &[])?;
+            }
+            (program_id, counter, shards)
+        }
+        ShardedCommand::Count {
+            program_id,
+            counter,
+            shards,
+        } => (program_id, counter, shards),
+    };
+
+    let account = config.client.get_account(&counter)?;
+    if account.owner != program_id {
+        return Err(format!("Counter {} is not owned by {}", counter, program_id).into());
+    }
+    let counter_state = Counter::unpack_any(&account.data)?;
+    let addresses = shard_addresses(&program_id, &counter, shards);
+    let mut shards = vec![];
+    // RPC nodes return at most 100 accounts per request
+    for chunk in addresses.chunks(100) {
+        let accounts = config.client.get_multiple_accounts(chunk)?;
+        // Shards that were never created hold nothing
+        for account in accounts.into_iter().flatten() {
+            if account.owner == program_id {
+                shards.push(Shard::unpack(&account.data)?);
+            }
+        }
+    }
+    println!("Aggregated count: {}", counter_state.count);
+    println!(
+        "Count: {}",
+        total_count(&counter_state, &shards).ok_or("Count overflows")?
+    );
+    Ok(())
+}
@@ -0,0 +1,168 @@
+use bootcamp_accounts::BorshAccount;
+use clap::Subcommand;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use token::{
+    instruction,
+    state::{Mint, TokenAccount},
+};
+
+use crate::{Config, Result};
+
+#[derive(Subcommand)]
+pub enum TokenCommand {
+    /// Creates a mint with the payer as its authority
+    CreateMint {
+        #[clap(long)]
+        program_id: Pubkey,
+    },
+    /// Creates a token account for `--mint`, owned by the payer unless `--owner` is given
+    CreateAccount {
+        #[clap(long)]
+        program_id: Pubkey,
+        #[clap(long)]
+        mint: Pubkey,
+        #[clap(long)]
+        owner: Option<Pubkey>,
+    },
+    /// Mints `--amount` tokens into `--account`, the payer must be the mint authority
+    Mint {
+        #[clap(long)]
+        program_id: Pubkey,
+        #[clap(long)]
+        mint: Pubkey,
+        #[clap(long)]
+        account: Pubkey,
+        #[clap(long)]
+        amount: u64,
+    },
+    /// Transfers `--amount` tokens owned by the payer from `--from` to `--to`
+    Transfer {
+        #[clap(long)]
+        program_id: Pubkey,
+        #[clap(long)]
+        from: Pubkey,
+        #[clap(long)]
+        to: Pubkey,
+        #[clap(long)]
+        amount: u64,
+    },
+    /// Burns `--amount` tokens owned by the payer from `--account`
+    Burn {
+        #[clap(long)]
+        program_id: Pubkey,
+        #[clap(long)]
+        mint: Pubkey,
+        #[clap(long)]
+        account: Pubkey,
+        #[clap(long)]
+        amount: u64,
+    },
+    /// Adds the account header to `--account`, created before token accounts had one
+    Migrate {
+        #[clap(long)]
+        program_id: Pubkey,
+        #[clap(long)]
+        account: Pubkey,
+    },
+}
+
+fn print_mint(config: &Config, mint: &Pubkey) -> Result<()> {
+    let data = config.client.get_account_data(mint)?;
+    let state = Mint::unpack(&data)?;
+    println!("Mint {}: supply {}, authority {}", mint, state.supply, state.authority);
+    Ok(())
+}
+
+fn print_token_account(config: &Config, token_account: &Pubkey) -> Result<()> {
+    let data = config.client.get_account_data(token_account)?;
+    let state = TokenAccount::unpack_any(&data)?;
+    println!(
+        "Token account {}: {} of mint {}, owner {}",
+        token_account, state.amount, state.mint, state.owner
+    );
+    Ok(())
+}
+
+pub fn process(config: &Config, command: TokenCommand) -> Result<()> {
+    let payer = config.payer.pubkey();
+    match command {
+        TokenCommand::CreateMint { program_id } => {
+            let mint = Keypair::new();
+            config.send(
+                &[
+                    config.create_account(&mint.pubkey(), Mint::LEN, &program_id)?,
+                    instruction::initialize_mint(program_id, mint.pubkey(), payer)?,
+                ],
+                &[&mint],
+            )?;
+            print_mint(config, &mint.pubkey())
+        }
+        TokenCommand::CreateAccount {
+            program_id,
+            mint,
+            owner,
+        } => {
+            let token_account = Keypair::new();
+            config.send(
+                &[
+                    config.create_account(&token_account.pubkey(), TokenAccount::LEN, &program_id)?,
+                    instruction::initialize_token_account(
+                        program_id,
+                        token_account.pubkey(),
+                        mint,
+                        owner.unwrap_or(payer),
+                    )?,
+                ],
+                &[&token_account],
+            )?;
+            print_token_account(config, &token_account.pubkey())
+        }
+        TokenCommand::Mint {
+            program_id,
+            mint,
+            account,
+            amount,
+        } => {
+            config.send(
+                &[instruction::mint(program_id, account, mint, payer, amount)?],
+                &[],
+            )?;
+            print_mint(config, &mint)?;
+            print_token_account(config, &account)
+        }
+        TokenCommand::Transfer {
+            program_id,
+            from,
+            to,
+            amount,
+        } => {
+            config.send(
+                &[instruction::transfer(program_id, from, to, payer, amount)?],
+                &[],
+            )?;
+            print_token_account(config, &from)?;
+            print_token_account(config, &to)
+        }
+        TokenCommand::Burn {
+            program_id,
+            mint,
+            account,
+            amount,
+        } => {
+            config.send(
+                &[instruction::burn(program_id, account, mint, payer, amount)?],
+                &[],
+            )?;
+            print_mint(config, &mint)?;
+            print_token_account(config, &account)
+        }
+        TokenCommand::Migrate {
+            program_id,
+            account,
+        } => {
+            config.send(&[instruction::migrate(program_id, account, payer)?], &[])?;
+            print_token_account(config, &account)
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use authorized_counter::state::Counter;
+use bootcamp_accounts::BorshAccount;
+use clap::Subcommand;
+use count_tracker::{instruction, state::Tracker};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+use crate::{Config, Result};
+
+#[derive(Subcommand)]
+pub enum TrackerCommand {
+    /// Creates the payer's tracker for `--counter`, creating a new authorized_counter
+    /// account if none is given. Also creates the counter's leaderboard if needed.
+    Init {
+        #[clap(long, default_value_t = count_tracker::id())]
+        program_id: Pubkey,
+        #[clap(long)]
+        counter: Option<Pubkey>,
+    },
+    /// Increments `--counter` through the payer's tracker
+    Increment {
+        #[clap(long, default_value_t = count_tracker::id())]
+        program_id: Pubkey,
+        #[clap(long)]
+        counter: Pubkey,
+    },
+    /// Migrates the payer's tracker for `--counter` to the latest layout
+    Migrate {
+        #[clap(long, default_value_t = count_tracker::id())]
+        program_id: Pubkey,
+        #[clap(long)]
+        counter: Pubkey,
+    },
+}
+
+struct Addresses {
+    tracker: Pubkey,
+    authority: Pubkey,
+    leaderboard: Pubkey,
+}
+
+fn addresses(program_id: &Pubkey, user: &Pubkey, counter: &Pubkey) -> Addresses {
+    let (tracker, _) =
+        Pubkey::find_program_address(&[user.as_ref(), counter.as_ref()], program_id);
+    let (authority, _) = Pubkey::find_program_address(&[counter.as_ref()], program_id);
+    let (leaderboard, _) =
+        Pubkey::find_program_address(&[b"leaderboard", counter.as_ref()], program_id);
+    Addresses {
+        tracker,
+        authority,
+        leaderboard,
+    }
+}
+
+pub fn process(config: &Config, command: TrackerCommand) -> Result<()> {
+    let user = config.payer.pubkey();
+    let (program_id, counter) = match command {
+        TrackerCommand::Init {
+            program_id,
+            counter,
+        } => {
+            let mut instructions = vec![];
+            let mut signers = vec![];
+            let counter_keypair = Keypair::new();
+            let counter = match counter {
+                Some(counter) => counter,
+                None => {
+                    signers.push(&counter_keypair);
+                    println!("Creating counter {}", counter_keypair.pubkey());
+                    instructions.push(config.create_account(
+                        &counter_keypair.pubkey(),
+                        Counter::LEN,
+                        &authorized_counter::id(),
+                    )?);
+                    counter_keypair.pubkey()
+                }
+            };
+            let addresses = addresses(&program_id, &user, &counter);
+            instructions.push(instruction::initialize(
+                program_id,
+                addresses.tracker,
+                user,
+                addresses.authority,
+                counter,
+            )?);
+            if config.client.get_account(&addresses.leaderboard).is_err() {
+                instructions.push(instruction::initialize_leaderboard(
+                    program_id,
+                    addresses.leaderboard,
+                    user,
+                    counter,
+                )?);
+            }
+            config.send(&instructions, &signers)?;
+            (program_id, counter)
+        }
+        TrackerCommand::Increment {
+            program_id,
+            counter,
+        } => {
+            let addresses = addresses(&program_id, &user, &counter);
+            config.send(
+                &[instruction::increment(
+                    program_id,
+                    addresses.tracker,
+                    user,
+                    counter,
+                    addresses.authority,
+                    addresses.leaderboard,
+                )?],
+                &[],
+            )?;
+            (program_id, counter)
+        }
+        TrackerCommand::Migrate {
+            program_id,
+            counter,
+        } => {
+            let tracker = addresses(&program_id, &user, &counter).tracker;
+            config.send(
+                &[instruction::migrate(program_id, tracker, user, user)?],
+                &[],
+            )?;
+            (program_id, counter)
+        }
+    };
+
+    let tracker = addresses(&program_id, &user, &counter).tracker;
+    let data = config.client.get_account_data(&tracker)?;
+    let tracker = Tracker::unpack_any(&data)?;
+    println!("Counter: {}", counter);
+    println!("User count: {}", tracker.count);
+    println!("Global count at the last increment: {}", tracker.global_count);
+    Ok(())
+}
@@ -0,0 +1,275 @@
+//! Account headers shared by the bootcamp programs.
+//!
+//! Every account starts with a 16 byte `AccountHeader` holding an 8 byte discriminator
+//! and a version, so one account type can never be loaded as another. The body follows
+//! the header and is either cast in place (`ZeroCopy`) or Borsh encoded (`BorshAccount`).
+//!
+//! ```ignore
+//! #[derive(Clone, Copy, Zeroable, Pod, ZeroCopy)]
+//! #[repr(C)]
+//! pub struct Marketplace { ... }
+//!
+//! // The discriminator defaults to sha256("account:<Name>")[..8] and the version to 1
+//! #[derive(BorshSerialize, BorshDeserialize, BorshAccount)]
+//! #[account(discriminator = "MYACCNT_", version = 2)]
+//! pub struct MyAccount { ... }
+//! ```
+//!
+//! Changing the fields of a `BorshAccount` bumps its version. The old layout stays
+//! around under its old version, and `load`, `unpack` and `save` keep working on
+//! accounts written by it until they are migrated with `upgrade`:
+//!
+//! ```ignore
+//! #[derive(BorshSerialize, BorshDeserialize, BorshAccount)]
+//! #[account(name = "Tracker", version = 1)]
+//! pub struct TrackerV1 { ... }
+//!
+//! // Needs `From<TrackerV1> for Tracker` and `From<Tracker> for TrackerV1`
+//! #[derive(BorshSerialize, BorshDeserialize, BorshAccount, Clone)]
+//! #[account(version = 2, previous = "TrackerV1")]
+//! pub struct Tracker { ... }
+//! ```
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use bytemuck::{bytes_of_mut, from_bytes, from_bytes_mut, Pod, Zeroable};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use std::{
+    cell::{Ref, RefMut},
+    mem,
+};
+
+pub use bootcamp_accounts_derive::{BorshAccount, ZeroCopy};
+
+// Lets the derives expand in crates that rename or don't import solana-program
+#[doc(hidden)]
+pub use solana_program;
+
+/// Written in front of the body of every account.
+#[derive(Debug, Clone, Copy, Zeroable, Pod)]
+#[repr(C)]
+pub struct AccountHeader {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub _padding: [u8; 7], // keeps the body 8-byte aligned
+}
+
+pub const HEADER_LEN: usize = mem::size_of::<AccountHeader>();
+
+pub trait Discriminator {
+    const DISCRIMINATOR: [u8; 8];
+    const VERSION: u8;
+}
+
+/// Checks the owner of `account` and that it can hold at least `len` bytes.
+pub fn check_account(account: &AccountInfo, program_id: &Pubkey, len: usize) -> ProgramResult {
+    if account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    if account.data_len() < len {
+        return Err(ProgramError::AccountDataTooSmall);
+    }
+    Ok(())
+}
+
+/// Checks that `data` starts with the header of `T`.
+pub fn check_header<T: Discriminator>(data: &[u8]) -> ProgramResult {
+    if header_version::<T>(data)? != T::VERSION {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(())
+}
+
+/// Version of the header `data` starts with, which must have the discriminator of `T`.
+pub fn header_version<T: Discriminator>(data: &[u8]) -> Result<u8, ProgramError> {
+    if data.len() < HEADER_LEN {
+        return Err(ProgramError::AccountDataTooSmall);
+    }
+    let header: &AccountHeader = from_bytes(&data[..HEADER_LEN]);
+    if header.discriminator == [0; 8] {
+        return Err(ProgramError::UninitializedAccount);
+    }
+    if header.discriminator != T::DISCRIMINATOR {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(header.version)
+}
+
+/// Writes the header of `T` to the start of `data`, which must not be initialized yet.
+pub fn write_header<T: Discriminator>(data: &mut [u8]) -> ProgramResult {
+    if data.len() < HEADER_LEN {
+        return Err(ProgramError::AccountDataTooSmall);
+    }
+    let header: &mut AccountHeader = from_bytes_mut(&mut data[..HEADER_LEN]);
+    if header.discriminator != [0; 8] {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+    header.discriminator = T::DISCRIMINATOR;
+    header.version = T::VERSION;
+    Ok(())
+}
+
+/// Accounts whose body is cast in place from the account data.
+pub trait ZeroCopy: Pod + Discriminator {
+    /// Size of the header plus the body
+    const LEN: usize = HEADER_LEN + mem::size_of::<Self>();
+
+    fn load<'a>(
+        account: &'a AccountInfo,
+        program_id: &Pubkey,
+    ) -> Result<Ref<'a, Self>, ProgramError> {
+        check_account(account, program_id, Self::LEN)?;
+        let data = account.try_borrow_data()?;
+        check_header::<Self>(&data)?;
+        Ok(Ref::map(data, |data| {
+            from_bytes(&data[HEADER_LEN..Self::LEN])
+        }))
+    }
+
+    fn load_mut<'a>(
+        account: &'a AccountInfo,
+        program_id: &Pubkey,
+    ) -> Result<RefMut<'a, Self>, ProgramError> {
+        check_account(account, program_id, Self::LEN)?;
+        let data = account.try_borrow_mut_data()?;
+        check_header::<Self>(&data)?;
+        Ok(RefMut::map(data, |data| {
+            from_bytes_mut(&mut data[HEADER_LEN..Self::LEN])
+        }))
+    }
+
+    /// Writes the header to an uninitialized account and returns its body.
+    fn init<'a>(
+        account: &'a AccountInfo,
+        program_id: &Pubkey,
+    ) -> Result<RefMut<'a, Self>, ProgramError> {
+        check_account(account, program_id, Self::LEN)?;
+        let mut data = account.try_borrow_mut_data()?;
+        write_header::<Self>(&mut data)?;
+        Ok(RefMut::map(data, |data| {
+            from_bytes_mut(&mut data[HEADER_LEN..Self::LEN])
+        }))
+    }
+
+    /// Copies the body out of raw account data, e.g. fetched by a client. Unlike
+    /// `load` the data does not have to be aligned.
+    fn read(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() < Self::LEN {
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+        check_header::<Self>(data)?;
+        let mut body = Self::zeroed();
+        bytes_of_mut(&mut body).copy_from_slice(&data[HEADER_LEN..Self::LEN]);
+        Ok(body)
+    }
+}
+
+/// Fallback for accounts that can't be `Pod`, e.g. because of padding. The body is
+/// Borsh encoded, so it is copied in and out instead of cast in place. Bytes past the
+/// body are left alone, which lets a Borsh header sit in front of raw data.
+pub trait BorshAccount: BorshSerialize + BorshDeserialize + Discriminator {
+    fn load(account: &AccountInfo, program_id: &Pubkey) -> Result<Self, ProgramError> {
+        check_account(account, program_id, HEADER_LEN)?;
+        Self::unpack(&account.try_borrow_data()?)
+    }
+
+    /// Decodes raw account data, e.g. fetched by a client.
+    fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let version = header_version::<Self>(data)?;
+        if version == Self::VERSION {
+            Ok(Self::deserialize(&mut &data[HEADER_LEN..])?)
+        } else {
+            Self::unpack_version(version, &data[HEADER_LEN..])
+        }
+    }
+
+    /// Writes `self` in the layout of the version the account is on.
+    fn save(&self, account: &AccountInfo) -> ProgramResult {
+        let mut data = account.try_borrow_mut_data()?;
+        let version = header_version::<Self>(&data)?;
+        if version == Self::VERSION {
+            Ok(self.serialize(&mut &mut data[HEADER_LEN..])?)
+        } else {
+            self.pack_version(version, &mut data[HEADER_LEN..])
+        }
+    }
+
+    /// Writes the header and `self` to an uninitialized account.
+    fn init(&self, account: &AccountInfo, program_id: &Pubkey) -> ProgramResult {
+        check_account(account, program_id, HEADER_LEN)?;
+        let mut data = account.try_borrow_mut_data()?;
+        write_header::<Self>(&mut data)?;
+        Ok(self.serialize(&mut &mut data[HEADER_LEN..])?)
+    }
+
+    /// Rewrites an account of an older version in the current layout. The account has
+    /// to be resized to fit it first.
+    fn upgrade(&self, account: &AccountInfo) -> ProgramResult {
+        let mut data = account.try_borrow_mut_data()?;
+        if header_version::<Self>(&data)? >= Self::VERSION {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let body = self.try_to_vec()?;
+        if data.len() < HEADER_LEN + body.len() {
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+        let header: &mut AccountHeader = from_bytes_mut(&mut data[..HEADER_LEN]);
+        header.version = Self::VERSION;
+        data[HEADER_LEN..HEADER_LEN + body.len()].copy_from_slice(&body);
+        Ok(())
+    }
+
+    /// Decodes the body of an account written by an older `version`. Implemented by
+    /// `#[account(previous = "...")]`, accounts without one only read their own version.
+    fn unpack_version(_version: u8, _body: &[u8]) -> Result<Self, ProgramError> {
+        Err(ProgramError::InvalidAccountData)
+    }
+
+    /// Encodes `self` into the body of an account of an older `version`.
+    fn pack_version(&self, _version: u8, _body: &mut [u8]) -> ProgramResult {
+        Err(ProgramError::InvalidAccountData)
+    }
+}
+
+/// `BorshAccount::unpack_version` of `T`, whose previous version is `P`. Versions before
+/// `P` are read by `P` and converted twice.
+pub fn unpack_previous<T, P>(version: u8, body: &[u8]) -> Result<T, ProgramError>
+where
+    T: BorshAccount,
+    P: BorshAccount + Into<T>,
+{
+    check_previous::<T, P>(version)?;
+    let previous = if version == P::VERSION {
+        P::deserialize(&mut &body[..])?
+    } else {
+        P::unpack_version(version, body)?
+    };
+    Ok(previous.into())
+}
+
+/// `BorshAccount::pack_version` of `T`, whose previous version is `P`. Fields that `P`
+/// has no room for are dropped.
+pub fn pack_previous<T, P>(value: &T, version: u8, body: &mut [u8]) -> ProgramResult
+where
+    T: BorshAccount + Clone,
+    P: BorshAccount + From<T>,
+{
+    check_previous::<T, P>(version)?;
+    let previous = P::from(value.clone());
+    if version == P::VERSION {
+        Ok(previous.serialize(&mut &mut body[..])?)
+    } else {
+        previous.pack_version(version, body)
+    }
+}
+
+fn check_previous<T: Discriminator, P: Discriminator>(version: u8) -> ProgramResult {
+    if P::DISCRIMINATOR != T::DISCRIMINATOR || P::VERSION >= T::VERSION || version > P::VERSION
+    {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(())
+}
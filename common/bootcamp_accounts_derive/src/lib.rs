@@ -0,0 +1,116 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use sha2::{Digest, Sha256};
+use syn::{parse_macro_input, DeriveInput, Error, Lit, Meta, NestedMeta, Type};
+
+/// Implements `bootcamp_accounts::Discriminator` and `bootcamp_accounts::ZeroCopy`.
+#[proc_macro_derive(ZeroCopy, attributes(account))]
+pub fn derive_zero_copy(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    match account_attributes(&input) {
+        Ok((_, Some(previous))) => {
+            Error::new_spanned(previous, "only BorshAccount supports previous versions")
+                .to_compile_error()
+                .into()
+        }
+        Ok((discriminator, None)) => quote! {
+            #discriminator
+            impl ::bootcamp_accounts::ZeroCopy for #name {}
+        }
+        .into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// Implements `bootcamp_accounts::Discriminator` and `bootcamp_accounts::BorshAccount`.
+/// `#[account(previous = "TypeV1")]` names the layout of the version before this one,
+/// which is then still read and written until the account is migrated.
+#[proc_macro_derive(BorshAccount, attributes(account))]
+pub fn derive_borsh_account(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    let (discriminator, previous) = match account_attributes(&input) {
+        Ok(attributes) => attributes,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let versions = previous.map(|previous| {
+        let program_error = quote! { ::bootcamp_accounts::solana_program::program_error };
+        let entrypoint = quote! { ::bootcamp_accounts::solana_program::entrypoint };
+        quote! {
+            fn unpack_version(
+                version: u8,
+                body: &[u8],
+            ) -> ::core::result::Result<Self, #program_error::ProgramError> {
+                ::bootcamp_accounts::unpack_previous::<Self, #previous>(version, body)
+            }
+
+            fn pack_version(&self, version: u8, body: &mut [u8]) -> #entrypoint::ProgramResult {
+                ::bootcamp_accounts::pack_previous::<Self, #previous>(self, version, body)
+            }
+        }
+    });
+    quote! {
+        #discriminator
+        impl ::bootcamp_accounts::BorshAccount for #name {
+            #versions
+        }
+    }
+    .into()
+}
+
+/// Reads `#[account(discriminator = "...", version = N, name = "...", previous = "...")]`.
+/// The discriminator defaults to the first 8 bytes of sha256("account:<name>"), where the
+/// name defaults to the type's, and the version to 1. Returns the `Discriminator` impl
+/// and the type of the previous version.
+fn account_attributes(input: &DeriveInput) -> syn::Result<(TokenStream2, Option<Type>)> {
+    let name = &input.ident;
+    let mut account_name = name.to_string();
+    let mut discriminator = None;
+    let mut version = 1u8;
+    let mut previous = None;
+
+    for attr in input.attrs.iter().filter(|a| a.path.is_ident("account")) {
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            meta => return Err(Error::new_spanned(meta, "expected #[account(...)]")),
+        };
+        for nested in list.nested {
+            let pair = match nested {
+                NestedMeta::Meta(Meta::NameValue(pair)) => pair,
+                other => return Err(Error::new_spanned(other, "expected `key = value`")),
+            };
+            match (&pair.lit, pair.path.get_ident()) {
+                (Lit::Str(s), Some(key)) if key == "discriminator" => {
+                    let bytes: [u8; 8] = s.value().as_bytes().try_into().map_err(|_| {
+                        Error::new_spanned(s, "discriminator must be exactly 8 bytes")
+                    })?;
+                    discriminator = Some(bytes);
+                }
+                (Lit::Str(s), Some(key)) if key == "name" => account_name = s.value(),
+                (Lit::Str(s), Some(key)) if key == "previous" => previous = Some(s.parse()?),
+                (Lit::Int(i), Some(key)) if key == "version" => {
+                    version = i.base10_parse()?;
+                }
+                _ => return Err(Error::new_spanned(pair, "unknown account attribute")),
+            }
+        }
+    }
+    if previous.is_some() && version < 2 {
+        return Err(Error::new_spanned(name, "only versions after 1 have a previous version"));
+    }
+
+    let discriminator = discriminator.unwrap_or_else(|| {
+        Sha256::digest(format!("account:{}", account_name).as_bytes())[..8]
+            .try_into()
+            .unwrap()
+    });
+    let discriminator = quote! {
+        impl ::bootcamp_accounts::Discriminator for #name {
+            const DISCRIMINATOR: [u8; 8] = [#(#discriminator),*];
+            const VERSION: u8 = #version;
+        }
+    };
+    Ok((discriminator, previous))
+}
@@ -0,0 +1,129 @@
+//! Anchor compatible IDLs for the native bootcamp programs.
+//!
+//! The instruction layouts are declared next to the instruction enum and the IDL is
+//! generated from the same types the program decodes, so it can't drift from them.
+//!
+//! ```ignore
+//! #[derive(BorshSerialize, BorshDeserialize, IdlInstruction)]
+//! pub enum TrackerInstruction {
+//!     #[account(0, writable, name = "tracker", desc = "PDA of [user, counter]")]
+//!     #[account(1, writable, signer, name = "user", desc = "Pays for the tracker")]
+//!     Initialize,
+//! }
+//!
+//! let idl = Idl::new("count_tracker", env!("CARGO_PKG_VERSION"))
+//!     .instructions::<TrackerInstruction>()
+//!     .account::<Tracker>();
+//! ```
+//!
+//! Programs depend on `bootcamp_idl_derive` for the derives and only on this crate with
+//! their `idl` feature, which the derived impls are compiled behind. `cargo test -p
+//! bootcamp_idl --test emit` writes the IDLs to `target/idl`.
+//!
+//! Anchor programs start their instruction data with an 8 byte hash of the name, the
+//! native programs with the 1 byte Borsh index of the variant. Instructions carry it as
+//! `"discriminant": {"type": "u8", "value": <index>}`, like Shank IDLs do. Accounts
+//! start with the 8 byte discriminator Anchor expects, followed by the rest of the
+//! `bootcamp_accounts` header.
+
+use bootcamp_accounts::Discriminator;
+use serde_json::{json, Value};
+use solana_program::pubkey::Pubkey;
+
+pub use bootcamp_idl_derive::{IdlInstruction, IdlType};
+
+/// Instruction enums, each variant is one instruction of the IDL.
+pub trait IdlInstruction {
+    fn idl_instructions() -> Vec<Value>;
+}
+
+/// Borsh encoded structs and enums, including the bodies of accounts.
+pub trait IdlType {
+    fn idl_type() -> Value;
+}
+
+#[derive(Debug, Clone)]
+pub struct Idl {
+    name: String,
+    version: String,
+    address: Option<Pubkey>,
+    instructions: Vec<Value>,
+    accounts: Vec<Value>,
+    types: Vec<Value>,
+}
+
+impl Idl {
+    pub fn new(name: &str, version: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            version: version.to_string(),
+            address: None,
+            instructions: vec![],
+            accounts: vec![],
+            types: vec![],
+        }
+    }
+
+    /// Address the program is deployed at, for programs that declare their id.
+    pub fn address(mut self, address: Pubkey) -> Self {
+        self.address = Some(address);
+        self
+    }
+
+    pub fn instructions<T: IdlInstruction>(mut self) -> Self {
+        self.instructions.extend(T::idl_instructions());
+        self
+    }
+
+    /// Adds an account whose body is Borsh encoded after the `bootcamp_accounts` header.
+    pub fn account<T: IdlType + Discriminator>(mut self) -> Self {
+        let mut account = T::idl_type();
+        // The discriminator is the first 8 bytes, the fields describe the rest
+        let header = [
+            json!({"name": "version", "type": "u8"}),
+            json!({"name": "padding", "type": {"array": ["u8", 7]}}),
+        ];
+        if let Some(fields) = account["type"]["fields"].as_array_mut() {
+            fields.splice(0..0, header);
+        }
+        account["discriminator"] = json!(T::DISCRIMINATOR);
+        account["version"] = json!(T::VERSION);
+        self.accounts.push(account);
+        self
+    }
+
+    /// Adds a type used by instructions or accounts, or the state of an account that
+    /// has no header.
+    pub fn ty<T: IdlType>(mut self) -> Self {
+        self.types.push(T::idl_type());
+        self
+    }
+
+    pub fn to_json(&self) -> Value {
+        let mut metadata = json!({"origin": "bootcamp"});
+        if let Some(address) = &self.address {
+            metadata["address"] = json!(address.to_string());
+        }
+        json!({
+            "version": self.version,
+            "name": self.name,
+            "instructions": self.instructions,
+            "accounts": self.accounts,
+            "types": self.types,
+            "metadata": metadata,
+        })
+    }
+}
+
+#[doc(hidden)]
+pub mod __private {
+    pub use serde_json::{json, Value};
+
+    /// Adds the non-empty `docs` to the IDL object `value`.
+    pub fn with_docs(mut value: Value, docs: &[&str]) -> Value {
+        if docs.iter().any(|line| !line.is_empty()) {
+            value["docs"] = json!(docs);
+        }
+        value
+    }
+}
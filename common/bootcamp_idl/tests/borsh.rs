@@ -0,0 +1,371 @@
+//! Decodes the Borsh encoding of instructions and accounts with nothing but their IDL,
+//! the way an IDL driven client would, and encodes the result back.
+
+use {
+    bootcamp_accounts::{write_header, HEADER_LEN},
+    bootcamp_idl::Idl,
+    borsh::BorshSerialize,
+    count_tracker::state::{Leaderboard, Tracker},
+    serde_json::{json, Map, Value},
+    solana_program::{hash::hash, instruction::Instruction, pubkey::Pubkey},
+    std::str::FromStr,
+};
+
+/// Decodes a value of the IDL type `ty` from the front of `data`.
+fn decode(idl: &Value, ty: &Value, data: &mut &[u8]) -> Value {
+    let mut take = |len: usize| {
+        assert!(
+            data.len() >= len,
+            "{} bytes left, {} expected",
+            data.len(),
+            len
+        );
+        let (bytes, rest) = std::mem::take(data).split_at(len);
+        *data = rest;
+        bytes
+    };
+    if let Some(name) = ty.as_str() {
+        return match name {
+            "bool" => json!(take(1)[0] != 0),
+            "u8" => json!(take(1)[0]),
+            "u16" => json!(u16::from_le_bytes(take(2).try_into().unwrap())),
+            "u32" => json!(u32::from_le_bytes(take(4).try_into().unwrap())),
+            "u64" => json!(u64::from_le_bytes(take(8).try_into().unwrap())),
+            "i64" => json!(i64::from_le_bytes(take(8).try_into().unwrap())),
+            "publicKey" => json!(Pubkey::new(take(32)).to_string()),
+            "bytes" => {
+                let len = u32::from_le_bytes(take(4).try_into().unwrap());
+                json!(take(len as usize))
+            }
+            "string" => {
+                let len = u32::from_le_bytes(take(4).try_into().unwrap());
+                json!(String::from_utf8(take(len as usize).to_vec()).unwrap())
+            }
+            _ => panic!("Unsupported type {}", name),
+        };
+    }
+    if let Some([elem, len]) = ty["array"].as_array().map(Vec::as_slice) {
+        let len = len.as_u64().unwrap();
+        return json!((0..len)
+            .map(|_| decode(idl, elem, data))
+            .collect::<Vec<_>>());
+    }
+    if let Some(elem) = ty.get("vec") {
+        let len = u32::from_le_bytes(data[..4].try_into().unwrap());
+        *data = &data[4..];
+        return json!((0..len)
+            .map(|_| decode(idl, elem, data))
+            .collect::<Vec<_>>());
+    }
+    if let Some(inner) = ty.get("option") {
+        let tag = data[0];
+        *data = &data[1..];
+        return if tag == 0 {
+            Value::Null
+        } else {
+            decode(idl, inner, data)
+        };
+    }
+    let defined = ty["defined"].as_str().expect("Unknown IDL type");
+    decode_fields(idl, &defined_type(idl, defined)["type"]["fields"], data)
+}
+
+fn decode_fields(idl: &Value, fields: &Value, data: &mut &[u8]) -> Value {
+    let mut object = Map::new();
+    for field in fields.as_array().unwrap() {
+        let name = field["name"].as_str().unwrap().to_string();
+        object.insert(name, decode(idl, &field["type"], data));
+    }
+    Value::Object(object)
+}
+
+/// Appends the Borsh encoding of `value`, of the IDL type `ty`, to `data`.
+fn encode(idl: &Value, ty: &Value, value: &Value, data: &mut Vec<u8>) {
+    if let Some(name) = ty.as_str() {
+        match name {
+            "bool" => data.push(value.as_bool().unwrap() as u8),
+            "u8" => data.push(value.as_u64().unwrap() as u8),
+            "u16" => data.extend((value.as_u64().unwrap() as u16).to_le_bytes()),
+            "u32" => data.extend((value.as_u64().unwrap() as u32).to_le_bytes()),
+            "u64" => data.extend(value.as_u64().unwrap().to_le_bytes()),
+            "i64" => data.extend(value.as_i64().unwrap().to_le_bytes()),
+            "publicKey" => {
+                let key = Pubkey::from_str(value.as_str().unwrap()).unwrap();
+                data.extend(key.to_bytes());
+            }
+            "bytes" => {
+                let bytes = value.as_array().unwrap();
+                data.extend((bytes.len() as u32).to_le_bytes());
+                data.extend(bytes.iter().map(|byte| byte.as_u64().unwrap() as u8));
+            }
+            "string" => {
+                let string = value.as_str().unwrap();
+                data.extend((string.len() as u32).to_le_bytes());
+                data.extend(string.as_bytes());
+            }
+            _ => panic!("Unsupported type {}", name),
+        }
+        return;
+    }
+    if let Some([elem, _]) = ty["array"].as_array().map(Vec::as_slice) {
+        for value in value.as_array().unwrap() {
+            encode(idl, elem, value, data);
+        }
+        return;
+    }
+    if let Some(elem) = ty.get("vec") {
+        let values = value.as_array().unwrap();
+        data.extend((values.len() as u32).to_le_bytes());
+        for value in values {
+            encode(idl, elem, value, data);
+        }
+        return;
+    }
+    if let Some(inner) = ty.get("option") {
+        if value.is_null() {
+            data.push(0);
+        } else {
+            data.push(1);
+            encode(idl, inner, value, data);
+        }
+        return;
+    }
+    let defined = ty["defined"].as_str().expect("Unknown IDL type");
+    encode_fields(
+        idl,
+        &defined_type(idl, defined)["type"]["fields"],
+        value,
+        data,
+    );
+}
+
+fn encode_fields(idl: &Value, fields: &Value, value: &Value, data: &mut Vec<u8>) {
+    for field in fields.as_array().unwrap() {
+        encode(
+            idl,
+            &field["type"],
+            &value[field["name"].as_str().unwrap()],
+            data,
+        );
+    }
+}
+
+fn defined_type<'a>(idl: &'a Value, name: &str) -> &'a Value {
+    idl["types"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .chain(idl["accounts"].as_array().unwrap())
+        .find(|ty| ty["name"] == name)
+        .unwrap_or_else(|| panic!("Type {} is not in the IDL", name))
+}
+
+/// Checks that the IDL describes the accounts and data of `instruction`, whose
+/// arguments are `args`.
+fn check_instruction(idl: &Idl, instruction: Instruction, name: &str, args: Value) {
+    let idl = idl.to_json();
+    let idl_instruction = idl["instructions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|ix| ix["discriminant"]["value"] == instruction.data[0])
+        .unwrap_or_else(|| panic!("No instruction with discriminant {}", instruction.data[0]));
+    assert_eq!(idl_instruction["name"], name);
+
+    let accounts = idl_instruction["accounts"].as_array().unwrap();
+    assert_eq!(accounts.len(), instruction.accounts.len(), "{}", name);
+    for (account, meta) in accounts.iter().zip(&instruction.accounts) {
+        assert_eq!(account["isMut"], meta.is_writable, "{}", account);
+        assert_eq!(account["isSigner"], meta.is_signer, "{}", account);
+    }
+
+    let mut data = &instruction.data[1..];
+    let decoded = decode_fields(&idl, &idl_instruction["args"], &mut data);
+    assert!(data.is_empty(), "{} has trailing bytes", name);
+    assert_eq!(decoded, args);
+
+    let mut encoded = vec![instruction.data[0]];
+    encode_fields(&idl, &idl_instruction["args"], &args, &mut encoded);
+    assert_eq!(encoded, instruction.data);
+}
+
+/// Checks that the IDL decodes `data` of an account to `fields` and back.
+fn check_account(idl: &Idl, name: &str, data: &[u8], fields: Value) {
+    let idl = idl.to_json();
+    let account = defined_type(&idl, name);
+    let discriminator = &hash(format!("account:{}", name).as_bytes()).to_bytes()[..8];
+    assert_eq!(account["discriminator"], json!(discriminator));
+    assert_eq!(&data[..8], discriminator);
+
+    let mut body = &data[8..];
+    let decoded = decode_fields(&idl, &account["type"]["fields"], &mut body);
+    assert!(body.is_empty(), "{} has trailing bytes", name);
+    assert_eq!(decoded, fields);
+
+    let mut encoded = data[..8].to_vec();
+    encode_fields(&idl, &account["type"]["fields"], &fields, &mut encoded);
+    assert_eq!(encoded, data);
+}
+
+#[test]
+fn test_counter_instructions() {
+    use counter::instruction::{decrement, increment, set_rate_limit};
+    let idl = counter::idl();
+    let (program_id, counter) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let ix = increment(program_id, counter).unwrap();
+    check_instruction(&idl, ix, "increment", json!({}));
+    let ix = decrement(program_id, counter).unwrap();
+    check_instruction(&idl, ix, "decrement", json!({}));
+    let ix = set_rate_limit(program_id, counter, Pubkey::new_unique(), 3, 60).unwrap();
+    let args = json!({ "maxIncrements": 3, "window": 60 });
+    check_instruction(&idl, ix, "setRateLimit", args);
+}
+
+#[test]
+fn test_authorized_counter_instructions() {
+    use authorized_counter::instruction::{
+        aggregate, get, increment, increment_shard, initialize_shard, migrate,
+        CounterInstruction,
+    };
+    let idl = authorized_counter::idl();
+    assert_eq!(
+        idl.to_json()["metadata"]["address"],
+        authorized_counter::id().to_string()
+    );
+    let ix = increment(
+        authorized_counter::id(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        CounterInstruction::Increment,
+    )
+    .unwrap();
+    check_instruction(&idl, ix, "increment", json!({}));
+    let ix = migrate(authorized_counter::id(), Pubkey::new_unique(), Pubkey::new_unique()).unwrap();
+    check_instruction(&idl, ix, "migrate", json!({}));
+    let [shard, counter, authority, payer] = [(); 4].map(|_| Pubkey::new_unique());
+    let ix = initialize_shard(authorized_counter::id(), shard, counter, authority, payer, 7)
+        .unwrap();
+    check_instruction(&idl, ix, "initializeShard", json!({ "index": 7 }));
+    let ix = increment_shard(authorized_counter::id(), shard, authority).unwrap();
+    check_instruction(&idl, ix, "incrementShard", json!({}));
+    let ix = aggregate(authorized_counter::id(), counter, &[]).unwrap();
+    check_instruction(&idl, ix, "aggregate", json!({}));
+    let ix = get(authorized_counter::id(), counter).unwrap();
+    check_instruction(&idl, ix, "get", json!({}));
+}
+
+#[test]
+fn test_count_tracker_instructions() {
+    use count_tracker::instruction::{
+        increment, increment_shard, initialize, initialize_leaderboard, initialize_shard, migrate,
+    };
+    let idl = count_tracker::idl();
+    let program_id = count_tracker::id();
+    let [tracker, user, authority, counter, leaderboard] = [(); 5].map(|_| Pubkey::new_unique());
+    let ix = initialize(program_id, tracker, user, authority, counter).unwrap();
+    check_instruction(&idl, ix, "initialize", json!({}));
+    let ix = increment(program_id, tracker, user, counter, authority, leaderboard).unwrap();
+    check_instruction(&idl, ix, "increment", json!({}));
+    let ix = initialize_leaderboard(program_id, leaderboard, user, counter).unwrap();
+    check_instruction(&idl, ix, "initializeLeaderboard", json!({}));
+    let ix = migrate(program_id, tracker, user, counter).unwrap();
+    check_instruction(&idl, ix, "migrate", json!({}));
+    let ix = initialize_shard(program_id, user, counter, authority, 2).unwrap();
+    check_instruction(&idl, ix, "initializeShard", json!({ "index": 2 }));
+    let ix = increment_shard(program_id, tracker, user, counter, authority, leaderboard, 2).unwrap();
+    check_instruction(&idl, ix, "incrementShard", json!({ "index": 2 }));
+}
+
+#[test]
+fn test_token_instructions() {
+    use token::instruction::{
+        burn, initialize_mint, initialize_token_account, migrate, mint, transfer,
+    };
+    let idl = token::idl();
+    let program_id = Pubkey::new_unique();
+    let [mint_key, token_account, dst, owner] = [(); 4].map(|_| Pubkey::new_unique());
+    let ix = initialize_mint(program_id, mint_key, owner).unwrap();
+    check_instruction(&idl, ix, "initializeMint", json!({}));
+    let ix = initialize_token_account(program_id, token_account, mint_key, owner).unwrap();
+    check_instruction(&idl, ix, "initializeTokenAccount", json!({}));
+    let ix = mint(program_id, token_account, mint_key, owner, 100).unwrap();
+    check_instruction(&idl, ix, "mint", json!({ "amount": 100 }));
+    let ix = burn(program_id, token_account, mint_key, owner, u64::MAX).unwrap();
+    check_instruction(&idl, ix, "burn", json!({ "amount": u64::MAX }));
+    let ix = transfer(program_id, token_account, dst, owner, 7).unwrap();
+    check_instruction(&idl, ix, "transfer", json!({ "amount": 7 }));
+    let ix = migrate(program_id, token_account, owner).unwrap();
+    check_instruction(&idl, ix, "migrate", json!({}));
+}
+
+#[test]
+fn test_echo_instructions() {
+    use echo::instruction::{
+        authorized_echo, echo, initialize_authorized_echo, initialize_vending_machine_echo,
+        vending_machine_echo,
+    };
+    let idl = echo::idl();
+    let program_id = Pubkey::new_unique();
+    let [buffer, authority, mint, token_account] = [(); 4].map(|_| Pubkey::new_unique());
+    let ix = echo(program_id, buffer, vec![1, 2, 3]).unwrap();
+    check_instruction(&idl, ix, "echo", json!({ "data": [1, 2, 3] }));
+    let ix = initialize_authorized_echo(program_id, buffer, authority, 5, 100).unwrap();
+    let args = json!({ "bufferSeed": 5, "bufferSize": 100 });
+    check_instruction(&idl, ix, "initializeAuthorizedEcho", args);
+    let ix = authorized_echo(program_id, buffer, authority, vec![]).unwrap();
+    check_instruction(&idl, ix, "authorizedEcho", json!({ "data": [] }));
+    let ix = initialize_vending_machine_echo(program_id, buffer, mint, authority, 3, 64).unwrap();
+    let args = json!({ "price": 3, "bufferSize": 64 });
+    check_instruction(&idl, ix, "initializeVendingMachineEcho", args);
+    let ix =
+        vending_machine_echo(program_id, buffer, authority, token_account, mint, vec![9]).unwrap();
+    check_instruction(&idl, ix, "vendingMachineEcho", json!({ "data": [9] }));
+}
+
+#[test]
+fn test_count_tracker_accounts() {
+    let idl = count_tracker::idl();
+    let [counter, user] = [(); 2].map(|_| Pubkey::new_unique());
+    let padding = [0; 7];
+
+    let tracker = Tracker {
+        bump: 254,
+        auth_bump: 253,
+        user,
+        counter,
+        count: 42,
+        global_count: 99,
+    };
+    let mut data = vec![0; Tracker::LEN];
+    write_header::<Tracker>(&mut data).unwrap();
+    tracker.serialize(&mut &mut data[HEADER_LEN..]).unwrap();
+    let fields = json!({
+        "version": 3,
+        "padding": padding,
+        "bump": 254,
+        "authBump": 253,
+        "user": user.to_string(),
+        "counter": counter.to_string(),
+        "count": 42,
+        "globalCount": 99,
+    });
+    check_account(&idl, "Tracker", &data, fields);
+
+    let mut leaderboard = Leaderboard::new(255, counter);
+    leaderboard.record(user, 3);
+    let mut data = vec![0; Leaderboard::LEN];
+    write_header::<Leaderboard>(&mut data).unwrap();
+    leaderboard.serialize(&mut &mut data[HEADER_LEN..]).unwrap();
+    let empty = json!({ "user": Pubkey::default().to_string(), "count": 0 });
+    let mut entries = vec![empty; count_tracker::state::LEADERBOARD_SIZE];
+    entries[0] = json!({ "user": user.to_string(), "count": 3 });
+    let fields = json!({
+        "version": 1,
+        "padding": padding,
+        "bump": 255,
+        "counter": counter.to_string(),
+        "len": 1,
+        "entries": entries,
+    });
+    check_account(&idl, "Leaderboard", &data, fields);
+}
@@ -0,0 +1,33 @@
+//! Writes the IDL of every native program to `target/idl/<program>.json`, e.g. for
+//! Anchor clients: `cargo test -p bootcamp_idl --test emit`.
+
+use {
+    bootcamp_idl::Idl,
+    std::{env, fs, path::PathBuf},
+};
+
+fn idl_dir() -> PathBuf {
+    let target = env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../target"));
+    target.join("idl")
+}
+
+#[test]
+fn emit_idls() {
+    let idls: [(&str, Idl); 5] = [
+        ("counter", counter::idl()),
+        ("authorized_counter", authorized_counter::idl()),
+        ("count_tracker", count_tracker::idl()),
+        ("token", token::idl()),
+        ("echo", echo::idl()),
+    ];
+    let dir = idl_dir();
+    fs::create_dir_all(&dir).unwrap();
+    for (name, idl) in idls {
+        let json = idl.to_json();
+        assert_eq!(json["name"], name);
+        let path = dir.join(format!("{}.json", name));
+        fs::write(&path, serde_json::to_string_pretty(&json).unwrap()).unwrap();
+    }
+}
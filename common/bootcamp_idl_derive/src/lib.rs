@@ -0,0 +1,308 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    parse_macro_input, Attribute, Data, DeriveInput, Error, Fields, GenericArgument, Lit, Meta,
+    NestedMeta, PathArguments, Type,
+};
+
+/// Implements `bootcamp_idl::IdlInstruction` for an instruction enum. The accounts of
+/// each variant are listed in order with
+/// `#[account(<index>, writable, signer, name = "...", desc = "...")]`.
+///
+/// The impl is only compiled with the `idl` feature of the deriving crate, so programs
+/// keep their account tables without linking `bootcamp_idl` on-chain.
+#[proc_macro_derive(IdlInstruction, attributes(account))]
+pub fn derive_idl_instruction(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match idl_instruction_impl(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// Implements `bootcamp_idl::IdlType` for a Borsh encoded struct or enum. Like
+/// `IdlInstruction`, only with the `idl` feature of the deriving crate.
+#[proc_macro_derive(IdlType)]
+pub fn derive_idl_type(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match idl_type_impl(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn idl_instruction_impl(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let data = match &input.data {
+        Data::Enum(data) => data,
+        _ => {
+            return Err(Error::new_spanned(
+                input,
+                "IdlInstruction only supports enums",
+            ))
+        }
+    };
+    check_generics(input)?;
+
+    let mut instructions = vec![];
+    // Borsh 0.9 encodes the variant index as a u8, whatever the explicit discriminants
+    for (discriminant, variant) in data.variants.iter().enumerate() {
+        let discriminant = discriminant as u8;
+        let instruction_name = camel_case(&variant.ident.to_string());
+        let docs = docs(&variant.attrs);
+        let accounts = accounts(&variant.attrs)?;
+        let args = match &variant.fields {
+            Fields::Named(fields) => fields
+                .named
+                .iter()
+                .map(|field| {
+                    let arg_name = camel_case(&field.ident.as_ref().unwrap().to_string());
+                    let ty = type_json(&field.ty)?;
+                    Ok(quote! { json!({"name": #arg_name, "type": #ty}) })
+                })
+                .collect::<syn::Result<Vec<_>>>()?,
+            Fields::Unit => vec![],
+            Fields::Unnamed(fields) => {
+                return Err(Error::new_spanned(
+                    fields,
+                    "instruction arguments need names",
+                ))
+            }
+        };
+        instructions.push(quote! {
+            with_docs(
+                json!({
+                    "name": #instruction_name,
+                    "accounts": [#(#accounts),*],
+                    "args": [#(#args),*],
+                    "discriminant": {"type": "u8", "value": #discriminant},
+                }),
+                &[#(#docs),*],
+            )
+        });
+    }
+
+    Ok(quote! {
+        #[cfg(feature = "idl")]
+        impl ::bootcamp_idl::IdlInstruction for #name {
+            fn idl_instructions() -> ::std::vec::Vec<::bootcamp_idl::__private::Value> {
+                use ::bootcamp_idl::__private::{json, with_docs};
+                vec![#(#instructions),*]
+            }
+        }
+    })
+}
+
+/// Reads the `#[account(...)]` attributes of a variant, which must be in index order.
+fn accounts(attrs: &[Attribute]) -> syn::Result<Vec<TokenStream2>> {
+    let mut accounts = vec![];
+    for attr in attrs.iter().filter(|a| a.path.is_ident("account")) {
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            meta => return Err(Error::new_spanned(meta, "expected #[account(...)]")),
+        };
+        let (mut index, mut name, mut desc) = (None, None, None);
+        let (mut writable, mut signer) = (false, false);
+        for nested in &list.nested {
+            match nested {
+                NestedMeta::Lit(Lit::Int(i)) => index = Some(i.base10_parse::<usize>()?),
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("writable") => writable = true,
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("signer") => signer = true,
+                NestedMeta::Meta(Meta::NameValue(pair)) => match (&pair.lit, pair.path.get_ident())
+                {
+                    (Lit::Str(s), Some(key)) if key == "name" => name = Some(s.value()),
+                    (Lit::Str(s), Some(key)) if key == "desc" => desc = Some(s.value()),
+                    _ => return Err(Error::new_spanned(pair, "unknown account attribute")),
+                },
+                other => return Err(Error::new_spanned(other, "unknown account attribute")),
+            }
+        }
+        if index != Some(accounts.len()) {
+            let msg = format!("expected the index of account {} first", accounts.len());
+            return Err(Error::new_spanned(list, msg));
+        }
+        let name = match name {
+            Some(name) => camel_case(&name),
+            None => return Err(Error::new_spanned(list, "missing name = \"...\"")),
+        };
+        let desc = desc.into_iter();
+        accounts.push(quote! {
+            with_docs(
+                json!({"name": #name, "isMut": #writable, "isSigner": #signer}),
+                &[#(#desc),*],
+            )
+        });
+    }
+    Ok(accounts)
+}
+
+fn idl_type_impl(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let type_name = name.to_string();
+    check_generics(input)?;
+    let ty = match &input.data {
+        Data::Struct(data) => {
+            let fields = fields_json(&data.fields)?;
+            quote! { json!({"kind": "struct", "fields": #fields}) }
+        }
+        Data::Enum(data) => {
+            let variants = data
+                .variants
+                .iter()
+                .map(|variant| {
+                    let variant_name = variant.ident.to_string();
+                    Ok(match &variant.fields {
+                        Fields::Unit => quote! { json!({"name": #variant_name}) },
+                        fields => {
+                            let fields = fields_json(fields)?;
+                            quote! { json!({"name": #variant_name, "fields": #fields}) }
+                        }
+                    })
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+            quote! { json!({"kind": "enum", "variants": [#(#variants),*]}) }
+        }
+        Data::Union(_) => return Err(Error::new_spanned(input, "unions are not Borsh types")),
+    };
+    let docs = docs(&input.attrs);
+
+    Ok(quote! {
+        #[cfg(feature = "idl")]
+        impl ::bootcamp_idl::IdlType for #name {
+            fn idl_type() -> ::bootcamp_idl::__private::Value {
+                use ::bootcamp_idl::__private::{json, with_docs};
+                with_docs(json!({"name": #type_name, "type": #ty}), &[#(#docs),*])
+            }
+        }
+    })
+}
+
+/// Named fields are listed with their names, tuple fields only by type.
+fn fields_json(fields: &Fields) -> syn::Result<TokenStream2> {
+    let fields = fields
+        .iter()
+        .map(|field| {
+            let ty = type_json(&field.ty)?;
+            Ok(match &field.ident {
+                Some(ident) => {
+                    let field_name = camel_case(&ident.to_string());
+                    let docs = docs(&field.attrs);
+                    quote! {
+                        with_docs(json!({"name": #field_name, "type": #ty}), &[#(#docs),*])
+                    }
+                }
+                None => ty,
+            })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+    Ok(quote! { json!([#(#fields),*]) })
+}
+
+/// Expression building the IDL type of `ty`. Lengths of arrays are evaluated by the
+/// generated code, so they can be constants.
+fn type_json(ty: &Type) -> syn::Result<TokenStream2> {
+    match ty {
+        Type::Array(array) => {
+            let elem = type_json(&array.elem)?;
+            let len = &array.len;
+            Ok(quote! { json!({"array": [#elem, (#len)]}) })
+        }
+        Type::Path(path) if path.qself.is_none() => {
+            let segment = path.path.segments.last().unwrap();
+            let ident = segment.ident.to_string();
+            let name = match ident.as_str() {
+                "bool" | "u8" | "u16" | "u32" | "u64" | "u128" | "i8" | "i16" | "i32" | "i64"
+                | "i128" => ident.as_str(),
+                // Borsh encodes pointer sized integers as 64 bits
+                "usize" => "u64",
+                "isize" => "i64",
+                "String" => "string",
+                "Pubkey" => "publicKey",
+                "Vec" | "Option" | "Box" => {
+                    let inner = generic_argument(segment, ty)?;
+                    return Ok(match ident.as_str() {
+                        "Vec" if is_u8(inner) => quote! { json!("bytes") },
+                        "Vec" => {
+                            let inner = type_json(inner)?;
+                            quote! { json!({"vec": #inner}) }
+                        }
+                        "Option" => {
+                            let inner = type_json(inner)?;
+                            quote! { json!({"option": #inner}) }
+                        }
+                        _ => type_json(inner)?,
+                    });
+                }
+                _ if segment.arguments.is_empty() => {
+                    return Ok(quote! { json!({"defined": #ident}) });
+                }
+                _ => return Err(Error::new_spanned(ty, "generic types are not supported")),
+            };
+            Ok(quote! { json!(#name) })
+        }
+        _ => Err(Error::new_spanned(ty, "type has no IDL equivalent")),
+    }
+}
+
+fn generic_argument<'a>(segment: &'a syn::PathSegment, ty: &Type) -> syn::Result<&'a Type> {
+    if let PathArguments::AngleBracketed(args) = &segment.arguments {
+        if let Some(GenericArgument::Type(inner)) = args.args.first() {
+            return Ok(inner);
+        }
+    }
+    Err(Error::new_spanned(ty, "expected a type argument"))
+}
+
+fn is_u8(ty: &Type) -> bool {
+    matches!(ty, Type::Path(path) if path.path.is_ident("u8"))
+}
+
+fn check_generics(input: &DeriveInput) -> syn::Result<()> {
+    if input.generics.params.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::new_spanned(
+            &input.generics,
+            "generic types are not supported",
+        ))
+    }
+}
+
+/// Lines of the doc comments in `attrs`.
+fn docs(attrs: &[Attribute]) -> Vec<String> {
+    attrs
+        .iter()
+        .filter(|a| a.path.is_ident("doc"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::NameValue(pair)) => match pair.lit {
+                Lit::Str(s) => Some(s.value().trim().to_string()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+/// Anchor IDLs name instructions, accounts and fields in camelCase.
+fn camel_case(name: &str) -> String {
+    let mut words = name.split('_').filter(|word| !word.is_empty());
+    let mut camel: String = match words.next() {
+        Some(first) => {
+            let mut chars = first.chars();
+            chars
+                .next()
+                .into_iter()
+                .flat_map(char::to_lowercase)
+                .chain(chars)
+                .collect()
+        }
+        None => return name.to_string(),
+    };
+    for word in words {
+        let mut chars = word.chars();
+        camel.extend(chars.next().into_iter().flat_map(char::to_uppercase));
+        camel.extend(chars);
+    }
+    camel
+}
@@ -0,0 +1,122 @@
+//! Maps the `ProgramError::Custom` codes returned by the programs back to their error
+//! variants and messages.
+
+use num_traits::FromPrimitive;
+use solana_program::{decode_error::DecodeError, program_error::ProgramError, pubkey::Pubkey};
+
+use std::{fmt, str::FromStr};
+
+use crate::Programs;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Program {
+    Counter,
+    AuthorizedCounter,
+    CountTracker,
+    Token,
+    Echo,
+    Marketplace,
+    Uploader,
+}
+
+impl FromStr for Program {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "counter" => Ok(Program::Counter),
+            "authorized_counter" => Ok(Program::AuthorizedCounter),
+            "count_tracker" => Ok(Program::CountTracker),
+            "token" => Ok(Program::Token),
+            "echo" => Ok(Program::Echo),
+            "marketplace" => Ok(Program::Marketplace),
+            "uploader" => Ok(Program::Uploader),
+            _ => Err(format!("Unknown program {}", s)),
+        }
+    }
+}
+
+impl Programs {
+    /// The program deployed at `program_id`, if it is one of ours.
+    pub fn program(&self, program_id: &Pubkey) -> Option<Program> {
+        let id = Some(*program_id);
+        if *program_id == self.authorized_counter {
+            Some(Program::AuthorizedCounter)
+        } else if *program_id == self.count_tracker {
+            Some(Program::CountTracker)
+        } else if id == self.counter {
+            Some(Program::Counter)
+        } else if id == self.token {
+            Some(Program::Token)
+        } else if id == self.echo {
+            Some(Program::Echo)
+        } else if id == self.marketplace {
+            Some(Program::Marketplace)
+        } else if id == self.uploader {
+            Some(Program::Uploader)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedError {
+    /// Name of the error enum, e.g. `TokenError`
+    pub error_type: &'static str,
+    pub variant: String,
+    /// The `#[error]` message of the variant
+    pub message: String,
+}
+
+impl fmt::Display for DecodedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}::{}: {}", self.error_type, self.variant, self.message)
+    }
+}
+
+fn decode<E>(code: u32) -> Option<DecodedError>
+where
+    E: DecodeError<E> + FromPrimitive + fmt::Debug + fmt::Display,
+{
+    let error = E::decode_custom_error_to_enum(code)?;
+    Some(DecodedError {
+        error_type: E::type_of(),
+        variant: format!("{:?}", error),
+        message: error.to_string(),
+    })
+}
+
+/// Looks up custom error `code` of `program`.
+pub fn decode_custom_error(program: Program, code: u32) -> Option<DecodedError> {
+    match program {
+        Program::Counter => decode::<counter::error::CounterError>(code),
+        Program::AuthorizedCounter => decode::<authorized_counter::error::CounterError>(code),
+        Program::CountTracker => decode::<count_tracker::error::CounterError>(code),
+        Program::Token => decode::<token::error::TokenError>(code),
+        Program::Echo => decode::<echo::error::EchoError>(code),
+        Program::Marketplace => decode::<marketplace::error::RuntimeLimitationError>(code),
+        Program::Uploader => decode::<uploader::error::UploaderError>(code),
+    }
+}
+
+/// Decodes `error` if it is a custom error of the program deployed at `program_id`.
+pub fn decode_program_error(
+    program_id: &Pubkey,
+    error: &ProgramError,
+    programs: &Programs,
+) -> Option<DecodedError> {
+    match error {
+        ProgramError::Custom(code) => decode_custom_error(programs.program(program_id)?, *code),
+        _ => None,
+    }
+}
+
+/// Decodes a `Program <id> failed: custom program error: 0x<code>` transaction log line.
+pub fn decode_log(line: &str, programs: &Programs) -> Option<DecodedError> {
+    let rest = line.strip_prefix("Program ")?;
+    let (program_id, rest) = rest.split_once(' ')?;
+    let code = rest.strip_prefix("failed: custom program error: 0x")?;
+    let program = programs.program(&Pubkey::from_str(program_id).ok()?)?;
+    decode_custom_error(program, u32::from_str_radix(code.trim(), 16).ok()?)
+}
@@ -0,0 +1,725 @@
+//! Detects which program and type an account belongs to and decodes it with the
+//! program crates' own state definitions.
+//!
+//! Accounts with an `AccountHeader` are recognised by their discriminator alone. The
+//! lecture_1 counter, plain echo buffers and accounts created before headers were added
+//! have no header, so they are recognised by their owner. So are authorized counters,
+//! which may or may not have one.
+
+use authorized_counter::state::Shard;
+use bootcamp_accounts::{BorshAccount, Discriminator, ZeroCopy, HEADER_LEN};
+use borsh::BorshDeserialize;
+use count_tracker::state::{Leaderboard, LegacyTracker, Tracker};
+use echo::state::{AuthorizedBufferHeader, VendingMachineBufferHeader};
+use marketplace::{
+    state::{Marketplace, Offer},
+    vec_account::VecHeader,
+};
+use serde_json::{json, Value};
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+use thiserror::Error;
+use token::state::{LegacyTokenAccount, Mint, TokenAccount};
+use uploader::state::{bitmap_len, is_chunk_written, BufferHeader};
+
+use std::mem;
+
+pub mod errors;
+
+/// Length of the Borsh encoded echo buffer headers that follow the account header
+const ECHO_HEADER_LEN: usize = 1 + 8;
+
+#[derive(Error, Debug)]
+pub enum InspectError {
+    #[error("Account owned by {0} does not match any known account type")]
+    Unknown(Pubkey),
+    #[error("Failed to decode {0}: {1}")]
+    Decode(&'static str, String),
+}
+
+/// Ids of the deployed programs. Only programs with a declared id have a default, the
+/// others are needed to recognise accounts without a header and to decode errors.
+#[derive(Debug, Clone)]
+pub struct Programs {
+    pub counter: Option<Pubkey>,
+    pub authorized_counter: Pubkey,
+    pub count_tracker: Pubkey,
+    pub token: Option<Pubkey>,
+    pub echo: Option<Pubkey>,
+    pub marketplace: Option<Pubkey>,
+    pub uploader: Option<Pubkey>,
+}
+
+impl Default for Programs {
+    fn default() -> Self {
+        Self {
+            counter: None,
+            authorized_counter: authorized_counter::id(),
+            count_tracker: count_tracker::id(),
+            token: None,
+            echo: None,
+            marketplace: None,
+            uploader: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Account {
+    Counter {
+        counter: counter::state::Counter,
+        rate_limit: Option<counter::state::RateLimit>,
+    },
+    AuthorizedCounter(authorized_counter::state::Counter),
+    Shard(Shard),
+    Tracker(Tracker),
+    Leaderboard(Box<Leaderboard>),
+    Mint(Mint),
+    TokenAccount(TokenAccount),
+    Marketplace(Box<Marketplace>),
+    OfferVec {
+        header: VecHeader,
+        offers: Vec<Offer>,
+    },
+    EchoBuffer(Vec<u8>),
+    AuthorizedEchoBuffer {
+        header: AuthorizedBufferHeader,
+        data: Vec<u8>,
+    },
+    VendingMachineEchoBuffer {
+        header: VendingMachineBufferHeader,
+        data: Vec<u8>,
+    },
+    UploadBuffer {
+        header: BufferHeader,
+        chunks_written: usize,
+    },
+}
+
+fn decode<T>(type_name: &'static str, result: Result<T, ProgramError>) -> Result<T, InspectError> {
+    result.map_err(|e| InspectError::Decode(type_name, e.to_string()))
+}
+
+fn has_discriminator<T: Discriminator>(data: &[u8]) -> bool {
+    data.get(..T::DISCRIMINATOR.len()) == Some(&T::DISCRIMINATOR[..])
+}
+
+fn echo_contents(data: &[u8]) -> Value {
+    json!({
+        "len": data.len(),
+        "text": String::from_utf8_lossy(data).trim_end_matches('\0'),
+    })
+}
+
+fn offer_json(offer: &Offer) -> Value {
+    json!({
+        "seller": offer.seller.to_string(),
+        "offer_mint": offer.offer_mint.to_string(),
+        "buyer_mint": offer.buyer_mint.to_string(),
+        "offer_amount": offer.offer_amount,
+        "buyer_amount": offer.buyer_amount,
+    })
+}
+
+pub fn inspect(owner: &Pubkey, data: &[u8], programs: &Programs) -> Result<Account, InspectError> {
+    if has_discriminator::<Shard>(data) {
+        return Ok(Account::Shard(decode("Shard", Shard::unpack(data))?));
+    }
+    if has_discriminator::<Tracker>(data) {
+        return Ok(Account::Tracker(decode("Tracker", Tracker::unpack(data))?));
+    }
+    if has_discriminator::<Leaderboard>(data) {
+        let leaderboard = decode("Leaderboard", Leaderboard::unpack(data))?;
+        return Ok(Account::Leaderboard(Box::new(leaderboard)));
+    }
+    if has_discriminator::<Mint>(data) {
+        return Ok(Account::Mint(decode("Mint", Mint::unpack(data))?));
+    }
+    if has_discriminator::<TokenAccount>(data) {
+        return Ok(Account::TokenAccount(decode(
+            "TokenAccount",
+            TokenAccount::unpack(data),
+        )?));
+    }
+    if has_discriminator::<Marketplace>(data) {
+        let marketplace = decode("Marketplace", Marketplace::read(data))?;
+        return Ok(Account::Marketplace(Box::new(marketplace)));
+    }
+    if has_discriminator::<VecHeader>(data) {
+        let header = decode("VecAccount", VecHeader::read(data))?;
+        let offers = data[VecHeader::LEN..]
+            .chunks_exact(mem::size_of::<Offer>())
+            .take(header.len as usize)
+            .map(|mut offer| Offer::deserialize(&mut offer))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| InspectError::Decode("VecAccount", e.to_string()))?;
+        return Ok(Account::OfferVec { header, offers });
+    }
+    if has_discriminator::<AuthorizedBufferHeader>(data) {
+        let header = decode("AuthorizedBufferHeader", AuthorizedBufferHeader::unpack(data))?;
+        let data = data[HEADER_LEN + ECHO_HEADER_LEN..].to_vec();
+        return Ok(Account::AuthorizedEchoBuffer { header, data });
+    }
+    if has_discriminator::<VendingMachineBufferHeader>(data) {
+        let header = decode(
+            "VendingMachineBufferHeader",
+            VendingMachineBufferHeader::unpack(data),
+        )?;
+        let data = data[HEADER_LEN + ECHO_HEADER_LEN..].to_vec();
+        return Ok(Account::VendingMachineEchoBuffer { header, data });
+    }
+    if has_discriminator::<BufferHeader>(data) {
+        let header = decode("BufferHeader", BufferHeader::read(data))?;
+        let data_len = header.data_len as usize;
+        let bitmap = data
+            .get(BufferHeader::LEN..BufferHeader::LEN + bitmap_len(data_len))
+            .ok_or(InspectError::Decode("BufferHeader", "bitmap is truncated".into()))?;
+        let chunks_written = (0..header.chunk_count as usize)
+            .filter(|chunk| is_chunk_written(bitmap, *chunk))
+            .count();
+        return Ok(Account::UploadBuffer {
+            header,
+            chunks_written,
+        });
+    }
+
+    if *owner == programs.authorized_counter {
+        let counter = authorized_counter::state::Counter::unpack_any(data)
+            .map_err(|e| InspectError::Decode("authorized Counter", e.to_string()))?;
+        return Ok(Account::AuthorizedCounter(counter));
+    }
+    // Trackers created before account headers
+    if *owner == programs.count_tracker && data.len() == LegacyTracker::LEN {
+        return Ok(Account::Tracker(decode("Tracker", Tracker::unpack_any(data))?));
+    }
+    // Token accounts created before account headers
+    if Some(*owner) == programs.token && data.len() == LegacyTokenAccount::LEN {
+        let token_account = decode("TokenAccount", TokenAccount::unpack_any(data))?;
+        return Ok(Account::TokenAccount(token_account));
+    }
+    if Some(*owner) == programs.counter {
+        let (counter, rate_limit) = counter::state::Counter::unpack(data)
+            .map_err(|e| InspectError::Decode("Counter", e.to_string()))?;
+        return Ok(Account::Counter {
+            counter,
+            rate_limit,
+        });
+    }
+    if Some(*owner) == programs.echo {
+        return Ok(Account::EchoBuffer(data.to_vec()));
+    }
+    Err(InspectError::Unknown(*owner))
+}
+
+impl Account {
+    pub fn program(&self) -> &'static str {
+        match self {
+            Account::Counter { .. } => "counter",
+            Account::AuthorizedCounter(_) | Account::Shard(_) => "authorized_counter",
+            Account::Tracker(_) | Account::Leaderboard(_) => "count_tracker",
+            Account::Mint(_) | Account::TokenAccount(_) => "token",
+            Account::Marketplace(_) | Account::OfferVec { .. } => "marketplace",
+            Account::EchoBuffer(_)
+            | Account::AuthorizedEchoBuffer { .. }
+            | Account::VendingMachineEchoBuffer { .. } => "echo",
+            Account::UploadBuffer { .. } => "uploader",
+        }
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Account::Counter { .. } | Account::AuthorizedCounter(_) => "Counter",
+            Account::Shard(_) => "Shard",
+            Account::Tracker(_) => "Tracker",
+            Account::Leaderboard(_) => "Leaderboard",
+            Account::Mint(_) => "Mint",
+            Account::TokenAccount(_) => "TokenAccount",
+            Account::Marketplace(_) => "Marketplace",
+            Account::OfferVec { .. } => "VecAccount<Offer>",
+            Account::EchoBuffer(_) => "EchoBuffer",
+            Account::AuthorizedEchoBuffer { .. } => "AuthorizedBuffer",
+            Account::VendingMachineEchoBuffer { .. } => "VendingMachineBuffer",
+            Account::UploadBuffer { .. } => "UploadBuffer",
+        }
+    }
+
+    fn fields(&self) -> Value {
+        match self {
+            Account::Counter {
+                counter,
+                rate_limit: None,
+            } => json!({ "count": counter.count }),
+            Account::Counter {
+                counter,
+                rate_limit: Some(rate_limit),
+            } => json!({
+                "count": counter.count,
+                "rate_limit": {
+                    "authority": rate_limit.authority.to_string(),
+                    "max_increments": rate_limit.max_increments,
+                    "window": rate_limit.window,
+                    "timestamps": &rate_limit.timestamps[..rate_limit.len as usize],
+                },
+            }),
+            Account::AuthorizedCounter(counter) => json!({
+                "authority": counter.authority.to_string(),
+                "count": counter.count,
+            }),
+            Account::Shard(shard) => json!({
+                "bump": shard.bump,
+                "index": shard.index,
+                "counter": shard.counter.to_string(),
+                "authority": shard.authority.to_string(),
+                "count": shard.count,
+            }),
+            Account::Tracker(tracker) => json!({
+                "bump": tracker.bump,
+                "auth_bump": tracker.auth_bump,
+                "user": tracker.user.to_string(),
+                "counter": tracker.counter.to_string(),
+                "count": tracker.count,
+                "global_count": tracker.global_count,
+            }),
+            Account::Leaderboard(leaderboard) => json!({
+                "bump": leaderboard.bump,
+                "counter": leaderboard.counter.to_string(),
+                "entries": leaderboard
+                    .ranked()
+                    .iter()
+                    .map(|entry| json!({ "user": entry.user.to_string(), "count": entry.count }))
+                    .collect::<Vec<_>>(),
+            }),
+            Account::Mint(mint) => json!({
+                "authority": mint.authority.to_string(),
+                "supply": mint.supply,
+            }),
+            Account::TokenAccount(token_account) => json!({
+                "owner": token_account.owner.to_string(),
+                "mint": token_account.mint.to_string(),
+                "amount": token_account.amount,
+            }),
+            Account::Marketplace(marketplace) => json!({
+                "user": marketplace.user.to_string(),
+                "offers": (0..marketplace.offers.len())
+                    .filter(|slot| marketplace.is_occupied(*slot))
+                    .map(|slot| {
+                        let mut offer = offer_json(&marketplace.offers[slot]);
+                        offer["slot"] = json!(slot);
+                        offer
+                    })
+                    .collect::<Vec<_>>(),
+            }),
+            Account::OfferVec { header, offers } => json!({
+                "authority": header.authority.to_string(),
+                "len": header.len,
+                "capacity": header.capacity,
+                "offers": offers.iter().map(offer_json).collect::<Vec<_>>(),
+            }),
+            Account::EchoBuffer(data) => echo_contents(data),
+            Account::AuthorizedEchoBuffer { header, data } => json!({
+                "bump_seed": header.bump_seed,
+                "buffer_seed": header.buffer_seed,
+                "data": echo_contents(data),
+            }),
+            Account::VendingMachineEchoBuffer { header, data } => json!({
+                "bump_seed": header.bump_seed,
+                "price": header.price,
+                "data": echo_contents(data),
+            }),
+            Account::UploadBuffer {
+                header,
+                chunks_written,
+            } => json!({
+                "authority": header.authority.to_string(),
+                "data_len": header.data_len,
+                "chunk_count": header.chunk_count,
+                "chunks_written": chunks_written,
+                "finalized": header.finalized != 0,
+            }),
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "program": self.program(),
+            "type": self.type_name(),
+            "data": self.fields(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use authorized_counter::state::LegacyCounter;
+    use bootcamp_accounts::write_header;
+    use borsh::BorshSerialize;
+    use bytemuck::bytes_of;
+
+    fn programs() -> Programs {
+        Programs {
+            counter: Some(Pubkey::new_unique()),
+            token: Some(Pubkey::new_unique()),
+            echo: Some(Pubkey::new_unique()),
+            ..Programs::default()
+        }
+    }
+
+    /// Data of an account of `len` bytes that `init` wrote `state` to.
+    fn borsh_account<T: BorshAccount>(state: &T, len: usize) -> Vec<u8> {
+        let mut data = vec![0; len];
+        write_header::<T>(&mut data).unwrap();
+        state.serialize(&mut &mut data[HEADER_LEN..]).unwrap();
+        data
+    }
+
+    fn zero_copy_account<T: ZeroCopy>(state: &T, len: usize) -> Vec<u8> {
+        let mut data = vec![0; len];
+        write_header::<T>(&mut data).unwrap();
+        data[HEADER_LEN..T::LEN].copy_from_slice(bytes_of(state));
+        data
+    }
+
+    fn inspect_json(owner: &Pubkey, data: &[u8], programs: &Programs) -> Value {
+        inspect(owner, data, programs).unwrap().to_json()
+    }
+
+    #[test]
+    fn test_counters() {
+        let programs = programs();
+        let counter_program = programs.counter.unwrap();
+        let data = counter::state::Counter { count: 3 }.try_to_vec().unwrap();
+        assert_eq!(
+            inspect_json(&counter_program, &data, &programs),
+            json!({ "program": "counter", "type": "Counter", "data": { "count": 3 } })
+        );
+
+        let authority = Pubkey::new_unique();
+        let mut rate_limit = counter::state::RateLimit {
+            authority,
+            max_increments: 2,
+            window: 60,
+            ..counter::state::RateLimit::default()
+        };
+        assert!(rate_limit.record(100));
+        let mut data = counter::state::Counter { count: 1 }.try_to_vec().unwrap();
+        data.extend(rate_limit.try_to_vec().unwrap());
+        assert_eq!(
+            inspect_json(&counter_program, &data, &programs)["data"],
+            json!({
+                "count": 1,
+                "rate_limit": {
+                    "authority": authority.to_string(),
+                    "max_increments": 2,
+                    "window": 60,
+                    "timestamps": [100],
+                },
+            })
+        );
+
+        let counter = authorized_counter::state::Counter {
+            authority,
+            count: 7,
+        };
+        let expected = json!({
+            "program": "authorized_counter",
+            "type": "Counter",
+            "data": { "authority": authority.to_string(), "count": 7 },
+        });
+        let data = borsh_account(&counter, authorized_counter::state::Counter::LEN);
+        assert_eq!(
+            inspect_json(&programs.authorized_counter, &data, &programs),
+            expected
+        );
+        // Counters created before account headers
+        let data = LegacyCounter {
+            authority,
+            count: 7,
+        }
+        .try_to_vec()
+        .unwrap();
+        assert_eq!(
+            inspect_json(&programs.authorized_counter, &data, &programs),
+            expected
+        );
+
+        let shard = Shard {
+            bump: 254,
+            index: 1,
+            counter: Pubkey::new_unique(),
+            authority,
+            count: 4,
+        };
+        let data = borsh_account(&shard, Shard::LEN);
+        assert_eq!(
+            inspect_json(&programs.authorized_counter, &data, &programs),
+            json!({
+                "program": "authorized_counter",
+                "type": "Shard",
+                "data": {
+                    "bump": 254,
+                    "index": 1,
+                    "counter": shard.counter.to_string(),
+                    "authority": authority.to_string(),
+                    "count": 4,
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn test_trackers() {
+        let programs = programs();
+        let (user, counter) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let tracker = Tracker {
+            bump: 255,
+            auth_bump: 253,
+            user,
+            counter,
+            count: 2,
+            global_count: 9,
+        };
+        let data = borsh_account(&tracker, Tracker::LEN);
+        assert_eq!(
+            inspect_json(&programs.count_tracker, &data, &programs),
+            json!({
+                "program": "count_tracker",
+                "type": "Tracker",
+                "data": {
+                    "bump": 255,
+                    "auth_bump": 253,
+                    "user": user.to_string(),
+                    "counter": counter.to_string(),
+                    "count": 2,
+                    "global_count": 9,
+                },
+            })
+        );
+
+        // Trackers created before account headers don't know their user
+        let legacy = LegacyTracker {
+            bump: 255,
+            auth_bump: 253,
+            counter,
+            count: 2,
+        };
+        let data = legacy.try_to_vec().unwrap();
+        assert_eq!(
+            inspect_json(&programs.count_tracker, &data, &programs)["data"],
+            json!({
+                "bump": 255,
+                "auth_bump": 253,
+                "user": Pubkey::default().to_string(),
+                "counter": counter.to_string(),
+                "count": 2,
+                "global_count": 0,
+            })
+        );
+
+        let mut leaderboard = Leaderboard::new(252, counter);
+        leaderboard.record(user, 2);
+        let data = borsh_account(&leaderboard, Leaderboard::LEN);
+        assert_eq!(
+            inspect_json(&programs.count_tracker, &data, &programs),
+            json!({
+                "program": "count_tracker",
+                "type": "Leaderboard",
+                "data": {
+                    "bump": 252,
+                    "counter": counter.to_string(),
+                    "entries": [{ "user": user.to_string(), "count": 2 }],
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn test_token() {
+        let programs = programs();
+        let token_program = programs.token.unwrap();
+        let (owner, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let data = borsh_account(
+            &Mint {
+                authority: owner,
+                supply: 100,
+            },
+            Mint::LEN,
+        );
+        assert_eq!(
+            inspect_json(&token_program, &data, &programs),
+            json!({
+                "program": "token",
+                "type": "Mint",
+                "data": { "authority": owner.to_string(), "supply": 100 },
+            })
+        );
+
+        let expected = json!({
+            "program": "token",
+            "type": "TokenAccount",
+            "data": { "owner": owner.to_string(), "mint": mint.to_string(), "amount": 40 },
+        });
+        let token_account = TokenAccount {
+            owner,
+            mint,
+            amount: 40,
+        };
+        let data = borsh_account(&token_account, TokenAccount::LEN);
+        assert_eq!(inspect_json(&token_program, &data, &programs), expected);
+        // Token accounts created before account headers
+        let legacy = LegacyTokenAccount {
+            tag: LegacyTokenAccount::TAG,
+            owner,
+            mint,
+            amount: 40,
+        };
+        let data = legacy.try_to_vec().unwrap();
+        assert_eq!(inspect_json(&token_program, &data, &programs), expected);
+    }
+
+    #[test]
+    fn test_marketplace() {
+        let programs = programs();
+        let owner = Pubkey::new_unique();
+        let offer = Offer {
+            seller: Pubkey::new_unique(),
+            offer_mint: Pubkey::new_unique(),
+            buyer_mint: Pubkey::new_unique(),
+            offer_amount: 1,
+            buyer_amount: 50,
+        };
+        let offer_json = json!({
+            "seller": offer.seller.to_string(),
+            "offer_mint": offer.offer_mint.to_string(),
+            "buyer_mint": offer.buyer_mint.to_string(),
+            "offer_amount": 1,
+            "buyer_amount": 50,
+        });
+
+        let mut marketplace: Marketplace = bytemuck::Zeroable::zeroed();
+        marketplace.user = Pubkey::new_unique();
+        marketplace.insert(3, offer);
+        let data = zero_copy_account(&marketplace, Marketplace::LEN);
+        let mut slot_json = offer_json.clone();
+        slot_json["slot"] = json!(3);
+        assert_eq!(
+            inspect_json(&owner, &data, &programs),
+            json!({
+                "program": "marketplace",
+                "type": "Marketplace",
+                "data": { "user": marketplace.user.to_string(), "offers": [slot_json] },
+            })
+        );
+
+        // Only the first `len` of the `capacity` offers are decoded
+        let header = VecHeader {
+            authority: marketplace.user,
+            len: 1,
+            capacity: 2,
+        };
+        let mut data = zero_copy_account(&header, VecHeader::LEN);
+        data.extend(bytes_of(&offer));
+        data.extend(bytes_of(&Offer::default()));
+        assert_eq!(
+            inspect_json(&owner, &data, &programs),
+            json!({
+                "program": "marketplace",
+                "type": "VecAccount<Offer>",
+                "data": {
+                    "authority": marketplace.user.to_string(),
+                    "len": 1,
+                    "capacity": 2,
+                    "offers": [offer_json],
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn test_echo_buffers() {
+        let programs = programs();
+        let echo_program = programs.echo.unwrap();
+        let text = json!({ "len": 8, "text": "hello" });
+        let data = b"hello\0\0\0".to_vec();
+        assert_eq!(
+            inspect_json(&echo_program, &data, &programs),
+            json!({ "program": "echo", "type": "EchoBuffer", "data": text })
+        );
+
+        let header = AuthorizedBufferHeader {
+            bump_seed: 255,
+            buffer_seed: 42,
+        };
+        let mut data = borsh_account(&header, HEADER_LEN + ECHO_HEADER_LEN);
+        data.extend(b"hello\0\0\0");
+        assert_eq!(
+            inspect_json(&echo_program, &data, &programs),
+            json!({
+                "program": "echo",
+                "type": "AuthorizedBuffer",
+                "data": { "bump_seed": 255, "buffer_seed": 42, "data": text },
+            })
+        );
+
+        let header = VendingMachineBufferHeader {
+            bump_seed: 254,
+            price: 10,
+        };
+        let mut data = borsh_account(&header, HEADER_LEN + ECHO_HEADER_LEN);
+        data.extend(b"hello\0\0\0");
+        assert_eq!(
+            inspect_json(&echo_program, &data, &programs),
+            json!({
+                "program": "echo",
+                "type": "VendingMachineBuffer",
+                "data": { "bump_seed": 254, "price": 10, "data": text },
+            })
+        );
+    }
+
+    #[test]
+    fn test_upload_buffer() {
+        let programs = programs();
+        let data_len = 2 * uploader::state::CHUNK_SIZE + 500;
+        let header = BufferHeader {
+            authority: Pubkey::new_unique(),
+            data_len: data_len as u64,
+            chunk_count: 3,
+            finalized: 0,
+            _padding: [0; 3],
+        };
+        let mut data = zero_copy_account(&header, uploader::state::buffer_size(data_len).unwrap());
+        // Chunks 0 and 2 landed
+        data[BufferHeader::LEN] = 0b101;
+        assert_eq!(
+            inspect_json(&Pubkey::new_unique(), &data, &programs),
+            json!({
+                "program": "uploader",
+                "type": "UploadBuffer",
+                "data": {
+                    "authority": header.authority.to_string(),
+                    "data_len": data_len,
+                    "chunk_count": 3,
+                    "chunks_written": 2,
+                    "finalized": false,
+                },
+            })
+        );
+
+        // The bitmap has to be there
+        let data = &data[..BufferHeader::LEN];
+        assert!(matches!(
+            inspect(&Pubkey::new_unique(), data, &programs),
+            Err(InspectError::Decode("BufferHeader", _))
+        ));
+    }
+
+    #[test]
+    fn test_unknown_account() {
+        let programs = programs();
+        let owner = Pubkey::new_unique();
+        assert!(matches!(
+            inspect(&owner, &[1; 40], &programs),
+            Err(InspectError::Unknown(unknown)) if unknown == owner
+        ));
+    }
+}
@@ -0,0 +1,166 @@
+//! Collects the compute units used by each instruction run under `solana-program-test`.
+//!
+//! The runtime logs `Program <id> consumed <X> of <Y> compute units` after every BPF
+//! invocation. `Profiler` installs a logger that keeps those lines, sends one
+//! transaction per measured instruction and records what the outermost invocation
+//! consumed. `Profiler::new` must run before the `ProgramTest` is created, otherwise
+//! the test framework installs its own logger first.
+
+use lazy_static::lazy_static;
+use log::{LevelFilter, Log, Metadata, Record};
+use serde::Serialize;
+use solana_program::{instruction::Instruction, pubkey::Pubkey};
+use solana_program_test::BanksClient;
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+    transport::TransportError,
+};
+
+use std::{fs, io, path::Path, str::FromStr, sync::Mutex};
+
+lazy_static! {
+    static ref COMPUTE_LOGS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+}
+
+/// Keeps every "consumed" line logged by the runtime.
+struct ComputeLogger;
+
+impl Log for ComputeLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= LevelFilter::Debug
+    }
+
+    fn log(&self, record: &Record) {
+        let line = record.args().to_string();
+        if parse_compute_units(&line).is_some() {
+            COMPUTE_LOGS.lock().unwrap().push(line);
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Compute units reported by a single "consumed" log line.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ComputeUnits {
+    pub program_id: Pubkey,
+    pub consumed: u64,
+    pub budget: u64,
+}
+
+/// Parses `Program <id> consumed <X> of <Y> compute units`.
+pub fn parse_compute_units(line: &str) -> Option<ComputeUnits> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    match words[..] {
+        ["Program", program_id, "consumed", consumed, "of", budget, "compute", "units"] => {
+            Some(ComputeUnits {
+                program_id: Pubkey::from_str(program_id).ok()?,
+                consumed: consumed.parse().ok()?,
+                budget: budget.parse().ok()?,
+            })
+        }
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Measurement {
+    pub program: String,
+    pub instruction: String,
+    pub program_id: String,
+    pub consumed: u64,
+    pub budget: u64,
+    pub success: bool,
+}
+
+pub struct Profiler {
+    measurements: Vec<Measurement>,
+}
+
+impl Profiler {
+    pub fn new() -> Self {
+        if log::set_logger(&ComputeLogger).is_err() {
+            panic!("A logger was installed before the profiler, create it before ProgramTest");
+        }
+        log::set_max_level(LevelFilter::Debug);
+        Self {
+            measurements: vec![],
+        }
+    }
+
+    /// Sends `instructions` in one transaction signed by `signers` (the first one pays)
+    /// and records the compute units used by the last instruction under `program` and
+    /// `instruction`. Failed transactions are recorded too, the result is returned so
+    /// the caller can decide whether the failure was expected.
+    pub async fn measure(
+        &mut self,
+        banks_client: &mut BanksClient,
+        program: &str,
+        instruction: &str,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), TransportError> {
+        let program_id = instructions.last().expect("No instructions").program_id;
+        let mut tx = Transaction::new_with_payer(instructions, Some(&signers[0].pubkey()));
+        tx.sign(signers, banks_client.get_latest_blockhash().await?);
+
+        // The solana logger may have lowered the level when the test was set up
+        log::set_max_level(LevelFilter::Debug);
+        COMPUTE_LOGS.lock().unwrap().clear();
+        let result = banks_client.process_transaction(tx).await;
+
+        // Inner invocations finish first, so the last line is the outermost one
+        let usage = COMPUTE_LOGS
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .filter_map(|line| parse_compute_units(line))
+            .find(|usage| usage.program_id == program_id);
+        match usage {
+            Some(usage) => self.measurements.push(Measurement {
+                program: program.to_string(),
+                instruction: instruction.to_string(),
+                program_id: program_id.to_string(),
+                consumed: usage.consumed,
+                budget: usage.budget,
+                success: result.is_ok(),
+            }),
+            None => println!("No compute units logged for {} {}", program, instruction),
+        }
+        result
+    }
+
+    pub fn measurements(&self) -> &[Measurement] {
+        &self.measurements
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("program,instruction,program_id,consumed,budget,success\n");
+        for m in &self.measurements {
+            csv += &format!(
+                "{},{},{},{},{},{}\n",
+                m.program, m.instruction, m.program_id, m.consumed, m.budget, m.success
+            );
+        }
+        csv
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.measurements).unwrap()
+    }
+
+    /// Writes `compute_units.csv` and `compute_units.json` into `dir`.
+    pub fn write_report(&self, dir: &Path) -> io::Result<()> {
+        fs::create_dir_all(dir)?;
+        fs::write(dir.join("compute_units.csv"), self.to_csv())?;
+        fs::write(dir.join("compute_units.json"), self.to_json())
+    }
+}
+
+impl Default for Profiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
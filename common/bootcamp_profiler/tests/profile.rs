@@ -0,0 +1,722 @@
+#![cfg(feature = "test-bpf")]
+//! Profiles every instruction of the lecture programs. Build the programs with
+//! `cargo build-bpf` first, then run `cargo test-bpf` in this crate. The report is
+//! written to `target/compute-report`, or to `$COMPUTE_REPORT_DIR` if it is set.
+
+use {
+    bootcamp_accounts::ZeroCopy,
+    bootcamp_profiler::Profiler,
+    authorized_counter::state::Shard,
+    borsh::BorshSerialize,
+    count_tracker::state::LegacyTracker,
+    marketplace::{
+        instruction::{RuntimeLimitationInstruction, StackMode},
+        state::{Marketplace, Offer},
+        vec_account::VecAccount,
+    },
+    solana_program::{
+        hash::hash,
+        instruction::{AccountMeta, Instruction},
+        program_option::COption,
+        program_pack::Pack,
+        pubkey::Pubkey,
+        system_program, sysvar,
+    },
+    solana_program_test::{BanksClient, ProgramTest},
+    solana_sdk::{
+        account::Account,
+        signature::{Keypair, Signer},
+        transaction::Transaction,
+    },
+    std::path::PathBuf,
+    token::state::LegacyTokenAccount,
+    uploader::state::{buffer_size, CHUNK_SIZE},
+};
+
+fn program_account(owner: &Pubkey, len: usize) -> Account {
+    Account {
+        lamports: 1_000_000_000,
+        data: vec![0; len],
+        owner: *owner,
+        ..Account::default()
+    }
+}
+
+/// Account holding `state` in the layout from before account headers.
+fn legacy_account<T: BorshSerialize>(owner: &Pubkey, state: T) -> Account {
+    Account {
+        data: state.try_to_vec().unwrap(),
+        ..program_account(owner, 0)
+    }
+}
+
+fn spl_account<T: Pack>(state: T) -> Account {
+    let mut data = vec![0; T::LEN];
+    T::pack(state, &mut data).unwrap();
+    Account {
+        lamports: 1_000_000_000,
+        data,
+        owner: spl_token::id(),
+        ..Account::default()
+    }
+}
+
+fn spl_mint(authority: &Pubkey, supply: u64) -> Account {
+    spl_account(spl_token::state::Mint {
+        mint_authority: COption::Some(*authority),
+        supply,
+        decimals: 0,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    })
+}
+
+fn spl_token_account(mint: &Pubkey, owner: &Pubkey, amount: u64) -> Account {
+    spl_account(spl_token::state::Account {
+        mint: *mint,
+        owner: *owner,
+        amount,
+        state: spl_token::state::AccountState::Initialized,
+        ..spl_token::state::Account::default()
+    })
+}
+
+/// Sends setup transactions that are not part of the report.
+async fn process(
+    banks_client: &mut BanksClient,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) {
+    let mut tx = Transaction::new_with_payer(instructions, Some(&signers[0].pubkey()));
+    tx.sign(signers, banks_client.get_latest_blockhash().await.unwrap());
+    banks_client.process_transaction(tx).await.unwrap();
+}
+
+fn report_dir() -> PathBuf {
+    std::env::var("COMPUTE_REPORT_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| {
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../target/compute-report")
+        })
+}
+
+#[tokio::test]
+async fn profile_all_programs() {
+    let mut profiler = Profiler::new();
+    let mut program_test = ProgramTest::default();
+
+    let counter_id = Pubkey::new_unique();
+    let tracker_id = Pubkey::new_unique();
+    let token_id = Pubkey::new_unique();
+    let echo_id = Pubkey::new_unique();
+    let marketplace_id = Pubkey::new_unique();
+    let uploader_id = Pubkey::new_unique();
+    program_test.add_program("counter", counter_id, None);
+    program_test.add_program("authorized_counter", authorized_counter::id(), None);
+    program_test.add_program("count_tracker", tracker_id, None);
+    program_test.add_program("token", token_id, None);
+    program_test.add_program("echo", echo_id, None);
+    program_test.add_program("marketplace", marketplace_id, None);
+    program_test.add_program("uploader", uploader_id, None);
+
+    // Signs alongside the fee payer wherever an authority or owner is needed
+    let user = Keypair::new();
+    program_test.add_account(user.pubkey(), program_account(&system_program::id(), 0));
+
+    let counter = Pubkey::new_unique();
+    program_test.add_account(counter, program_account(&counter_id, 8));
+    // Signs to claim its rate limit
+    let rate_limited_counter = Keypair::new();
+    program_test.add_account(
+        rate_limited_counter.pubkey(),
+        program_account(&counter_id, counter::state::Counter::RATE_LIMITED_LEN),
+    );
+    let global_counter = Pubkey::new_unique();
+    program_test.add_account(global_counter, program_account(&authorized_counter::id(), 40));
+    let tracked_counter = Pubkey::new_unique();
+    program_test.add_account(tracked_counter, program_account(&authorized_counter::id(), 40));
+
+    // Tracker without an account header, of a counter that has no account
+    let legacy_counter = Pubkey::new_unique();
+    let (legacy_tracker, bump) = Pubkey::find_program_address(
+        &[user.pubkey().as_ref(), legacy_counter.as_ref()],
+        &tracker_id,
+    );
+    let (_, auth_bump) = Pubkey::find_program_address(&[legacy_counter.as_ref()], &tracker_id);
+    program_test.add_account(
+        legacy_tracker,
+        legacy_account(
+            &tracker_id,
+            LegacyTracker {
+                bump,
+                auth_bump,
+                counter: legacy_counter,
+                count: 1,
+            },
+        ),
+    );
+
+    let mint = Pubkey::new_unique();
+    program_test.add_account(mint, program_account(&token_id, token::state::Mint::LEN));
+    let (src, dst) = (Pubkey::new_unique(), Pubkey::new_unique());
+    for token_account in [src, dst] {
+        program_test.add_account(
+            token_account,
+            program_account(&token_id, token::state::TokenAccount::LEN),
+        );
+    }
+
+    let legacy_token_account = Pubkey::new_unique();
+    program_test.add_account(
+        legacy_token_account,
+        legacy_account(
+            &token_id,
+            LegacyTokenAccount {
+                tag: LegacyTokenAccount::TAG,
+                owner: user.pubkey(),
+                mint,
+                amount: 10,
+            },
+        ),
+    );
+
+    let echo_buffer = Pubkey::new_unique();
+    program_test.add_account(echo_buffer, program_account(&echo_id, 64));
+    let echo_mint = Pubkey::new_unique();
+    program_test.add_account(echo_mint, spl_mint(&user.pubkey(), 100));
+    let echo_token_account = Pubkey::new_unique();
+    program_test.add_account(
+        echo_token_account,
+        spl_token_account(&echo_mint, &user.pubkey(), 100),
+    );
+
+    let marketplace = Pubkey::new_unique();
+    program_test.add_account(marketplace, program_account(&marketplace_id, Marketplace::LEN));
+    let tx_buffer = Pubkey::new_unique();
+    program_test.add_account(tx_buffer, program_account(&marketplace_id, 1024));
+    let offers = Pubkey::new_unique();
+    program_test.add_account(
+        offers,
+        program_account(&marketplace_id, VecAccount::<Offer>::space(4)),
+    );
+    let buyer = Keypair::new();
+    let (offer_mint, buyer_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+    program_test.add_account(offer_mint, spl_mint(&user.pubkey(), 1_000));
+    program_test.add_account(buyer_mint, spl_mint(&user.pubkey(), 1_000));
+    let seller_token_account = Pubkey::new_unique();
+    program_test.add_account(
+        seller_token_account,
+        spl_token_account(&offer_mint, &user.pubkey(), 1_000),
+    );
+    let seller_payment_account = Pubkey::new_unique();
+    program_test.add_account(
+        seller_payment_account,
+        spl_token_account(&buyer_mint, &user.pubkey(), 0),
+    );
+    let buyer_payment_account = Pubkey::new_unique();
+    program_test.add_account(
+        buyer_payment_account,
+        spl_token_account(&buyer_mint, &buyer.pubkey(), 1_000),
+    );
+    let buyer_token_account = Pubkey::new_unique();
+    program_test.add_account(
+        buyer_token_account,
+        spl_token_account(&offer_mint, &buyer.pubkey(), 0),
+    );
+
+    // Two full chunks, so that Write and Finalize are measured on full chunks
+    let blob: Vec<u8> = (0..2 * CHUNK_SIZE).map(|i| (i % 251) as u8).collect();
+    let upload_buffer = Pubkey::new_unique();
+    program_test.add_account(
+        upload_buffer,
+        program_account(&uploader_id, buffer_size(blob.len()).unwrap()),
+    );
+
+    let (mut banks_client, payer, _recent_blockhash) = program_test.start().await;
+    let signers = &[&payer, &user];
+
+    // lecture_1/counter
+    for (name, ix) in [
+        ("Increment", counter::instruction::increment(counter_id, counter).unwrap()),
+        ("Decrement", counter::instruction::decrement(counter_id, counter).unwrap()),
+    ] {
+        profiler
+            .measure(&mut banks_client, "counter", name, &[ix], &[&payer])
+            .await
+            .unwrap();
+    }
+    let ix = counter::instruction::claim_rate_limit(
+        counter_id,
+        rate_limited_counter.pubkey(),
+        user.pubkey(),
+        5,
+        60,
+    )
+    .unwrap();
+    profiler
+        .measure(
+            &mut banks_client,
+            "counter",
+            "SetRateLimit",
+            &[ix],
+            &[&payer, &user, &rate_limited_counter],
+        )
+        .await
+        .unwrap();
+
+    // lecture_3/tracker/authorized_counter
+    let ix = authorized_counter::instruction::increment(
+        authorized_counter::id(),
+        global_counter,
+        user.pubkey(),
+        authorized_counter::instruction::CounterInstruction::Increment,
+    )
+    .unwrap();
+    profiler
+        .measure(&mut banks_client, "authorized_counter", "Increment", &[ix], signers)
+        .await
+        .unwrap();
+    // The counter was created without an account header
+    let ix = authorized_counter::instruction::migrate(
+        authorized_counter::id(),
+        global_counter,
+        payer.pubkey(),
+    )
+    .unwrap();
+    profiler
+        .measure(&mut banks_client, "authorized_counter", "Migrate", &[ix], &[&payer])
+        .await
+        .unwrap();
+    let (shard, _) = Shard::find_address(&authorized_counter::id(), &global_counter, 0);
+    let ix = authorized_counter::instruction::initialize_shard(
+        authorized_counter::id(),
+        shard,
+        global_counter,
+        user.pubkey(),
+        payer.pubkey(),
+        0,
+    )
+    .unwrap();
+    profiler
+        .measure(&mut banks_client, "authorized_counter", "InitializeShard", &[ix], signers)
+        .await
+        .unwrap();
+    let ix = authorized_counter::instruction::increment_shard(
+        authorized_counter::id(),
+        shard,
+        user.pubkey(),
+    )
+    .unwrap();
+    profiler
+        .measure(&mut banks_client, "authorized_counter", "IncrementShard", &[ix], signers)
+        .await
+        .unwrap();
+    let ix = authorized_counter::instruction::aggregate(
+        authorized_counter::id(),
+        global_counter,
+        &[shard],
+    )
+    .unwrap();
+    profiler
+        .measure(&mut banks_client, "authorized_counter", "Aggregate", &[ix], &[&payer])
+        .await
+        .unwrap();
+    let ix =
+        authorized_counter::instruction::get(authorized_counter::id(), global_counter).unwrap();
+    profiler
+        .measure(&mut banks_client, "authorized_counter", "Get", &[ix], &[&payer])
+        .await
+        .unwrap();
+
+    // lecture_3/tracker/count_tracker
+    let (tracker, _) = Pubkey::find_program_address(
+        &[user.pubkey().as_ref(), tracked_counter.as_ref()],
+        &tracker_id,
+    );
+    let (authority, _) = Pubkey::find_program_address(&[tracked_counter.as_ref()], &tracker_id);
+    let (leaderboard, _) = Pubkey::find_program_address(
+        &[b"leaderboard", tracked_counter.as_ref()],
+        &tracker_id,
+    );
+    let ix = count_tracker::instruction::initialize(
+        tracker_id,
+        tracker,
+        user.pubkey(),
+        authority,
+        tracked_counter,
+    )
+    .unwrap();
+    profiler
+        .measure(&mut banks_client, "count_tracker", "Initialize", &[ix], signers)
+        .await
+        .unwrap();
+    let ix = count_tracker::instruction::initialize_leaderboard(
+        tracker_id,
+        leaderboard,
+        payer.pubkey(),
+        tracked_counter,
+    )
+    .unwrap();
+    profiler
+        .measure(&mut banks_client, "count_tracker", "InitializeLeaderboard", &[ix], &[&payer])
+        .await
+        .unwrap();
+    let ix = count_tracker::instruction::increment(
+        tracker_id,
+        tracker,
+        user.pubkey(),
+        tracked_counter,
+        authority,
+        leaderboard,
+    )
+    .unwrap();
+    profiler
+        .measure(&mut banks_client, "count_tracker", "Increment", &[ix], signers)
+        .await
+        .unwrap();
+    let ix = count_tracker::instruction::migrate(
+        tracker_id,
+        legacy_tracker,
+        user.pubkey(),
+        payer.pubkey(),
+    )
+    .unwrap();
+    profiler
+        .measure(&mut banks_client, "count_tracker", "Migrate", &[ix], &[&payer])
+        .await
+        .unwrap();
+    // The counter's authority is the tracker's PDA since the first Increment
+    let ix = count_tracker::instruction::initialize_shard(
+        tracker_id,
+        payer.pubkey(),
+        tracked_counter,
+        authority,
+        0,
+    )
+    .unwrap();
+    profiler
+        .measure(&mut banks_client, "count_tracker", "InitializeShard", &[ix], &[&payer])
+        .await
+        .unwrap();
+    let ix = count_tracker::instruction::increment_shard(
+        tracker_id,
+        tracker,
+        user.pubkey(),
+        tracked_counter,
+        authority,
+        leaderboard,
+        0,
+    )
+    .unwrap();
+    profiler
+        .measure(&mut banks_client, "count_tracker", "IncrementShard", &[ix], signers)
+        .await
+        .unwrap();
+
+    // lecture_4/token
+    let ix = token::instruction::initialize_mint(token_id, mint, user.pubkey()).unwrap();
+    profiler
+        .measure(&mut banks_client, "token", "InitializeMint", &[ix], signers)
+        .await
+        .unwrap();
+    let init_token_account = |token_account| {
+        token::instruction::initialize_token_account(token_id, token_account, mint, user.pubkey())
+            .unwrap()
+    };
+    profiler
+        .measure(
+            &mut banks_client,
+            "token",
+            "InitializeTokenAccount",
+            &[init_token_account(src)],
+            &[&payer],
+        )
+        .await
+        .unwrap();
+    process(&mut banks_client, &[init_token_account(dst)], &[&payer]).await;
+    for (name, ix) in [
+        ("Mint", token::instruction::mint(token_id, src, mint, user.pubkey(), 100)),
+        ("Transfer", token::instruction::transfer(token_id, src, dst, user.pubkey(), 40)),
+        ("Burn", token::instruction::burn(token_id, src, mint, user.pubkey(), 10)),
+    ] {
+        profiler
+            .measure(&mut banks_client, "token", name, &[ix.unwrap()], signers)
+            .await
+            .unwrap();
+    }
+    let ix = token::instruction::migrate(token_id, legacy_token_account, payer.pubkey()).unwrap();
+    profiler
+        .measure(&mut banks_client, "token", "Migrate", &[ix], &[&payer])
+        .await
+        .unwrap();
+
+    // project_specs/echo-skeleton: still unimplemented, so failures are recorded too
+    use echo::instruction as echo_ix;
+    let (authorized_buffer, _) = Pubkey::find_program_address(
+        &[b"authority", user.pubkey().as_ref(), &0u64.to_le_bytes()],
+        &echo_id,
+    );
+    let (vending_machine_buffer, _) = Pubkey::find_program_address(
+        &[b"vending_machine", echo_mint.as_ref(), &10u64.to_le_bytes()],
+        &echo_id,
+    );
+    let echo_instructions = [
+        ("Echo", echo_ix::echo(echo_id, echo_buffer, b"hello".to_vec())),
+        (
+            "InitializeAuthorizedEcho",
+            echo_ix::initialize_authorized_echo(echo_id, authorized_buffer, user.pubkey(), 0, 64),
+        ),
+        (
+            "AuthorizedEcho",
+            echo_ix::authorized_echo(echo_id, authorized_buffer, user.pubkey(), b"hello".to_vec()),
+        ),
+        (
+            "InitializeVendingMachineEcho",
+            echo_ix::initialize_vending_machine_echo(
+                echo_id,
+                vending_machine_buffer,
+                echo_mint,
+                user.pubkey(),
+                10,
+                64,
+            ),
+        ),
+        (
+            "VendingMachineEcho",
+            echo_ix::vending_machine_echo(
+                echo_id,
+                vending_machine_buffer,
+                user.pubkey(),
+                echo_token_account,
+                echo_mint,
+                b"hello".to_vec(),
+            ),
+        ),
+    ];
+    for (name, ix) in echo_instructions {
+        let ix = ix.unwrap();
+        let signers: &[&Keypair] = if ix.accounts.iter().any(|meta| meta.is_signer) {
+            signers
+        } else {
+            &[&payer]
+        };
+        let _ = profiler
+            .measure(&mut banks_client, "echo", name, &[ix], signers)
+            .await;
+    }
+
+    // lecture_5/runtime_limitations
+    let marketplace_ix = |instruction: RuntimeLimitationInstruction, accounts| {
+        Instruction::new_with_borsh(marketplace_id, &instruction, accounts)
+    };
+    for mode in [StackMode::Borsh, StackMode::Boxed, StackMode::ZeroCopy] {
+        let ix = marketplace_ix(
+            RuntimeLimitationInstruction::Stack { mode },
+            vec![
+                AccountMeta::new(marketplace, false),
+                AccountMeta::new_readonly(user.pubkey(), false),
+            ],
+        );
+        // Borsh mode overflows the stack on purpose
+        let name = format!("Stack {:?}", mode);
+        let result = profiler
+            .measure(&mut banks_client, "marketplace", &name, &[ix], &[&payer])
+            .await;
+        assert_eq!(result.is_ok(), mode != StackMode::Borsh);
+    }
+    let ix = marketplace_ix(
+        RuntimeLimitationInstruction::Runtime { max_iter: 10_000 },
+        vec![],
+    );
+    profiler
+        .measure(&mut banks_client, "marketplace", "Runtime", &[ix], &[&payer])
+        .await
+        .unwrap();
+    let new_account = Keypair::new();
+    let ix = marketplace_ix(
+        RuntimeLimitationInstruction::Cpi { size: 1024 },
+        vec![
+            AccountMeta::new(payer.pubkey(), true),
+            AccountMeta::new(new_account.pubkey(), true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    );
+    profiler
+        .measure(&mut banks_client, "marketplace", "Cpi", &[ix], &[&payer, &new_account])
+        .await
+        .unwrap();
+    let ix = marketplace_ix(
+        RuntimeLimitationInstruction::ZeroCopy,
+        vec![
+            AccountMeta::new(marketplace, false),
+            AccountMeta::new_readonly(user.pubkey(), false),
+        ],
+    );
+    profiler
+        .measure(&mut banks_client, "marketplace", "ZeroCopy", &[ix], &[&payer])
+        .await
+        .unwrap();
+    let ix = marketplace_ix(
+        RuntimeLimitationInstruction::Tx {
+            offset: 0,
+            data: vec![b'a'; 900],
+        },
+        vec![AccountMeta::new(tx_buffer, false)],
+    );
+    profiler
+        .measure(&mut banks_client, "marketplace", "Tx", &[ix], &[&payer])
+        .await
+        .unwrap();
+    let ix = marketplace_ix(
+        RuntimeLimitationInstruction::RecursiveCpi {
+            depth: 3,
+            stack_payload: 1024,
+        },
+        vec![AccountMeta::new_readonly(marketplace_id, false)],
+    );
+    profiler
+        .measure(&mut banks_client, "marketplace", "RecursiveCpi", &[ix], &[&payer])
+        .await
+        .unwrap();
+
+    let (vault, _) = Pubkey::find_program_address(
+        &[b"vault", marketplace.as_ref(), offer_mint.as_ref()],
+        &marketplace_id,
+    );
+    let post_offer = |buyer_amount| {
+        marketplace_ix(
+            RuntimeLimitationInstruction::PostOffer {
+                offer_amount: 100,
+                buyer_amount,
+            },
+            vec![
+                AccountMeta::new(marketplace, false),
+                AccountMeta::new(user.pubkey(), true),
+                AccountMeta::new(seller_token_account, false),
+                AccountMeta::new(vault, false),
+                AccountMeta::new_readonly(offer_mint, false),
+                AccountMeta::new_readonly(buyer_mint, false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+            ],
+        )
+    };
+    profiler
+        .measure(&mut banks_client, "marketplace", "PostOffer", &[post_offer(50)], signers)
+        .await
+        .unwrap();
+    let ix = marketplace_ix(
+        RuntimeLimitationInstruction::CancelOffer { slot: 0 },
+        vec![
+            AccountMeta::new(marketplace, false),
+            AccountMeta::new_readonly(user.pubkey(), true),
+            AccountMeta::new(seller_token_account, false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+    );
+    profiler
+        .measure(&mut banks_client, "marketplace", "CancelOffer", &[ix], signers)
+        .await
+        .unwrap();
+    process(&mut banks_client, &[post_offer(60)], signers).await;
+    let ix = marketplace_ix(
+        RuntimeLimitationInstruction::FillOffer { slot: 0 },
+        vec![
+            AccountMeta::new(marketplace, false),
+            AccountMeta::new_readonly(buyer.pubkey(), true),
+            AccountMeta::new(buyer_payment_account, false),
+            AccountMeta::new(seller_payment_account, false),
+            AccountMeta::new(buyer_token_account, false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+    );
+    profiler
+        .measure(&mut banks_client, "marketplace", "FillOffer", &[ix], &[&payer, &buyer])
+        .await
+        .unwrap();
+
+    let ix = marketplace_ix(
+        RuntimeLimitationInstruction::Push {
+            offers: vec![Offer::default(); 8],
+        },
+        vec![
+            AccountMeta::new(offers, false),
+            AccountMeta::new(user.pubkey(), true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    );
+    profiler
+        .measure(&mut banks_client, "marketplace", "Push", &[ix], signers)
+        .await
+        .unwrap();
+    let ix = marketplace_ix(
+        RuntimeLimitationInstruction::Remove { index: 0 },
+        vec![
+            AccountMeta::new(offers, false),
+            AccountMeta::new_readonly(user.pubkey(), true),
+        ],
+    );
+    profiler
+        .measure(&mut banks_client, "marketplace", "Remove", &[ix], signers)
+        .await
+        .unwrap();
+    let ix = marketplace_ix(
+        RuntimeLimitationInstruction::Compact,
+        vec![
+            AccountMeta::new(offers, false),
+            AccountMeta::new(user.pubkey(), true),
+        ],
+    );
+    profiler
+        .measure(&mut banks_client, "marketplace", "Compact", &[ix], signers)
+        .await
+        .unwrap();
+
+    // uploader/program: a full chunk only fits in a transaction with a single signer
+    let ix = uploader::instruction::initialize(
+        uploader_id,
+        upload_buffer,
+        payer.pubkey(),
+        blob.len() as u64,
+    )
+    .unwrap();
+    profiler
+        .measure(&mut banks_client, "uploader", "Initialize", &[ix], &[&payer])
+        .await
+        .unwrap();
+    let write = |chunk: usize| {
+        let offset = chunk * CHUNK_SIZE;
+        uploader::instruction::write(
+            uploader_id,
+            upload_buffer,
+            payer.pubkey(),
+            offset as u64,
+            blob[offset..offset + CHUNK_SIZE].to_vec(),
+        )
+        .unwrap()
+    };
+    profiler
+        .measure(&mut banks_client, "uploader", "Write", &[write(0)], &[&payer])
+        .await
+        .unwrap();
+    process(&mut banks_client, &[write(1)], &[&payer]).await;
+    let ix = uploader::instruction::finalize(
+        uploader_id,
+        upload_buffer,
+        payer.pubkey(),
+        hash(&blob).to_bytes(),
+    )
+    .unwrap();
+    profiler
+        .measure(&mut banks_client, "uploader", "Finalize", &[ix], &[&payer])
+        .await
+        .unwrap();
+
+    let dir = report_dir();
+    profiler.write_report(&dir).unwrap();
+    println!("{}", profiler.to_csv());
+    println!("Compute report written to {}", dir.display());
+}
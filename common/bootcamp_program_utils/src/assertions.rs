@@ -0,0 +1,77 @@
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, msg, program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+pub fn assert_with_msg(statement: bool, err: ProgramError, msg: &str) -> ProgramResult {
+    if !statement {
+        msg!(msg);
+        Err(err)
+    } else {
+        Ok(())
+    }
+}
+
+/// Same as `assert_with_msg`, but also logs the key of the offending account.
+fn assert_account(
+    statement: bool,
+    account: &AccountInfo,
+    err: ProgramError,
+    msg: &str,
+) -> ProgramResult {
+    if !statement {
+        msg!("{}: {}", msg, account.key);
+        Err(err)
+    } else {
+        Ok(())
+    }
+}
+
+pub fn expect_signer(account: &AccountInfo, err: ProgramError, msg: &str) -> ProgramResult {
+    assert_account(account.is_signer, account, err, msg)
+}
+
+pub fn expect_writable(account: &AccountInfo, msg: &str) -> ProgramResult {
+    assert_account(
+        account.is_writable,
+        account,
+        ProgramError::InvalidArgument,
+        msg,
+    )
+}
+
+/// Checks that `account` is owned by `owner`. Programs can read accounts of any owner,
+/// so this must be checked before trusting the data of an account.
+pub fn expect_owner(account: &AccountInfo, owner: &Pubkey, msg: &str) -> ProgramResult {
+    assert_account(
+        account.owner == owner,
+        account,
+        ProgramError::IncorrectProgramId,
+        msg,
+    )
+}
+
+pub fn expect_program(
+    account: &AccountInfo,
+    program_id: &Pubkey,
+    err: ProgramError,
+    msg: &str,
+) -> ProgramResult {
+    assert_account(account.key == program_id, account, err, msg)
+}
+
+/// Checks that no account is passed in more than once, so an aliased account can't
+/// stand in for two of the instruction's accounts.
+pub fn expect_unique(accounts: &[&AccountInfo], err: ProgramError) -> ProgramResult {
+    for (i, account) in accounts.iter().enumerate() {
+        assert_account(
+            accounts[i + 1..]
+                .iter()
+                .all(|other| other.key != account.key),
+            account,
+            err.clone(),
+            "Accounts must be unique",
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use num_traits::FromPrimitive;
+use solana_program::{
+    account_info::AccountInfo,
+    decode_error::DecodeError,
+    entrypoint::{ProcessInstruction, ProgramResult},
+    program_error::PrintProgramError,
+    pubkey::Pubkey,
+};
+
+/// Declares the entrypoint of a program, unless it is built with its `no-entrypoint`
+/// feature. Errors returned by the processor are logged as `$error` when they are
+/// custom codes, since clients only see the code.
+///
+/// Instructions are only logged with the `log-instruction-data` feature of this crate:
+///
+/// ```text
+/// cargo build-bpf --features bootcamp_program_utils/log-instruction-data
+/// ```
+#[macro_export]
+macro_rules! entrypoint {
+    ($process_instruction:path, $error:ty) => {
+        #[cfg(not(feature = "no-entrypoint"))]
+        $crate::solana_program::entrypoint!(process_instruction);
+
+        #[cfg(not(feature = "no-entrypoint"))]
+        fn process_instruction(
+            program_id: &$crate::solana_program::pubkey::Pubkey,
+            accounts: &[$crate::solana_program::account_info::AccountInfo],
+            instruction_data: &[u8],
+        ) -> $crate::solana_program::entrypoint::ProgramResult {
+            $crate::entrypoint::process_instruction::<$error>(
+                $process_instruction,
+                program_id,
+                accounts,
+                instruction_data,
+            )
+        }
+    };
+}
+
+pub fn process_instruction<E>(
+    process_instruction: ProcessInstruction,
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult
+where
+    E: 'static + std::error::Error + DecodeError<E> + PrintProgramError + FromPrimitive,
+{
+    #[cfg(feature = "log-instruction-data")]
+    solana_program::msg!(
+        "process_instruction: {}: {} accounts, data={:?}",
+        program_id,
+        accounts.len(),
+        instruction_data
+    );
+
+    if let Err(error) = process_instruction(program_id, accounts, instruction_data) {
+        error.print::<E>();
+        return Err(error);
+    }
+    Ok(())
+}
@@ -0,0 +1,32 @@
+//! Helpers shared by the processors of the bootcamp programs.
+//!
+//! Every check logs why it failed before returning its error, since clients only see
+//! the error code. The `entrypoint!` macro replaces the `entrypoint.rs` boilerplate.
+//!
+//! ```ignore
+//! bootcamp_program_utils::entrypoint!(Processor::process_instruction, CounterError);
+//!
+//! expect_signer(user, ProgramError::MissingRequiredSignature, "User must sign")?;
+//! let bump = expect_pda(tracker_ai, &[user.key.as_ref()], program_id, "Invalid tracker")?;
+//! create_pda_account(user, tracker_ai, system_program, program_id, Tracker::LEN, seeds)?;
+//! resize_account(tracker_ai, payer, system_program, Tracker::LEN)?;
+//! set_return_data(&tracker.count.to_le_bytes());
+//! ```
+
+mod assertions;
+#[doc(hidden)]
+pub mod entrypoint;
+mod pda;
+mod resize;
+mod return_data;
+
+pub use assertions::{
+    assert_with_msg, expect_owner, expect_program, expect_signer, expect_unique, expect_writable,
+};
+pub use pda::{create_pda_account, expect_pda, expect_pda_with_bump};
+pub use resize::resize_account;
+pub use return_data::{set_return_data, RETURN_DATA_TAG};
+
+// Lets `entrypoint!` expand in crates that rename or don't import solana-program
+#[doc(hidden)]
+pub use solana_program;
@@ -0,0 +1,81 @@
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction,
+    sysvar::{rent::Rent, Sysvar},
+};
+
+use crate::assertions::assert_with_msg;
+
+/// Checks that `account` is the PDA derived from `seeds` and returns its bump seed.
+pub fn expect_pda(
+    account: &AccountInfo,
+    seeds: &[&[u8]],
+    program_id: &Pubkey,
+    msg: &str,
+) -> Result<u8, ProgramError> {
+    let (key, bump) = Pubkey::find_program_address(seeds, program_id);
+    assert_with_msg(key == *account.key, ProgramError::InvalidArgument, msg)?;
+    Ok(bump)
+}
+
+/// Same as `expect_pda`, but for seeds that already include a stored bump seed.
+/// This avoids the bump search of `find_program_address`.
+pub fn expect_pda_with_bump(
+    account: &AccountInfo,
+    seeds_with_bump: &[&[u8]],
+    program_id: &Pubkey,
+    msg: &str,
+) -> ProgramResult {
+    let key = Pubkey::create_program_address(seeds_with_bump, program_id)?;
+    assert_with_msg(key == *account.key, ProgramError::InvalidArgument, msg)
+}
+
+/// Creates the rent exempt PDA `account` with `space` bytes owned by `owner`, paid for
+/// by `payer`. `signer_seeds` include the bump seed of `account`.
+///
+/// Anyone can transfer lamports to a PDA before it is created, which would make
+/// `create_account` fail, so a funded account is topped up and allocated instead.
+pub fn create_pda_account<'a>(
+    payer: &AccountInfo<'a>,
+    account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    owner: &Pubkey,
+    space: usize,
+    signer_seeds: &[&[u8]],
+) -> ProgramResult {
+    let lamports = Rent::get()?.minimum_balance(space);
+    if account.lamports() == 0 {
+        return invoke_signed(
+            &system_instruction::create_account(
+                payer.key,
+                account.key,
+                lamports,
+                space as u64,
+                owner,
+            ),
+            &[payer.clone(), account.clone(), system_program.clone()],
+            &[signer_seeds],
+        );
+    }
+    let top_up = lamports.saturating_sub(account.lamports());
+    if top_up > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, top_up),
+            &[payer.clone(), account.clone(), system_program.clone()],
+        )?;
+    }
+    invoke_signed(
+        &system_instruction::allocate(account.key, space as u64),
+        &[account.clone(), system_program.clone()],
+        &[signer_seeds],
+    )?;
+    invoke_signed(
+        &system_instruction::assign(account.key, owner),
+        &[account.clone(), system_program.clone()],
+        &[signer_seeds],
+    )
+}
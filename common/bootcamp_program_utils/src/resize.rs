@@ -0,0 +1,30 @@
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    program::invoke,
+    system_instruction,
+    sysvar::{rent::Rent, Sysvar},
+};
+
+/// Resizes `account`, which must be owned by the executing program, to `len` bytes.
+/// `payer` tops up the rent exemption of the new length, new bytes are zeroed.
+///
+/// The runtime only lets an account grow by `MAX_PERMITTED_DATA_INCREASE` bytes per
+/// instruction.
+pub fn resize_account<'a>(
+    account: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    len: usize,
+) -> ProgramResult {
+    let top_up = Rent::get()?
+        .minimum_balance(len)
+        .saturating_sub(account.lamports());
+    if top_up > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, top_up),
+            &[payer.clone(), account.clone(), system_program.clone()],
+        )?;
+    }
+    account.realloc(len, true)
+}
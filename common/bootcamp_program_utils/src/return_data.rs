@@ -0,0 +1,15 @@
+/// First field of the `sol_log_data` call that carries return data off-chain.
+pub const RETURN_DATA_TAG: &[u8] = b"bootcamp:return_data";
+
+/// Sets the return data of the executing program.
+///
+/// Natively, solana-program 1.9 takes the write lock of its syscall stubs to set return
+/// data while `invoke` holds the read lock for the whole CPI, so a callee setting return
+/// data deadlocks. Off-chain the data is passed to the stubs tagged with
+/// `RETURN_DATA_TAG` through `sol_log_data` instead, which only takes the read lock.
+pub fn set_return_data(data: &[u8]) {
+    #[cfg(target_arch = "bpf")]
+    solana_program::program::set_return_data(data);
+    #[cfg(not(target_arch = "bpf"))]
+    solana_program::log::sol_log_data(&[RETURN_DATA_TAG, data]);
+}
@@ -0,0 +1,119 @@
+use {
+    bootcamp_program_utils::{create_pda_account, expect_pda, expect_signer},
+    bootcamp_runtime::{Account, Runtime, RuntimeError},
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        instruction::{AccountMeta, Instruction},
+        native_token::LAMPORTS_PER_SOL,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+        system_program,
+    },
+};
+
+const SPACE: usize = 100;
+
+/// Creates the PDA of [payer] with `SPACE` bytes.
+fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], _: &[u8]) -> ProgramResult {
+    let [payer, pda, system_program] = match accounts {
+        [a, b, c] => [a, b, c],
+        _ => return Err(ProgramError::NotEnoughAccountKeys),
+    };
+    expect_signer(
+        payer,
+        ProgramError::MissingRequiredSignature,
+        "Payer must sign",
+    )?;
+    let bump = expect_pda(pda, &[payer.key.as_ref()], program_id, "Invalid PDA seeds")?;
+    create_pda_account(
+        payer,
+        pda,
+        system_program,
+        program_id,
+        SPACE,
+        &[payer.key.as_ref(), &[bump]],
+    )
+}
+
+struct Setup {
+    runtime: Runtime,
+    payer: Pubkey,
+    pda: Pubkey,
+    instruction: Instruction,
+}
+
+fn setup() -> Setup {
+    let mut runtime = Runtime::new();
+    let program_id = Pubkey::new_unique();
+    runtime.add_program(program_id, process_instruction);
+    let payer = Pubkey::new_unique();
+    runtime.add_account(
+        payer,
+        Account::new(LAMPORTS_PER_SOL, 0, &system_program::id()),
+    );
+    let (pda, _) = Pubkey::find_program_address(&[payer.as_ref()], &program_id);
+    let instruction = Instruction::new_with_bytes(
+        program_id,
+        &[],
+        vec![
+            AccountMeta::new(payer, true),
+            AccountMeta::new(pda, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    );
+    Setup {
+        runtime,
+        payer,
+        pda,
+        instruction,
+    }
+}
+
+#[test]
+fn test_create_pda_account() {
+    let Setup {
+        mut runtime,
+        payer,
+        pda,
+        instruction,
+    } = setup();
+    runtime.process_instruction(&instruction, &[payer]).unwrap();
+
+    let rent = runtime.rent().minimum_balance(SPACE);
+    let account = runtime.account(&pda).unwrap();
+    assert_eq!(account.owner, instruction.program_id);
+    assert_eq!(account.data.len(), SPACE);
+    assert_eq!(account.lamports, rent);
+    assert_eq!(
+        runtime.account(&payer).unwrap().lamports,
+        LAMPORTS_PER_SOL - rent
+    );
+    assert!(matches!(
+        runtime.process_instruction(&instruction, &[payer]),
+        Err(RuntimeError::Program(id, _)) if id == system_program::id()
+    ));
+}
+
+#[test]
+fn test_create_funded_pda_account() {
+    let Setup {
+        mut runtime,
+        payer,
+        pda,
+        instruction,
+    } = setup();
+    // Lamports sent to the PDA before it exists don't block its creation
+    runtime.add_account(pda, Account::new(1, 0, &system_program::id()));
+    runtime.process_instruction(&instruction, &[payer]).unwrap();
+
+    let rent = runtime.rent().minimum_balance(SPACE);
+    let account = runtime.account(&pda).unwrap();
+    assert_eq!(account.owner, instruction.program_id);
+    assert_eq!(account.data.len(), SPACE);
+    assert_eq!(account.lamports, rent);
+    assert_eq!(
+        runtime.account(&payer).unwrap().lamports,
+        LAMPORTS_PER_SOL - rent + 1
+    );
+}
@@ -0,0 +1,424 @@
+//! State of the transaction being processed on the current thread, and the syscalls the
+//! programs reach it through.
+//!
+//! The syscall stubs are global, but every test thread runs its own `Runtime`, so the
+//! stubs only forward to the context of the calling thread.
+
+use bootcamp_program_utils::RETURN_DATA_TAG;
+use solana_program::{
+    account_info::AccountInfo,
+    clock::Clock,
+    entrypoint::{deserialize, ProcessInstruction, ProgramResult, SUCCESS},
+    instruction::Instruction,
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+    pubkey::Pubkey,
+    rent::Rent,
+    system_program,
+};
+
+use std::{cell::RefCell, collections::HashMap, ptr, slice, sync::Once};
+
+use crate::{
+    serialization::{self, serialize, unique, InstructionAccount},
+    system_program as system, Account, RuntimeError, MAX_INVOKE_DEPTH,
+};
+
+/// An account of the instruction a program is executing. Its state is read from the
+/// input buffer rather than the `AccountInfo`, whose data slice a program may shrink,
+/// e.g. by serializing into it.
+struct FrameAccount {
+    key: Pubkey,
+    capacity: usize,
+    executable: bool,
+    rent_epoch: u64,
+    // Points into the input buffer of the frame
+    serialized: *mut u8,
+}
+
+impl FrameAccount {
+    fn read(&self) -> Account {
+        unsafe { serialization::read(self.serialized, self.executable, self.rent_epoch) }
+    }
+}
+
+struct Frame {
+    program_id: Pubkey,
+    accounts: Vec<FrameAccount>,
+    /// The accounts the program is verified against, as they were before the program
+    /// ran or, for the accounts of an invocation, once it returned
+    pre: Vec<InstructionAccount>,
+}
+
+pub(crate) struct Context {
+    pub programs: HashMap<Pubkey, ProcessInstruction>,
+    pub rent: Rent,
+    pub clock: Clock,
+    pub logs: Vec<String>,
+    pub return_data: Option<(Pubkey, Vec<u8>)>,
+    /// The first failed invocation. It fails the transaction even if the invoking
+    /// program ignores the error, like on chain.
+    pub error: Option<RuntimeError>,
+    frames: Vec<Frame>,
+}
+
+impl Context {
+    pub fn new(programs: HashMap<Pubkey, ProcessInstruction>, rent: Rent, clock: Clock) -> Self {
+        Self {
+            programs,
+            rent,
+            clock,
+            logs: vec![],
+            return_data: None,
+            error: None,
+            frames: vec![],
+        }
+    }
+}
+
+thread_local! {
+    static CONTEXT: RefCell<Option<Context>> = const { RefCell::new(None) };
+}
+
+static STUBS: Once = Once::new();
+
+pub(crate) fn enter(context: Context) {
+    STUBS.call_once(|| {
+        set_syscall_stubs(Box::new(Stubs));
+    });
+    CONTEXT.with(|current| *current.borrow_mut() = Some(context));
+}
+
+pub(crate) fn exit() -> Context {
+    CONTEXT.with(|current| current.borrow_mut().take().expect("no transaction to exit"))
+}
+
+/// Runs `f` on the context of this thread, if a transaction is being processed.
+fn try_with_context<R>(f: impl FnOnce(&mut Context) -> R) -> Option<R> {
+    CONTEXT.with(|current| current.borrow_mut().as_mut().map(f))
+}
+
+fn with_context<R>(f: impl FnOnce(&mut Context) -> R) -> R {
+    try_with_context(f).expect("syscall outside of Runtime::process_transaction")
+}
+
+fn log(message: String) {
+    with_context(|context| context.logs.push(message));
+}
+
+struct Stubs;
+
+impl SyscallStubs for Stubs {
+    fn sol_log(&self, message: &str) {
+        let message = format!("Program log: {}", message);
+        if try_with_context(|context| context.logs.push(message.clone())).is_none() {
+            println!("{}", message);
+        }
+    }
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        invoke(instruction, account_infos, signers_seeds).map_err(|error| {
+            with_context(|context| {
+                context.error.get_or_insert_with(|| error.clone());
+            });
+            error.into()
+        })
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let clock = with_context(|context| context.clock.clone());
+        unsafe { ptr::write(var_addr as *mut Clock, clock) };
+        SUCCESS
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let rent = with_context(|context| context.rent);
+        unsafe { ptr::write(var_addr as *mut Rent, rent) };
+        SUCCESS
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        with_context(|context| context.return_data.clone())
+    }
+
+    fn sol_set_return_data(&mut self, data: &[u8]) {
+        set_return_data(data);
+    }
+
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        // `bootcamp_program_utils::set_return_data` can't take the write lock in a CPI
+        if let [RETURN_DATA_TAG, data] = fields {
+            return set_return_data(data);
+        }
+        let fields: Vec<_> = fields.iter().map(base64::encode).collect();
+        log(format!("Program data: {}", fields.join(" ")));
+    }
+}
+
+fn set_return_data(data: &[u8]) {
+    with_context(|context| {
+        let program_id = context.frames.last().expect("no program is executing").program_id;
+        context.return_data = (!data.is_empty()).then(|| (program_id, data.to_vec()));
+    });
+}
+
+/// Checks that one more program can be invoked and logs the invocation.
+fn push_invocation(program_id: &Pubkey) -> Result<(), RuntimeError> {
+    with_context(|context| {
+        let depth = context.frames.len();
+        if depth >= MAX_INVOKE_DEPTH {
+            return Err(RuntimeError::CallDepth);
+        }
+        // A program may only invoke itself directly
+        let caller = context.frames.last().map(|frame| frame.program_id);
+        if caller != Some(*program_id)
+            && context.frames.iter().any(|frame| frame.program_id == *program_id)
+        {
+            return Err(RuntimeError::Reentrancy(*program_id));
+        }
+        context
+            .logs
+            .push(format!("Program {} invoke [{}]", program_id, depth + 1));
+        Ok(())
+    })
+}
+
+fn log_result(program_id: &Pubkey, result: &Result<(), RuntimeError>) {
+    log(match result {
+        Ok(()) => format!("Program {} success", program_id),
+        Err(error) => format!("Program {} failed: {}", program_id, error),
+    });
+}
+
+/// Runs `program_id` on `accounts` the way the loader would, and returns the accounts
+/// afterwards in the order of `unique`.
+pub(crate) fn execute(
+    program_id: &Pubkey,
+    accounts: &[InstructionAccount],
+    instruction_data: &[u8],
+) -> Result<Vec<Account>, RuntimeError> {
+    push_invocation(program_id)?;
+    let process_instruction = with_context(|context| {
+        context.return_data = None;
+        context.programs.get(program_id).copied()
+    })
+    .ok_or(RuntimeError::UnknownProgram(*program_id))?;
+
+    let mut input = serialize(program_id, accounts, instruction_data);
+    let input_ptr = input.as_mut_ptr();
+    let frame = Frame {
+        program_id: *program_id,
+        accounts: input
+            .accounts
+            .iter()
+            .zip(unique(accounts))
+            .map(|(serialized, instruction_account)| FrameAccount {
+                key: serialized.key,
+                capacity: serialized.capacity,
+                executable: instruction_account.account.executable,
+                rent_epoch: instruction_account.account.rent_epoch,
+                serialized: unsafe { input_ptr.add(serialized.owner_offset) },
+            })
+            .collect(),
+        pre: unique(accounts).cloned().collect(),
+    };
+    with_context(|context| context.frames.push(frame));
+    let result = {
+        let (input_program_id, infos, data) = unsafe { deserialize(input_ptr) };
+        process_instruction(input_program_id, &infos, data)
+    };
+    let frame = with_context(|context| context.frames.pop()).unwrap();
+    let post: Vec<Account> = frame.accounts.iter().map(FrameAccount::read).collect();
+
+    let result = result
+        .map_err(|error| RuntimeError::Program(*program_id, error))
+        .and_then(|()| verify(program_id, &frame.pre, &post));
+    log_result(program_id, &result);
+    result.map(|()| post)
+}
+
+/// The rules the runtime enforces on every program once it returns.
+fn verify(
+    program_id: &Pubkey,
+    pre: &[InstructionAccount],
+    post: &[Account],
+) -> Result<(), RuntimeError> {
+    let pre_total: u128 = pre.iter().map(|a| a.account.lamports as u128).sum();
+    let post_total: u128 = post.iter().map(|account| account.lamports as u128).sum();
+    if pre_total != post_total {
+        return Err(RuntimeError::UnbalancedInstruction);
+    }
+    pre.iter()
+        .zip(post)
+        .try_for_each(|(before, after)| verify_account(program_id, before, after))
+}
+
+fn verify_account(
+    program_id: &Pubkey,
+    instruction_account: &InstructionAccount,
+    after: &Account,
+) -> Result<(), RuntimeError> {
+    let before = &instruction_account.account;
+    let key = instruction_account.key;
+    if before == after {
+        return Ok(());
+    }
+    if !instruction_account.is_writable {
+        return Err(RuntimeError::ReadonlyAccountModified(key));
+    }
+    let spent = after.lamports < before.lamports;
+    let modified = after.data != before.data || after.owner != before.owner;
+    if before.owner != *program_id && (spent || modified) {
+        return Err(RuntimeError::ExternalAccountModified(key));
+    }
+    Ok(())
+}
+
+/// Runs `f` on the pre-state and the current state of each of `infos` in the executing
+/// program.
+fn for_each_pre_account(
+    infos: &[AccountInfo],
+    mut f: impl FnMut(&mut InstructionAccount, Account) -> Result<(), RuntimeError>,
+) -> Result<(), RuntimeError> {
+    with_context(|context| {
+        let frame = context.frames.last_mut().expect("no program is executing");
+        infos.iter().try_for_each(|info| {
+            let missing = RuntimeError::MissingAccount(*info.key);
+            let pre = frame.pre.iter_mut().find(|pre| pre.key == *info.key);
+            let account = frame.accounts.iter().find(|account| account.key == *info.key);
+            f(pre.ok_or_else(|| missing.clone())?, account.ok_or(missing)?.read())
+        })
+    })
+}
+
+/// Runs `f` on the account `key` of the executing program.
+fn with_frame_account<R>(
+    key: &Pubkey,
+    f: impl FnOnce(&FrameAccount) -> Result<R, RuntimeError>,
+) -> Result<R, RuntimeError> {
+    with_context(|context| {
+        let frame = context.frames.last().expect("no program is executing");
+        let account = frame.accounts.iter().find(|account| account.key == *key);
+        f(account.ok_or(RuntimeError::MissingAccount(*key))?)
+    })
+}
+
+/// Changes the data length of an account of the executing program, like `realloc`.
+pub(crate) fn resize(account: &AccountInfo, len: usize) -> Result<(), RuntimeError> {
+    let data = with_frame_account(account.key, |frame_account| {
+        if len > frame_account.capacity {
+            return Err(RuntimeError::InvalidRealloc(*account.key));
+        }
+        Ok(unsafe { serialization::resize(frame_account.serialized, len) })
+    })?;
+    *account.data.borrow_mut() = unsafe { slice::from_raw_parts_mut(data, len) };
+    Ok(())
+}
+
+/// Changes the owner of an account of the executing program.
+pub(crate) fn assign(key: &Pubkey, owner: &Pubkey) -> Result<(), RuntimeError> {
+    with_frame_account(key, |frame_account| {
+        unsafe { ptr::write_unaligned(frame_account.serialized as *mut Pubkey, *owner) };
+        Ok(())
+    })
+}
+
+/// Copies the state of an account after an invocation back into the caller.
+fn update(info: &AccountInfo, account: &Account) -> Result<(), RuntimeError> {
+    resize(info, account.data.len())?;
+    assign(info.key, &account.owner)?;
+    **info.lamports.borrow_mut() = account.lamports;
+    info.data.borrow_mut().copy_from_slice(&account.data);
+    Ok(())
+}
+
+fn invoke(
+    instruction: &Instruction,
+    account_infos: &[AccountInfo],
+    signers_seeds: &[&[&[u8]]],
+) -> Result<(), RuntimeError> {
+    let program_id = &instruction.program_id;
+    let caller = with_context(|context| {
+        context.frames.last().expect("no program is executing").program_id
+    });
+    // The invoked program must be one of the accounts of the caller
+    with_frame_account(program_id, |_| Ok(()))?;
+    let signers = signers_seeds
+        .iter()
+        .map(|seeds| Pubkey::create_program_address(seeds, &caller))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| RuntimeError::InvalidSeeds)?;
+
+    let mut callee_infos = Vec::with_capacity(instruction.accounts.len());
+    for meta in &instruction.accounts {
+        let metas = instruction.accounts.iter().filter(|m| m.pubkey == meta.pubkey);
+        let is_signer = metas.clone().any(|m| m.is_signer);
+        let is_writable = metas.clone().any(|m| m.is_writable);
+        let info = account_infos
+            .iter()
+            .find(|info| *info.key == meta.pubkey)
+            .ok_or(RuntimeError::MissingAccount(meta.pubkey))?;
+        if (is_writable && !info.is_writable)
+            || (is_signer && !info.is_signer && !signers.contains(info.key))
+        {
+            return Err(RuntimeError::PrivilegeEscalation(meta.pubkey));
+        }
+        let mut callee_info = info.clone();
+        callee_info.is_signer = is_signer;
+        callee_info.is_writable = is_writable;
+        callee_infos.push(callee_info);
+    }
+
+    // The caller may not pass on changes it was not allowed to make, and is verified
+    // against the accounts as the invocation left them once it returns
+    for_each_pre_account(&callee_infos, |pre, account| {
+        verify_account(&caller, pre, &account)
+    })?;
+    invoke_callee(instruction, &callee_infos)?;
+    for_each_pre_account(&callee_infos, |pre, account| {
+        pre.account = account;
+        Ok(())
+    })
+}
+
+fn invoke_callee(
+    instruction: &Instruction,
+    callee_infos: &[AccountInfo],
+) -> Result<(), RuntimeError> {
+    let program_id = &instruction.program_id;
+    if *program_id == system_program::id() {
+        push_invocation(program_id)?;
+        let result = system::process(callee_infos, &instruction.data);
+        log_result(program_id, &result);
+        return result;
+    }
+
+    let accounts: Vec<InstructionAccount> = callee_infos
+        .iter()
+        .map(|info| {
+            Ok(InstructionAccount {
+                key: *info.key,
+                is_signer: info.is_signer,
+                is_writable: info.is_writable,
+                account: with_frame_account(info.key, |account| Ok(account.read()))?,
+            })
+        })
+        .collect::<Result<_, RuntimeError>>()?;
+    let post = execute(program_id, &accounts, &instruction.data)?;
+    for (account, after) in unique(&accounts).zip(&post) {
+        if account.is_writable && account.account != *after {
+            let info = callee_infos.iter().find(|info| *info.key == account.key).unwrap();
+            update(info, after)?;
+        }
+    }
+    Ok(())
+}
+
+/// Fails the transaction with the first failed invocation, if there was one.
+pub(crate) fn take_error() -> Option<RuntimeError> {
+    with_context(|context| context.error.take())
+}
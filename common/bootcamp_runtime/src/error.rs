@@ -0,0 +1,51 @@
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+use thiserror::Error;
+
+use crate::MAX_INVOKE_DEPTH;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum RuntimeError {
+    #[error("Program {0} failed: {1}")]
+    Program(Pubkey, ProgramError),
+    #[error("Program {0} is not registered with the runtime")]
+    UnknownProgram(Pubkey),
+    #[error("Account {0} is missing from the invocation")]
+    MissingAccount(Pubkey),
+    #[error("Account {0} must sign the transaction")]
+    MissingSignature(Pubkey),
+    #[error("Account {0} is signer or writable in the invoked instruction but not the caller")]
+    PrivilegeEscalation(Pubkey),
+    #[error("Signer seeds do not derive a program address of the caller")]
+    InvalidSeeds,
+    #[error("Invocations are nested deeper than {}", MAX_INVOKE_DEPTH)]
+    CallDepth,
+    #[error("Program {0} was invoked again by another program")]
+    Reentrancy(Pubkey),
+    #[error("Sum of the account balances changed")]
+    UnbalancedInstruction,
+    #[error("Read-only account {0} was modified")]
+    ReadonlyAccountModified(Pubkey),
+    #[error("Account {0} was modified by a program that does not own it")]
+    ExternalAccountModified(Pubkey),
+    #[error("Account {0} grew by more than the permitted data increase")]
+    InvalidRealloc(Pubkey),
+    #[error("System instruction is not supported by the runtime")]
+    UnsupportedSystemInstruction,
+}
+
+/// What the invoking program sees when a cross-program invocation fails. The
+/// transaction fails with the `RuntimeError` either way.
+impl From<RuntimeError> for ProgramError {
+    fn from(e: RuntimeError) -> Self {
+        match e {
+            RuntimeError::Program(_, error) => error,
+            RuntimeError::MissingAccount(_) => ProgramError::NotEnoughAccountKeys,
+            RuntimeError::MissingSignature(_) | RuntimeError::PrivilegeEscalation(_) => {
+                ProgramError::MissingRequiredSignature
+            }
+            RuntimeError::UnknownProgram(_) => ProgramError::IncorrectProgramId,
+            RuntimeError::InvalidSeeds => ProgramError::InvalidSeeds,
+            _ => ProgramError::InvalidArgument,
+        }
+    }
+}
@@ -0,0 +1,243 @@
+//! Runs the bootcamp programs natively against in-memory accounts, without compiling
+//! them to BPF or starting a `solana-program-test` bank.
+//!
+//! Each program gets its accounts serialized the way the loader does, including the
+//! padding `realloc` grows into. Cross-program invocations to the system program,
+//! spl-token and any registered program are emulated, and the signer privileges of
+//! every invocation are checked against the caller and its PDA signer seeds.
+//!
+//! ```ignore
+//! let mut runtime = Runtime::new();
+//! runtime.add_program(count_tracker::id(), Processor::process_instruction);
+//! runtime.add_account(user, Account::new(LAMPORTS_PER_SOL, 0, &system_program::id()));
+//! runtime.process_instruction(&instruction, &[user])?;
+//! ```
+
+mod context;
+pub mod error;
+mod serialization;
+mod system_program;
+
+pub use error::RuntimeError;
+
+use solana_program::{
+    bpf_loader,
+    clock::{Clock, Slot},
+    entrypoint::ProcessInstruction,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_program as system, sysvar,
+};
+
+use std::collections::HashMap;
+
+use crate::context::Context;
+use crate::serialization::{unique, InstructionAccount};
+
+/// Invocations nested deeper than this fail, counting the outermost program.
+pub const MAX_INVOKE_DEPTH: usize = 4;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Account {
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub owner: Pubkey,
+    pub executable: bool,
+    pub rent_epoch: u64,
+}
+
+impl Account {
+    pub fn new(lamports: u64, space: usize, owner: &Pubkey) -> Self {
+        Self {
+            lamports,
+            data: vec![0; space],
+            owner: *owner,
+            ..Self::default()
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Runtime {
+    accounts: HashMap<Pubkey, Account>,
+    programs: HashMap<Pubkey, ProcessInstruction>,
+    rent: Rent,
+    clock: Clock,
+    logs: Vec<String>,
+    return_data: Option<(Pubkey, Vec<u8>)>,
+}
+
+impl Runtime {
+    /// A runtime with the system program, spl-token and the rent and clock sysvars.
+    pub fn new() -> Self {
+        let mut runtime = Self {
+            accounts: HashMap::new(),
+            programs: HashMap::new(),
+            rent: Rent::default(),
+            clock: Clock::default(),
+            logs: vec![],
+            return_data: None,
+        };
+        runtime.add_account(
+            system::id(),
+            Account {
+                lamports: 1,
+                executable: true,
+                ..Account::default()
+            },
+        );
+        runtime.add_program(spl_token::id(), spl_token::processor::Processor::process);
+        runtime.update_sysvars();
+        runtime
+    }
+
+    /// Registers the processor of a program, e.g. `Processor::process_instruction`.
+    pub fn add_program(&mut self, program_id: Pubkey, process_instruction: ProcessInstruction) {
+        self.programs.insert(program_id, process_instruction);
+        self.add_account(
+            program_id,
+            Account {
+                lamports: 1,
+                owner: bpf_loader::id(),
+                executable: true,
+                ..Account::default()
+            },
+        );
+    }
+
+    pub fn add_account(&mut self, key: Pubkey, account: Account) {
+        self.accounts.insert(key, account);
+    }
+
+    /// Adds an account holding exactly the rent exemption for `space` bytes.
+    pub fn add_rent_exempt_account(&mut self, key: Pubkey, space: usize, owner: &Pubkey) {
+        let lamports = self.rent.minimum_balance(space);
+        self.add_account(key, Account::new(lamports, space, owner));
+    }
+
+    pub fn account(&self, key: &Pubkey) -> Option<&Account> {
+        self.accounts.get(key)
+    }
+
+    pub fn rent(&self) -> &Rent {
+        &self.rent
+    }
+
+    pub fn clock(&self) -> &Clock {
+        &self.clock
+    }
+
+    pub fn set_clock(&mut self, clock: Clock) {
+        self.clock = clock;
+        self.update_sysvars();
+    }
+
+    pub fn warp_to_slot(&mut self, slot: Slot) {
+        self.set_clock(Clock {
+            slot,
+            ..self.clock.clone()
+        });
+    }
+
+    /// Logs of the last transaction.
+    pub fn logs(&self) -> &[String] {
+        &self.logs
+    }
+
+    /// Return data of the last transaction.
+    pub fn return_data(&self) -> Option<&(Pubkey, Vec<u8>)> {
+        self.return_data.as_ref()
+    }
+
+    fn update_sysvars(&mut self) {
+        for (key, data) in [
+            (sysvar::rent::id(), bincode::serialize(&self.rent).unwrap()),
+            (sysvar::clock::id(), bincode::serialize(&self.clock).unwrap()),
+        ] {
+            let lamports = self.rent.minimum_balance(data.len());
+            self.add_account(
+                key,
+                Account {
+                    lamports,
+                    data,
+                    owner: sysvar::id(),
+                    ..Account::default()
+                },
+            );
+        }
+    }
+
+    pub fn process_instruction(
+        &mut self,
+        instruction: &Instruction,
+        signers: &[Pubkey],
+    ) -> Result<(), RuntimeError> {
+        self.process_transaction(std::slice::from_ref(instruction), signers)
+    }
+
+    /// Processes `instructions` atomically: if one fails, none of them changes an account.
+    pub fn process_transaction(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[Pubkey],
+    ) -> Result<(), RuntimeError> {
+        let snapshot = self.accounts.clone();
+        context::enter(Context::new(
+            self.programs.clone(),
+            self.rent,
+            self.clock.clone(),
+        ));
+        let result = instructions
+            .iter()
+            .try_for_each(|instruction| self.process(instruction, signers));
+        let context = context::exit();
+        self.logs = context.logs;
+        self.return_data = context.return_data;
+        if result.is_err() {
+            self.accounts = snapshot;
+        }
+        result
+    }
+
+    fn process(
+        &mut self,
+        instruction: &Instruction,
+        signers: &[Pubkey],
+    ) -> Result<(), RuntimeError> {
+        let mut accounts = Vec::with_capacity(instruction.accounts.len());
+        for meta in &instruction.accounts {
+            let metas = instruction.accounts.iter().filter(|m| m.pubkey == meta.pubkey);
+            let is_signer = metas.clone().any(|m| m.is_signer);
+            let is_writable = metas.clone().any(|m| m.is_writable);
+            if is_signer && !signers.contains(&meta.pubkey) {
+                return Err(RuntimeError::MissingSignature(meta.pubkey));
+            }
+            accounts.push(InstructionAccount {
+                key: meta.pubkey,
+                is_signer,
+                is_writable,
+                account: self.accounts.get(&meta.pubkey).cloned().unwrap_or_default(),
+            });
+        }
+
+        let result = context::execute(&instruction.program_id, &accounts, &instruction.data);
+        if let Some(error) = context::take_error() {
+            return Err(error);
+        }
+        for (account, after) in unique(&accounts).zip(result?) {
+            if after.lamports == 0 {
+                self.accounts.remove(&account.key);
+            } else {
+                self.accounts.insert(account.key, after);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for Runtime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
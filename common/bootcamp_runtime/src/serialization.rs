@@ -0,0 +1,133 @@
+//! Lays out the input of a program the way the BPF loader does, so the program can be
+//! handed its accounts by `solana_program::entrypoint::deserialize`. The data of every
+//! account is followed by `MAX_PERMITTED_DATA_INCREASE` bytes for `realloc`.
+
+use solana_program::{entrypoint::MAX_PERMITTED_DATA_INCREASE, pubkey::Pubkey};
+
+use std::{mem, ptr, slice};
+
+use crate::Account;
+
+/// An account as passed to one instruction. If the account appears more than once, the
+/// privileges of all its occurrences are merged.
+#[derive(Clone, Debug)]
+pub(crate) struct InstructionAccount {
+    pub key: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+    pub account: Account,
+}
+
+/// Where an account ended up in the input buffer.
+pub(crate) struct SerializedAccount {
+    pub key: Pubkey,
+    pub owner_offset: usize,
+    /// Largest length the data can be resized to
+    pub capacity: usize,
+}
+
+pub(crate) struct Input {
+    // u64 words keep the buffer 8-byte aligned, so account data can be cast in place
+    buffer: Vec<u64>,
+    pub accounts: Vec<SerializedAccount>,
+}
+
+impl Input {
+    pub fn as_mut_ptr(&mut self) -> *mut u8 {
+        self.buffer.as_mut_ptr() as *mut u8
+    }
+}
+
+/// The first occurrence of every account, in instruction order.
+pub(crate) fn unique(
+    accounts: &[InstructionAccount],
+) -> impl Iterator<Item = &InstructionAccount> {
+    accounts
+        .iter()
+        .enumerate()
+        .filter(|(i, account)| accounts[..*i].iter().all(|other| other.key != account.key))
+        .map(|(_, account)| account)
+}
+
+// Offsets of the fields of a serialized account from its owner
+const LAMPORTS: usize = 32;
+const DATA_LEN: usize = LAMPORTS + 8;
+const DATA: usize = DATA_LEN + 8;
+
+/// Reads the account whose owner is at `owner` in an input buffer.
+///
+/// # Safety
+/// `owner` must point to the owner of an account serialized by `serialize`.
+pub(crate) unsafe fn read(owner: *const u8, executable: bool, rent_epoch: u64) -> Account {
+    let len = ptr::read_unaligned(owner.add(DATA_LEN) as *const u64) as usize;
+    Account {
+        lamports: ptr::read_unaligned(owner.add(LAMPORTS) as *const u64),
+        data: slice::from_raw_parts(owner.add(DATA), len).to_vec(),
+        owner: ptr::read_unaligned(owner as *const Pubkey),
+        executable,
+        rent_epoch,
+    }
+}
+
+/// Sets the data length of the account whose owner is at `owner` and returns its data.
+///
+/// # Safety
+/// `owner` must point to the owner of an account serialized by `serialize`, with room
+/// for `len` bytes of data.
+pub(crate) unsafe fn resize(owner: *mut u8, len: usize) -> *mut u8 {
+    ptr::write_unaligned(owner.add(DATA_LEN) as *mut u64, len as u64);
+    owner.add(DATA)
+}
+
+pub(crate) fn serialize(
+    program_id: &Pubkey,
+    accounts: &[InstructionAccount],
+    instruction_data: &[u8],
+) -> Input {
+    let mut bytes = Vec::new();
+    let mut serialized = Vec::new();
+    bytes.extend_from_slice(&(accounts.len() as u64).to_le_bytes());
+    for (i, instruction_account) in accounts.iter().enumerate() {
+        if let Some(index) = accounts[..i]
+            .iter()
+            .position(|other| other.key == instruction_account.key)
+        {
+            bytes.push(index as u8);
+            bytes.extend_from_slice(&[0; 7]);
+            continue;
+        }
+        let account = &instruction_account.account;
+        bytes.push(u8::MAX);
+        bytes.push(instruction_account.is_signer as u8);
+        bytes.push(instruction_account.is_writable as u8);
+        bytes.push(account.executable as u8);
+        // Padding, newer runtimes keep the original data length here
+        bytes.extend_from_slice(&(account.data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(instruction_account.key.as_ref());
+        let owner_offset = bytes.len();
+        bytes.extend_from_slice(account.owner.as_ref());
+        bytes.extend_from_slice(&account.lamports.to_le_bytes());
+        bytes.extend_from_slice(&(account.data.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&account.data);
+        bytes.resize(bytes.len() + MAX_PERMITTED_DATA_INCREASE, 0);
+        bytes.resize(bytes.len() + (8 - bytes.len() % 8) % 8, 0);
+        bytes.extend_from_slice(&account.rent_epoch.to_le_bytes());
+        serialized.push(SerializedAccount {
+            key: instruction_account.key,
+            owner_offset,
+            capacity: account.data.len() + MAX_PERMITTED_DATA_INCREASE,
+        });
+    }
+    bytes.extend_from_slice(&(instruction_data.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(instruction_data);
+    bytes.extend_from_slice(program_id.as_ref());
+
+    let mut buffer = vec![0u64; bytes.len() / mem::size_of::<u64>() + 1];
+    unsafe {
+        ptr::copy_nonoverlapping(bytes.as_ptr(), buffer.as_mut_ptr() as *mut u8, bytes.len());
+    }
+    Input {
+        buffer,
+        accounts: serialized,
+    }
+}
@@ -0,0 +1,111 @@
+//! The instructions of the system program that the bootcamp programs invoke.
+
+use solana_program::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction::{SystemError, SystemInstruction, MAX_PERMITTED_DATA_LENGTH},
+    system_program,
+};
+
+use crate::{context, RuntimeError};
+
+fn error(error: ProgramError) -> RuntimeError {
+    RuntimeError::Program(system_program::id(), error)
+}
+
+fn system_error(e: SystemError) -> RuntimeError {
+    error(ProgramError::Custom(e as u32))
+}
+
+fn account<'a, 'b>(
+    accounts: &'b [AccountInfo<'a>],
+    index: usize,
+) -> Result<&'b AccountInfo<'a>, RuntimeError> {
+    accounts
+        .get(index)
+        .ok_or_else(|| error(ProgramError::NotEnoughAccountKeys))
+}
+
+fn expect_signer(account: &AccountInfo) -> Result<(), RuntimeError> {
+    if !account.is_signer {
+        return Err(error(ProgramError::MissingRequiredSignature));
+    }
+    Ok(())
+}
+
+fn expect_writable(account: &AccountInfo) -> Result<(), RuntimeError> {
+    if !account.is_writable {
+        return Err(RuntimeError::ReadonlyAccountModified(*account.key));
+    }
+    Ok(())
+}
+
+fn transfer(from: &AccountInfo, to: &AccountInfo, lamports: u64) -> Result<(), RuntimeError> {
+    expect_signer(from)?;
+    expect_writable(from)?;
+    expect_writable(to)?;
+    if !from.data_is_empty() {
+        return Err(error(ProgramError::InvalidArgument));
+    }
+    if *from.owner != system_program::id() {
+        return Err(RuntimeError::ExternalAccountModified(*from.key));
+    }
+    if lamports > from.lamports() {
+        return Err(system_error(SystemError::ResultWithNegativeLamports));
+    }
+    **from.lamports.borrow_mut() -= lamports;
+    **to.lamports.borrow_mut() += lamports;
+    Ok(())
+}
+
+fn allocate(account: &AccountInfo, space: u64) -> Result<(), RuntimeError> {
+    expect_signer(account)?;
+    expect_writable(account)?;
+    if !account.data_is_empty() || *account.owner != system_program::id() {
+        return Err(system_error(SystemError::AccountAlreadyInUse));
+    }
+    if space > MAX_PERMITTED_DATA_LENGTH {
+        return Err(system_error(SystemError::InvalidAccountDataLength));
+    }
+    context::resize(account, space as usize)
+}
+
+fn assign(account: &AccountInfo, owner: &Pubkey) -> Result<(), RuntimeError> {
+    if account.owner == owner {
+        return Ok(());
+    }
+    expect_signer(account)?;
+    expect_writable(account)?;
+    if *account.owner != system_program::id() {
+        return Err(RuntimeError::ExternalAccountModified(*account.key));
+    }
+    context::assign(account.key, owner)
+}
+
+pub(crate) fn process(accounts: &[AccountInfo], data: &[u8]) -> Result<(), RuntimeError> {
+    let instruction = bincode::deserialize(data)
+        .map_err(|_| error(ProgramError::InvalidInstructionData))?;
+    match instruction {
+        SystemInstruction::CreateAccount {
+            lamports,
+            space,
+            owner,
+        } => {
+            let from = account(accounts, 0)?;
+            let to = account(accounts, 1)?;
+            if to.lamports() > 0 {
+                return Err(system_error(SystemError::AccountAlreadyInUse));
+            }
+            allocate(to, space)?;
+            assign(to, &owner)?;
+            transfer(from, to, lamports)
+        }
+        SystemInstruction::Assign { owner } => assign(account(accounts, 0)?, &owner),
+        SystemInstruction::Transfer { lamports } => {
+            transfer(account(accounts, 0)?, account(accounts, 1)?, lamports)
+        }
+        SystemInstruction::Allocate { space } => allocate(account(accounts, 0)?, space),
+        _ => Err(RuntimeError::UnsupportedSystemInstruction),
+    }
+}
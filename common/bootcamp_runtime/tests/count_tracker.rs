@@ -0,0 +1,323 @@
+use {
+    authorized_counter::{
+        error::CounterError,
+        instruction::CounterInstruction,
+        state::{total_count, Counter, Shard},
+    },
+    bootcamp_accounts::BorshAccount,
+    bootcamp_runtime::{Account, Runtime, RuntimeError},
+    count_tracker::{
+        instruction,
+        state::{Leaderboard, Tracker},
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        instruction::{AccountMeta, Instruction},
+        native_token::LAMPORTS_PER_SOL,
+        program::{invoke, invoke_signed},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+        system_program,
+    },
+};
+
+const COUNTER_LEN: usize = 40;
+
+struct Setup {
+    runtime: Runtime,
+    counter: Pubkey,
+    authority: Pubkey,
+    leaderboard: Pubkey,
+}
+
+fn setup() -> Setup {
+    let mut runtime = Runtime::new();
+    runtime.add_program(
+        authorized_counter::id(),
+        authorized_counter::processor::Processor::process_instruction,
+    );
+    runtime.add_program(
+        count_tracker::id(),
+        count_tracker::processor::Processor::process_instruction,
+    );
+    let counter = Pubkey::new_unique();
+    runtime.add_rent_exempt_account(counter, COUNTER_LEN, &authorized_counter::id());
+    let (authority, _) = Pubkey::find_program_address(&[counter.as_ref()], &count_tracker::id());
+    let (leaderboard, _) = Pubkey::find_program_address(
+        &[b"leaderboard", counter.as_ref()],
+        &count_tracker::id(),
+    );
+    Setup {
+        runtime,
+        counter,
+        authority,
+        leaderboard,
+    }
+}
+
+fn add_user(runtime: &mut Runtime) -> Pubkey {
+    let user = Pubkey::new_unique();
+    runtime.add_account(user, Account::new(LAMPORTS_PER_SOL, 0, &system_program::id()));
+    user
+}
+
+fn tracker(counter: &Pubkey, user: &Pubkey) -> Pubkey {
+    let seeds = &[user.as_ref(), counter.as_ref()];
+    Pubkey::find_program_address(seeds, &count_tracker::id()).0
+}
+
+fn counter_state(runtime: &Runtime, counter: &Pubkey) -> Counter {
+    Counter::unpack_any(&runtime.account(counter).unwrap().data).unwrap()
+}
+
+#[test]
+fn test_increment_through_cpi() {
+    let Setup {
+        mut runtime,
+        counter,
+        authority,
+        leaderboard,
+    } = setup();
+    let id = count_tracker::id();
+    let alice = add_user(&mut runtime);
+    let bob = add_user(&mut runtime);
+
+    let ix = instruction::initialize_leaderboard(id, leaderboard, alice, counter).unwrap();
+    runtime.process_instruction(&ix, &[alice]).unwrap();
+    for user in [alice, bob] {
+        let ix = instruction::initialize(id, tracker(&counter, &user), user, authority, counter)
+            .unwrap();
+        runtime.process_instruction(&ix, &[user]).unwrap();
+    }
+    let increment = |user| {
+        let tracker = tracker(&counter, &user);
+        instruction::increment(id, tracker, user, counter, authority, leaderboard).unwrap()
+    };
+    runtime
+        .process_transaction(&[increment(alice), increment(alice)], &[alice])
+        .unwrap();
+    runtime.process_instruction(&increment(bob), &[bob]).unwrap();
+    assert_eq!(
+        runtime.return_data(),
+        Some(&(id, 1u64.to_le_bytes().to_vec()))
+    );
+
+    let tracker_account = runtime.account(&tracker(&counter, &alice)).unwrap();
+    let alice_tracker = Tracker::unpack(&tracker_account.data).unwrap();
+    assert_eq!((alice_tracker.count, alice_tracker.global_count), (2, 2));
+    assert_eq!(tracker_account.lamports, runtime.rent().minimum_balance(Tracker::LEN));
+    let global = counter_state(&runtime, &counter);
+    assert_eq!(global.count, 3);
+    assert_eq!(global.authority, authority);
+    let bob_tracker = runtime.account(&tracker(&counter, &bob)).unwrap();
+    assert_eq!(Tracker::unpack(&bob_tracker.data).unwrap().global_count, 3);
+    let get = authorized_counter::instruction::get(authorized_counter::id(), counter).unwrap();
+    runtime.process_instruction(&get, &[]).unwrap();
+    assert_eq!(
+        runtime.return_data(),
+        Some(&(authorized_counter::id(), 3u64.to_le_bytes().to_vec()))
+    );
+    let leaderboard = Leaderboard::unpack(&runtime.account(&leaderboard).unwrap().data).unwrap();
+    let ranked: Vec<_> = leaderboard.ranked().iter().map(|e| (e.user, e.count)).collect();
+    assert_eq!(ranked, vec![(alice, 2), (bob, 1)]);
+}
+
+#[test]
+fn test_increment_shards_through_cpi() {
+    let Setup {
+        mut runtime,
+        counter,
+        authority,
+        leaderboard,
+    } = setup();
+    let id = count_tracker::id();
+    let alice = add_user(&mut runtime);
+    let bob = add_user(&mut runtime);
+    let ix = instruction::initialize_leaderboard(id, leaderboard, alice, counter).unwrap();
+    runtime.process_instruction(&ix, &[alice]).unwrap();
+    for user in [alice, bob] {
+        let ix = instruction::initialize(id, tracker(&counter, &user), user, authority, counter)
+            .unwrap();
+        runtime.process_instruction(&ix, &[user]).unwrap();
+    }
+    let initialize_shards = |payer| {
+        [0, 1].map(|index| {
+            instruction::initialize_shard(id, payer, counter, authority, index).unwrap()
+        })
+    };
+    // The authority PDA claims the counter on the first increment through a tracker
+    assert_eq!(
+        runtime.process_transaction(&initialize_shards(bob), &[bob]),
+        Err(RuntimeError::Program(
+            authorized_counter::id(),
+            CounterError::UnclaimedCounter.into()
+        ))
+    );
+    let alice_tracker = tracker(&counter, &alice);
+    let ix = instruction::increment(id, alice_tracker, alice, counter, authority, leaderboard)
+        .unwrap();
+    runtime.process_instruction(&ix, &[alice]).unwrap();
+    runtime
+        .process_transaction(&initialize_shards(bob), &[bob])
+        .unwrap();
+
+    let increment_shard = |user, index| {
+        let tracker = tracker(&counter, &user);
+        instruction::increment_shard(id, tracker, user, counter, authority, leaderboard, index)
+            .unwrap()
+    };
+    runtime
+        .process_instruction(&increment_shard(alice, 0), &[alice])
+        .unwrap();
+    runtime
+        .process_instruction(&increment_shard(bob, 1), &[bob])
+        .unwrap();
+    runtime
+        .process_instruction(&increment_shard(alice, 1), &[alice])
+        .unwrap();
+    assert_eq!(
+        runtime.return_data(),
+        Some(&(id, 3u64.to_le_bytes().to_vec()))
+    );
+
+    // Shards don't touch the counter or the global count in the trackers
+    let global = counter_state(&runtime, &counter);
+    assert_eq!(global.count, 1);
+    let shards: Vec<_> = [0, 1]
+        .map(|index| {
+            let (shard, _) = Shard::find_address(&authorized_counter::id(), &counter, index);
+            Shard::unpack(&runtime.account(&shard).unwrap().data).unwrap()
+        })
+        .to_vec();
+    assert_eq!(shards.iter().map(|shard| shard.count).collect::<Vec<_>>(), vec![1, 2]);
+    assert!(shards.iter().all(|shard| shard.authority == authority));
+    assert_eq!(total_count(&global, &shards), Some(4));
+    let alice_tracker = runtime.account(&tracker(&counter, &alice)).unwrap();
+    let alice_tracker = Tracker::unpack(&alice_tracker.data).unwrap();
+    assert_eq!((alice_tracker.count, alice_tracker.global_count), (3, 1));
+    let leaderboard = Leaderboard::unpack(&runtime.account(&leaderboard).unwrap().data).unwrap();
+    let ranked: Vec<_> = leaderboard.ranked().iter().map(|e| (e.user, e.count)).collect();
+    assert_eq!(ranked, vec![(alice, 3), (bob, 1)]);
+
+    // The index has to match the shard
+    let mut ix = increment_shard(bob, 0);
+    ix.accounts[5] = increment_shard(bob, 1).accounts[5].clone();
+    assert_eq!(
+        runtime.process_instruction(&ix, &[bob]),
+        Err(RuntimeError::Program(id, ProgramError::InvalidArgument))
+    );
+}
+
+#[test]
+fn test_failed_transaction_is_rolled_back() {
+    let Setup {
+        mut runtime,
+        counter,
+        authority,
+        leaderboard,
+    } = setup();
+    let id = count_tracker::id();
+    let user = add_user(&mut runtime);
+    let tracker = tracker(&counter, &user);
+
+    let init_leaderboard =
+        instruction::initialize_leaderboard(id, leaderboard, user, counter).unwrap();
+    let init_tracker = instruction::initialize(id, tracker, user, authority, counter).unwrap();
+    // The second leaderboard allocation fails, which undoes the first one
+    let result = runtime.process_transaction(
+        &[init_leaderboard.clone(), init_tracker, init_leaderboard],
+        &[user],
+    );
+    assert!(matches!(result, Err(RuntimeError::Program(id, _)) if id == system_program::id()));
+    assert!(runtime.account(&leaderboard).is_none());
+    assert!(runtime.account(&tracker).is_none());
+    assert_eq!(runtime.account(&user).unwrap().lamports, LAMPORTS_PER_SOL);
+}
+
+/// Increments an authorized counter through a CPI, signing for the authority PDA of
+/// [counter] only if the instruction data is [1].
+fn proxy(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [counter_program, counter, authority] = match accounts {
+        [a, b, c] => [a, b, c],
+        _ => return Err(ProgramError::NotEnoughAccountKeys),
+    };
+    let ix = authorized_counter::instruction::increment(
+        *counter_program.key,
+        *counter.key,
+        *authority.key,
+        CounterInstruction::Increment,
+    )?;
+    let infos = &[counter.clone(), authority.clone(), counter_program.clone()];
+    if data == [1] {
+        let (_, bump) = Pubkey::find_program_address(&[counter.key.as_ref()], program_id);
+        invoke_signed(&ix, infos, &[&[counter.key.as_ref(), &[bump]]])
+    } else {
+        invoke(&ix, infos)
+    }
+}
+
+#[test]
+fn test_cpi_requires_signer_seeds() {
+    let Setup {
+        mut runtime,
+        counter,
+        ..
+    } = setup();
+    let proxy_id = Pubkey::new_unique();
+    runtime.add_program(proxy_id, proxy);
+    let (authority, _) = Pubkey::find_program_address(&[counter.as_ref()], &proxy_id);
+    let ix = |sign| {
+        Instruction::new_with_bytes(
+            proxy_id,
+            &[sign],
+            vec![
+                AccountMeta::new_readonly(authorized_counter::id(), false),
+                AccountMeta::new(counter, false),
+                AccountMeta::new_readonly(authority, false),
+            ],
+        )
+    };
+
+    assert_eq!(
+        runtime.process_instruction(&ix(0), &[]),
+        Err(RuntimeError::PrivilegeEscalation(authority))
+    );
+    assert_eq!(counter_state(&runtime, &counter).count, 0);
+    runtime.process_instruction(&ix(1), &[]).unwrap();
+    assert_eq!(counter_state(&runtime, &counter).count, 1);
+}
+
+/// Moves a lamport from the first account to the second, whoever owns them.
+fn thief(_program_id: &Pubkey, accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+    **accounts[0].try_borrow_mut_lamports()? -= 1;
+    **accounts[1].try_borrow_mut_lamports()? += 1;
+    Ok(())
+}
+
+#[test]
+fn test_runtime_rules() {
+    let mut runtime = Runtime::new();
+    let thief_id = Pubkey::new_unique();
+    runtime.add_program(thief_id, thief);
+    let victim = add_user(&mut runtime);
+    let receiver = add_user(&mut runtime);
+
+    let steal = |victim_meta| {
+        let accounts = vec![victim_meta, AccountMeta::new(receiver, false)];
+        Instruction::new_with_bytes(thief_id, &[], accounts)
+    };
+    assert_eq!(
+        runtime.process_instruction(&steal(AccountMeta::new(victim, false)), &[]),
+        Err(RuntimeError::ExternalAccountModified(victim))
+    );
+    assert_eq!(
+        runtime.process_instruction(&steal(AccountMeta::new_readonly(victim, false)), &[]),
+        Err(RuntimeError::ReadonlyAccountModified(victim))
+    );
+    assert_eq!(
+        runtime.process_instruction(&steal(AccountMeta::new(victim, true)), &[]),
+        Err(RuntimeError::MissingSignature(victim))
+    );
+    assert_eq!(runtime.account(&victim).unwrap().lamports, LAMPORTS_PER_SOL);
+}
@@ -0,0 +1,279 @@
+use {
+    bootcamp_accounts::ZeroCopy,
+    bootcamp_runtime::{Account, Runtime, RuntimeError, MAX_INVOKE_DEPTH},
+    borsh::BorshSerialize,
+    marketplace::{
+        error::RuntimeLimitationError,
+        instruction::{RuntimeLimitationInstruction, StackMode},
+        processor::Processor,
+        state::{Marketplace, Offer},
+        vec_account::{VecAccount, VecHeader},
+    },
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        native_token::LAMPORTS_PER_SOL,
+        program_option::COption,
+        program_pack::Pack,
+        pubkey::Pubkey,
+        system_program, sysvar,
+    },
+    spl_token::state::{Account as TokenAccount, AccountState, Mint},
+};
+
+fn setup() -> (Runtime, Pubkey) {
+    let mut runtime = Runtime::new();
+    let program_id = Pubkey::new_unique();
+    runtime.add_program(program_id, Processor::process_instruction);
+    (runtime, program_id)
+}
+
+fn add_user(runtime: &mut Runtime) -> Pubkey {
+    let user = Pubkey::new_unique();
+    runtime.add_account(user, Account::new(LAMPORTS_PER_SOL, 0, &system_program::id()));
+    user
+}
+
+fn add_packed<T: Pack>(runtime: &mut Runtime, state: T) -> Pubkey {
+    let key = Pubkey::new_unique();
+    runtime.add_rent_exempt_account(key, T::LEN, &spl_token::id());
+    let mut account = runtime.account(&key).unwrap().clone();
+    T::pack(state, &mut account.data).unwrap();
+    runtime.add_account(key, account);
+    key
+}
+
+fn add_token_account(runtime: &mut Runtime, mint: Pubkey, owner: Pubkey, amount: u64) -> Pubkey {
+    add_packed(
+        runtime,
+        TokenAccount {
+            mint,
+            owner,
+            amount,
+            state: AccountState::Initialized,
+            ..TokenAccount::default()
+        },
+    )
+}
+
+fn token_amount(runtime: &Runtime, key: &Pubkey) -> u64 {
+    TokenAccount::unpack(&runtime.account(key).unwrap().data).unwrap().amount
+}
+
+fn instruction(
+    program_id: Pubkey,
+    instruction: RuntimeLimitationInstruction,
+    accounts: Vec<AccountMeta>,
+) -> Instruction {
+    Instruction::new_with_bytes(program_id, &instruction.try_to_vec().unwrap(), accounts)
+}
+
+#[test]
+fn test_post_and_cancel_offer() {
+    let (mut runtime, program_id) = setup();
+    let seller = add_user(&mut runtime);
+    let marketplace = Pubkey::new_unique();
+    runtime.add_rent_exempt_account(marketplace, Marketplace::LEN, &program_id);
+    let ix = instruction(
+        program_id,
+        RuntimeLimitationInstruction::ZeroCopy,
+        vec![
+            AccountMeta::new(marketplace, false),
+            AccountMeta::new_readonly(seller, false),
+        ],
+    );
+    runtime.process_instruction(&ix, &[]).unwrap();
+
+    let mint = Mint {
+        mint_authority: COption::Some(seller),
+        supply: 10,
+        is_initialized: true,
+        ..Mint::default()
+    };
+    let offer_mint = add_packed(&mut runtime, mint);
+    let buyer_mint = add_packed(&mut runtime, mint);
+    let seller_token_account = add_token_account(&mut runtime, offer_mint, seller, 10);
+    let (vault, _) = Pubkey::find_program_address(
+        &[b"vault", marketplace.as_ref(), offer_mint.as_ref()],
+        &program_id,
+    );
+
+    let post_offer = instruction(
+        program_id,
+        RuntimeLimitationInstruction::PostOffer {
+            offer_amount: 4,
+            buyer_amount: 100,
+        },
+        vec![
+            AccountMeta::new(marketplace, false),
+            AccountMeta::new(seller, true),
+            AccountMeta::new(seller_token_account, false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new_readonly(offer_mint, false),
+            AccountMeta::new_readonly(buyer_mint, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+    );
+    runtime.process_instruction(&post_offer, &[seller]).unwrap();
+
+    // The vault is created with a system CPI signed by its PDA
+    let vault_account = runtime.account(&vault).unwrap();
+    assert_eq!(vault_account.owner, spl_token::id());
+    assert_eq!(
+        vault_account.lamports,
+        runtime.rent().minimum_balance(TokenAccount::LEN)
+    );
+    assert_eq!(TokenAccount::unpack(&vault_account.data).unwrap().owner, vault);
+    assert_eq!(token_amount(&runtime, &vault), 4);
+    assert_eq!(token_amount(&runtime, &seller_token_account), 6);
+    let state = Marketplace::read(&runtime.account(&marketplace).unwrap().data).unwrap();
+    assert!(state.is_occupied(0));
+    assert_eq!(state.offers[0].seller, seller);
+    assert_eq!(state.offers[0].buyer_amount, 100);
+
+    let cancel_offer = instruction(
+        program_id,
+        RuntimeLimitationInstruction::CancelOffer { slot: 0 },
+        vec![
+            AccountMeta::new(marketplace, false),
+            AccountMeta::new_readonly(seller, true),
+            AccountMeta::new(seller_token_account, false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+    );
+    runtime.process_instruction(&cancel_offer, &[seller]).unwrap();
+    assert_eq!(token_amount(&runtime, &vault), 0);
+    assert_eq!(token_amount(&runtime, &seller_token_account), 10);
+    let state = Marketplace::read(&runtime.account(&marketplace).unwrap().data).unwrap();
+    assert!(!state.is_occupied(0));
+
+    // The vault only releases tokens when the program signs for it
+    let steal = spl_token::instruction::transfer(
+        &spl_token::id(),
+        &vault,
+        &seller_token_account,
+        &vault,
+        &[],
+        0,
+    )
+    .unwrap();
+    assert_eq!(
+        runtime.process_instruction(&steal, &[]),
+        Err(RuntimeError::MissingSignature(vault))
+    );
+}
+
+#[test]
+fn test_zero_copy_reruns_on_initialized_marketplace() {
+    let (mut runtime, program_id) = setup();
+    let marketplace = Pubkey::new_unique();
+    runtime.add_rent_exempt_account(marketplace, Marketplace::LEN, &program_id);
+    let experiments = [
+        RuntimeLimitationInstruction::ZeroCopy,
+        RuntimeLimitationInstruction::Stack {
+            mode: StackMode::ZeroCopy,
+        },
+        RuntimeLimitationInstruction::ZeroCopy,
+    ];
+    for experiment in experiments {
+        let user = add_user(&mut runtime);
+        let ix = instruction(
+            program_id,
+            experiment,
+            vec![
+                AccountMeta::new(marketplace, false),
+                AccountMeta::new_readonly(user, false),
+            ],
+        );
+        runtime.process_instruction(&ix, &[]).unwrap();
+        let state = Marketplace::read(&runtime.account(&marketplace).unwrap().data).unwrap();
+        assert_eq!(state.user, user);
+    }
+}
+
+#[test]
+fn test_vec_account_realloc() {
+    let (mut runtime, program_id) = setup();
+    let authority = add_user(&mut runtime);
+    let offers = Pubkey::new_unique();
+    let space = VecAccount::<Offer>::space(0);
+    runtime.add_rent_exempt_account(offers, space, &program_id);
+    let vec_instruction = |instruction_kind| {
+        instruction(
+            program_id,
+            instruction_kind,
+            vec![
+                AccountMeta::new(offers, false),
+                AccountMeta::new(authority, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )
+    };
+    let push = |n| {
+        vec_instruction(RuntimeLimitationInstruction::Push {
+            offers: vec![Offer::default(); n],
+        })
+    };
+
+    runtime.process_instruction(&push(2), &[authority]).unwrap();
+    runtime.process_instruction(&push(1), &[authority]).unwrap();
+    let account = runtime.account(&offers).unwrap();
+    let header = VecHeader::read(&account.data).unwrap();
+    assert_eq!((header.len, header.capacity), (3, 4));
+    assert_eq!(account.data.len(), VecAccount::<Offer>::space(4));
+    // The authority tops up the rent exemption through the system program
+    assert_eq!(account.lamports, runtime.rent().minimum_balance(account.data.len()));
+
+    // One instruction can grow the account by at most 10KB
+    let too_many = 10 * 1024 / std::mem::size_of::<Offer>() + 2;
+    assert_eq!(
+        runtime.process_instruction(&push(too_many), &[authority]),
+        Err(RuntimeError::Program(
+            program_id,
+            RuntimeLimitationError::GrowthLimitExceeded.into()
+        ))
+    );
+
+    let remove = vec_instruction(RuntimeLimitationInstruction::Remove { index: 0 });
+    let compact = vec_instruction(RuntimeLimitationInstruction::Compact);
+    runtime
+        .process_transaction(&[remove, compact], &[authority])
+        .unwrap();
+    let account = runtime.account(&offers).unwrap();
+    let header = VecHeader::read(&account.data).unwrap();
+    assert_eq!((header.len, header.capacity), (2, 2));
+    assert_eq!(account.data.len(), VecAccount::<Offer>::space(2));
+    assert_eq!(account.lamports, runtime.rent().minimum_balance(account.data.len()));
+    let paid = runtime.rent().minimum_balance(account.data.len())
+        - runtime.rent().minimum_balance(space);
+    assert_eq!(
+        runtime.account(&authority).unwrap().lamports,
+        LAMPORTS_PER_SOL - paid
+    );
+}
+
+#[test]
+fn test_recursive_cpi_depth() {
+    let (mut runtime, program_id) = setup();
+    let recurse = |depth| {
+        instruction(
+            program_id,
+            RuntimeLimitationInstruction::RecursiveCpi {
+                depth,
+                stack_payload: 4096,
+            },
+            vec![AccountMeta::new_readonly(program_id, false)],
+        )
+    };
+
+    let max_depth = MAX_INVOKE_DEPTH as u8 - 1;
+    runtime.process_instruction(&recurse(max_depth), &[]).unwrap();
+    let invocations = runtime.logs().iter().filter(|log| log.contains(" invoke [")).count();
+    assert_eq!(invocations, MAX_INVOKE_DEPTH);
+    assert_eq!(
+        runtime.process_instruction(&recurse(max_depth + 1), &[]),
+        Err(RuntimeError::CallDepth)
+    );
+}
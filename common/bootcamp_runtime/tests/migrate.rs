@@ -0,0 +1,323 @@
+use {
+    authorized_counter::state::{Counter, LegacyCounter},
+    bootcamp_accounts::{header_version, write_header, BorshAccount, Discriminator},
+    bootcamp_runtime::{Account, Runtime, RuntimeError},
+    borsh::BorshSerialize,
+    count_tracker::{
+        error::CounterError,
+        instruction,
+        state::{Leaderboard, LegacyTracker, Tracker, TrackerV1},
+    },
+    solana_program::{
+        native_token::LAMPORTS_PER_SOL, program_error::ProgramError, pubkey::Pubkey, system_program,
+    },
+    token::state::{LegacyTokenAccount, Mint, TokenAccount},
+};
+
+fn add_user(runtime: &mut Runtime) -> Pubkey {
+    let user = Pubkey::new_unique();
+    runtime.add_account(
+        user,
+        Account::new(LAMPORTS_PER_SOL, 0, &system_program::id()),
+    );
+    user
+}
+
+/// Adds a rent exempt account holding `data`, as written by an older program version.
+fn add_data_account(runtime: &mut Runtime, key: Pubkey, data: Vec<u8>, owner: &Pubkey) {
+    runtime.add_rent_exempt_account(key, data.len(), owner);
+    let mut account = runtime.account(&key).unwrap().clone();
+    account.data = data;
+    runtime.add_account(key, account);
+}
+
+fn assert_custom_error(result: Result<(), RuntimeError>, program_id: Pubkey, error: u32) {
+    assert_eq!(
+        result,
+        Err(RuntimeError::Program(
+            program_id,
+            ProgramError::Custom(error)
+        ))
+    );
+}
+
+struct TrackerSetup {
+    runtime: Runtime,
+    user: Pubkey,
+    counter: Pubkey,
+    authority: Pubkey,
+    auth_bump: u8,
+    leaderboard: Pubkey,
+}
+
+/// A counter with a leaderboard, for trackers of `user` that older program versions
+/// created.
+fn tracker_setup() -> TrackerSetup {
+    let mut runtime = Runtime::new();
+    runtime.add_program(
+        authorized_counter::id(),
+        authorized_counter::processor::Processor::process_instruction,
+    );
+    runtime.add_program(
+        count_tracker::id(),
+        count_tracker::processor::Processor::process_instruction,
+    );
+    let id = count_tracker::id();
+    let user = add_user(&mut runtime);
+    let counter = Pubkey::new_unique();
+    runtime.add_rent_exempt_account(counter, Counter::LEN, &authorized_counter::id());
+    let (authority, auth_bump) = Pubkey::find_program_address(&[counter.as_ref()], &id);
+    let (leaderboard, _) = Pubkey::find_program_address(&[b"leaderboard", counter.as_ref()], &id);
+    let ix = instruction::initialize_leaderboard(id, leaderboard, user, counter).unwrap();
+    runtime.process_instruction(&ix, &[user]).unwrap();
+    TrackerSetup {
+        runtime,
+        user,
+        counter,
+        authority,
+        auth_bump,
+        leaderboard,
+    }
+}
+
+#[test]
+fn test_migrate_tracker() {
+    let TrackerSetup {
+        mut runtime,
+        user,
+        counter,
+        authority,
+        auth_bump,
+        leaderboard,
+    } = tracker_setup();
+    let id = count_tracker::id();
+    let (tracker, bump) = Pubkey::find_program_address(&[user.as_ref(), counter.as_ref()], &id);
+    let mut data = vec![0; TrackerV1::LEN];
+    write_header::<TrackerV1>(&mut data).unwrap();
+    let v1 = TrackerV1 {
+        bump,
+        auth_bump,
+        counter,
+        count: 5,
+    };
+    v1.serialize(&mut &mut data[bootcamp_accounts::HEADER_LEN..])
+        .unwrap();
+    add_data_account(&mut runtime, tracker, data, &id);
+
+    // Trackers keep working before they are migrated
+    let increment =
+        instruction::increment(id, tracker, user, counter, authority, leaderboard).unwrap();
+    runtime.process_instruction(&increment, &[user]).unwrap();
+    let data = &runtime.account(&tracker).unwrap().data;
+    assert_eq!(data.len(), TrackerV1::LEN);
+    assert_eq!(header_version::<Tracker>(data).unwrap(), 1);
+    assert_eq!(Tracker::unpack(data).unwrap().count, 6);
+
+    // The tracker PDA must derive from the user
+    let payer = add_user(&mut runtime);
+    let stranger = Pubkey::new_unique();
+    let ix = instruction::migrate(id, tracker, stranger, payer).unwrap();
+    assert!(runtime.process_instruction(&ix, &[payer]).is_err());
+
+    let ix = instruction::migrate(id, tracker, user, payer).unwrap();
+    runtime.process_instruction(&ix, &[payer]).unwrap();
+    let account = runtime.account(&tracker).unwrap();
+    assert_eq!(account.data.len(), Tracker::LEN);
+    assert_eq!(
+        account.lamports,
+        runtime.rent().minimum_balance(Tracker::LEN)
+    );
+    assert_eq!(
+        header_version::<Tracker>(&account.data).unwrap(),
+        Tracker::VERSION
+    );
+    let migrated = Tracker::unpack(&account.data).unwrap();
+    assert_eq!((migrated.user, migrated.count), (user, 6));
+
+    runtime.process_instruction(&increment, &[user]).unwrap();
+    let data = &runtime.account(&tracker).unwrap().data;
+    let tracker_state = Tracker::unpack(data).unwrap();
+    assert_eq!((tracker_state.count, tracker_state.global_count), (7, 2));
+    let leaderboard = Leaderboard::unpack(&runtime.account(&leaderboard).unwrap().data).unwrap();
+    assert_eq!(leaderboard.ranked()[0].count, 7);
+
+    let result = runtime.process_instruction(&ix, &[payer]);
+    assert_custom_error(result, id, CounterError::AlreadyMigrated as u32);
+}
+
+#[test]
+fn test_migrate_legacy_tracker() {
+    let TrackerSetup {
+        mut runtime,
+        user,
+        counter,
+        authority,
+        auth_bump,
+        leaderboard,
+    } = tracker_setup();
+    let id = count_tracker::id();
+    let (tracker, bump) = Pubkey::find_program_address(&[user.as_ref(), counter.as_ref()], &id);
+    let legacy = LegacyTracker {
+        bump,
+        auth_bump,
+        counter,
+        count: 5,
+    };
+    add_data_account(&mut runtime, tracker, legacy.try_to_vec().unwrap(), &id);
+
+    // Trackers without a header keep working before they are migrated
+    let increment =
+        instruction::increment(id, tracker, user, counter, authority, leaderboard).unwrap();
+    runtime.process_instruction(&increment, &[user]).unwrap();
+    let data = &runtime.account(&tracker).unwrap().data;
+    assert_eq!(data.len(), LegacyTracker::LEN);
+    assert_eq!(Tracker::unpack_any(data).unwrap().count, 6);
+    assert_eq!(
+        Tracker::unpack(data).unwrap_err(),
+        ProgramError::InvalidAccountData
+    );
+
+    let payer = add_user(&mut runtime);
+    let ix = instruction::migrate(id, tracker, user, payer).unwrap();
+    runtime.process_instruction(&ix, &[payer]).unwrap();
+    let account = runtime.account(&tracker).unwrap();
+    assert_eq!(account.data.len(), Tracker::LEN);
+    assert_eq!(
+        account.lamports,
+        runtime.rent().minimum_balance(Tracker::LEN)
+    );
+    assert_eq!(
+        header_version::<Tracker>(&account.data).unwrap(),
+        Tracker::VERSION
+    );
+    let migrated = Tracker::unpack(&account.data).unwrap();
+    assert_eq!(
+        (migrated.bump, migrated.auth_bump, migrated.counter),
+        (bump, auth_bump, counter)
+    );
+    assert_eq!((migrated.user, migrated.count), (user, 6));
+
+    runtime.process_instruction(&increment, &[user]).unwrap();
+    let data = &runtime.account(&tracker).unwrap().data;
+    let tracker_state = Tracker::unpack(data).unwrap();
+    assert_eq!((tracker_state.count, tracker_state.global_count), (7, 2));
+
+    let result = runtime.process_instruction(&ix, &[payer]);
+    assert_custom_error(result, id, CounterError::AlreadyMigrated as u32);
+}
+
+#[test]
+fn test_migrate_counter() {
+    let mut runtime = Runtime::new();
+    let id = authorized_counter::id();
+    runtime.add_program(
+        id,
+        authorized_counter::processor::Processor::process_instruction,
+    );
+    let authority = add_user(&mut runtime);
+    let counter = Pubkey::new_unique();
+    let legacy = LegacyCounter {
+        authority,
+        count: 3,
+    };
+    add_data_account(&mut runtime, counter, legacy.try_to_vec().unwrap(), &id);
+
+    let increment = authorized_counter::instruction::increment(
+        id,
+        counter,
+        authority,
+        authorized_counter::instruction::CounterInstruction::Increment,
+    )
+    .unwrap();
+    runtime
+        .process_instruction(&increment, &[authority])
+        .unwrap();
+    assert_eq!(
+        runtime.account(&counter).unwrap().data.len(),
+        LegacyCounter::LEN
+    );
+
+    let payer = add_user(&mut runtime);
+    let ix = authorized_counter::instruction::migrate(id, counter, payer).unwrap();
+    runtime.process_instruction(&ix, &[payer]).unwrap();
+    let account = runtime.account(&counter).unwrap();
+    assert_eq!(account.data.len(), Counter::LEN);
+    assert_eq!(
+        account.lamports,
+        runtime.rent().minimum_balance(Counter::LEN)
+    );
+    let migrated = Counter::unpack(&account.data).unwrap();
+    assert_eq!((migrated.authority, migrated.count), (authority, 4));
+
+    runtime
+        .process_instruction(&increment, &[authority])
+        .unwrap();
+    assert_eq!(
+        Counter::unpack(&runtime.account(&counter).unwrap().data)
+            .unwrap()
+            .count,
+        5
+    );
+
+    let result = runtime.process_instruction(&ix, &[payer]);
+    let error = authorized_counter::error::CounterError::AlreadyMigrated;
+    assert_custom_error(result, id, error as u32);
+}
+
+#[test]
+fn test_migrate_token_account() {
+    let mut runtime = Runtime::new();
+    let id = Pubkey::new_unique();
+    runtime.add_program(id, token::processor::Processor::process_instruction);
+    let owner = add_user(&mut runtime);
+    let mint = Pubkey::new_unique();
+    let mut data = vec![0; Mint::LEN];
+    write_header::<Mint>(&mut data).unwrap();
+    let mint_state = Mint {
+        authority: owner,
+        supply: 10,
+    };
+    mint_state
+        .serialize(&mut &mut data[bootcamp_accounts::HEADER_LEN..])
+        .unwrap();
+    add_data_account(&mut runtime, mint, data, &id);
+
+    let legacy = Pubkey::new_unique();
+    let legacy_state = LegacyTokenAccount {
+        tag: LegacyTokenAccount::TAG,
+        owner,
+        mint,
+        amount: 10,
+    };
+    add_data_account(
+        &mut runtime,
+        legacy,
+        legacy_state.try_to_vec().unwrap(),
+        &id,
+    );
+    let dst = Pubkey::new_unique();
+    runtime.add_rent_exempt_account(dst, TokenAccount::LEN, &id);
+    let ix = token::instruction::initialize_token_account(id, dst, mint, owner).unwrap();
+    runtime.process_instruction(&ix, &[owner]).unwrap();
+
+    // Legacy token accounts keep working before they are migrated
+    let ix = token::instruction::transfer(id, legacy, dst, owner, 4).unwrap();
+    runtime.process_instruction(&ix, &[owner]).unwrap();
+    let data = &runtime.account(&legacy).unwrap().data;
+    assert_eq!(data.len(), LegacyTokenAccount::LEN);
+    assert_eq!(TokenAccount::unpack_any(data).unwrap().amount, 6);
+
+    let payer = add_user(&mut runtime);
+    let ix = token::instruction::migrate(id, legacy, payer).unwrap();
+    runtime.process_instruction(&ix, &[payer]).unwrap();
+    let account = runtime.account(&legacy).unwrap();
+    assert_eq!(account.data.len(), TokenAccount::LEN);
+    let migrated = TokenAccount::unpack(&account.data).unwrap();
+    assert_eq!(
+        (migrated.owner, migrated.mint, migrated.amount),
+        (owner, mint, 6)
+    );
+
+    let result = runtime.process_instruction(&ix, &[payer]);
+    assert_custom_error(result, id, token::error::TokenError::AlreadyMigrated as u32);
+}
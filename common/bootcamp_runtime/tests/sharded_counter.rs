@@ -0,0 +1,160 @@
+use {
+    authorized_counter::{
+        error::CounterError,
+        instruction::{self, CounterInstruction},
+        state::{total_count, Counter, Shard},
+    },
+    bootcamp_accounts::BorshAccount,
+    bootcamp_runtime::{Account, Runtime, RuntimeError},
+    solana_program::{
+        native_token::LAMPORTS_PER_SOL, program_error::ProgramError, pubkey::Pubkey, system_program,
+    },
+};
+
+const SHARDS: u8 = 4;
+
+fn add_user(runtime: &mut Runtime) -> Pubkey {
+    let user = Pubkey::new_unique();
+    runtime.add_account(
+        user,
+        Account::new(LAMPORTS_PER_SOL, 0, &system_program::id()),
+    );
+    user
+}
+
+fn counter_state(runtime: &Runtime, counter: &Pubkey) -> Counter {
+    Counter::unpack_any(&runtime.account(counter).unwrap().data).unwrap()
+}
+
+fn shard_state(runtime: &Runtime, shard: &Pubkey) -> Shard {
+    Shard::unpack(&runtime.account(shard).unwrap().data).unwrap()
+}
+
+/// A new counter claimed by `authority`, with its `SHARDS` shards.
+fn sharded_counter(runtime: &mut Runtime, authority: Pubkey) -> (Pubkey, Vec<Pubkey>) {
+    let id = authorized_counter::id();
+    let counter = Pubkey::new_unique();
+    runtime.add_rent_exempt_account(counter, Counter::LEN, &id);
+    let (shard, _) = Shard::find_address(&id, &counter, 0);
+    let ix = instruction::initialize_shard(id, shard, counter, authority, authority, 0).unwrap();
+    // The first increment claims the counter, which shards need
+    assert_eq!(
+        runtime.process_instruction(&ix, &[authority]),
+        Err(RuntimeError::Program(
+            id,
+            CounterError::UnclaimedCounter.into()
+        ))
+    );
+    let ix = instruction::increment(id, counter, authority, CounterInstruction::Increment).unwrap();
+    runtime.process_instruction(&ix, &[authority]).unwrap();
+
+    let shards: Vec<_> = (0..SHARDS)
+        .map(|index| Shard::find_address(&id, &counter, index).0)
+        .collect();
+    let instructions: Vec<_> = shards
+        .iter()
+        .zip(0..)
+        .map(|(shard, index)| {
+            instruction::initialize_shard(id, *shard, counter, authority, authority, index).unwrap()
+        })
+        .collect();
+    runtime
+        .process_transaction(&instructions, &[authority])
+        .unwrap();
+    (counter, shards)
+}
+
+#[test]
+fn test_increment_shards() {
+    let mut runtime = Runtime::new();
+    let id = authorized_counter::id();
+    runtime.add_program(
+        id,
+        authorized_counter::processor::Processor::process_instruction,
+    );
+    let authority = add_user(&mut runtime);
+    let (counter, shards) = sharded_counter(&mut runtime, authority);
+    for (index, shard) in shards.iter().enumerate() {
+        let state = shard_state(&runtime, shard);
+        assert_eq!((state.index as usize, state.count), (index, 0));
+        assert_eq!((state.counter, state.authority), (counter, authority));
+        let account = runtime.account(shard).unwrap();
+        assert_eq!(account.lamports, runtime.rent().minimum_balance(Shard::LEN));
+    }
+
+    let increment = |shard| instruction::increment_shard(id, shard, authority).unwrap();
+    let instructions = [shards[0], shards[1], shards[1], shards[3]].map(increment);
+    runtime
+        .process_transaction(&instructions, &[authority])
+        .unwrap();
+    // Shards don't touch the counter
+    assert_eq!(counter_state(&runtime, &counter).count, 1);
+    let states: Vec<_> = shards
+        .iter()
+        .map(|shard| shard_state(&runtime, shard))
+        .collect();
+    let counts: Vec<_> = states.iter().map(|shard| shard.count).collect();
+    assert_eq!(counts, vec![1, 2, 0, 1]);
+    assert_eq!(
+        total_count(&counter_state(&runtime, &counter), &states),
+        Some(5)
+    );
+
+    // Only the authority of the counter increments its shards
+    let stranger = add_user(&mut runtime);
+    let ix = instruction::increment_shard(id, shards[0], stranger).unwrap();
+    assert_eq!(
+        runtime.process_instruction(&ix, &[stranger]),
+        Err(RuntimeError::Program(
+            id,
+            ProgramError::MissingRequiredSignature
+        ))
+    );
+}
+
+#[test]
+fn test_aggregate() {
+    let mut runtime = Runtime::new();
+    let id = authorized_counter::id();
+    runtime.add_program(
+        id,
+        authorized_counter::processor::Processor::process_instruction,
+    );
+    let authority = add_user(&mut runtime);
+    let (counter, shards) = sharded_counter(&mut runtime, authority);
+    let increment = |shard| instruction::increment_shard(id, shard, authority).unwrap();
+    let instructions = [shards[0], shards[2], shards[2]].map(increment);
+    runtime
+        .process_transaction(&instructions, &[authority])
+        .unwrap();
+
+    // Shards of another counter can't be aggregated into this one
+    let (other_counter, other_shards) = sharded_counter(&mut runtime, authority);
+    let ix = instruction::aggregate(id, counter, &[shards[0], other_shards[0]]).unwrap();
+    assert_eq!(
+        runtime.process_instruction(&ix, &[]),
+        Err(RuntimeError::Program(
+            id,
+            CounterError::ShardMismatch.into()
+        ))
+    );
+
+    // Anyone can aggregate, a shard passed in twice is only counted once
+    let ix = instruction::aggregate(id, counter, &[shards[0], shards[2], shards[2]]).unwrap();
+    runtime.process_instruction(&ix, &[]).unwrap();
+    assert_eq!(counter_state(&runtime, &counter).count, 4);
+    for shard in &shards {
+        assert_eq!(shard_state(&runtime, shard).count, 0);
+    }
+    assert_eq!(counter_state(&runtime, &other_counter).count, 1);
+
+    // Increments keep landing on shards and the counter after an aggregation
+    runtime
+        .process_instruction(&increment(shards[1]), &[authority])
+        .unwrap();
+    let ix = instruction::increment(id, counter, authority, CounterInstruction::Increment).unwrap();
+    runtime.process_instruction(&ix, &[authority]).unwrap();
+    let ix = instruction::aggregate(id, counter, &shards).unwrap();
+    runtime.process_instruction(&ix, &[]).unwrap();
+    assert_eq!(counter_state(&runtime, &counter).count, 6);
+}
@@ -0,0 +1,192 @@
+use {
+    bootcamp_accounts::ZeroCopy,
+    bootcamp_runtime::{Account, Runtime, RuntimeError},
+    solana_program::{
+        hash::hash, native_token::LAMPORTS_PER_SOL, program_error::ProgramError, pubkey::Pubkey,
+        system_program,
+    },
+    uploader::{
+        error::UploaderError,
+        instruction,
+        state::{
+            bitmap_len, buffer_size, is_chunk_written, BufferHeader, CHUNK_SIZE, MAX_DATA_LEN,
+        },
+    },
+};
+
+/// Two full chunks and a shorter last one.
+const BLOB_LEN: usize = 2 * CHUNK_SIZE + 500;
+
+struct Setup {
+    runtime: Runtime,
+    id: Pubkey,
+    buffer: Pubkey,
+    authority: Pubkey,
+    blob: Vec<u8>,
+}
+
+/// A buffer for a `BLOB_LEN` blob, initialized by `authority`.
+fn setup() -> Setup {
+    let mut runtime = Runtime::new();
+    let id = Pubkey::new_unique();
+    runtime.add_program(id, uploader::processor::Processor::process_instruction);
+    let authority = Pubkey::new_unique();
+    runtime.add_account(
+        authority,
+        Account::new(LAMPORTS_PER_SOL, 0, &system_program::id()),
+    );
+    let buffer = Pubkey::new_unique();
+    runtime.add_rent_exempt_account(buffer, buffer_size(BLOB_LEN).unwrap(), &id);
+    let ix = instruction::initialize(id, buffer, authority, BLOB_LEN as u64).unwrap();
+    runtime.process_instruction(&ix, &[authority]).unwrap();
+    let blob = (0..BLOB_LEN).map(|i| (i % 251) as u8).collect();
+    Setup {
+        runtime,
+        id,
+        buffer,
+        authority,
+        blob,
+    }
+}
+
+fn write_chunk(setup: &mut Setup, chunk: usize) -> Result<(), RuntimeError> {
+    let offset = chunk * CHUNK_SIZE;
+    let data = setup.blob[offset..(offset + CHUNK_SIZE).min(BLOB_LEN)].to_vec();
+    let ix = instruction::write(
+        setup.id,
+        setup.buffer,
+        setup.authority,
+        offset as u64,
+        data,
+    )
+    .unwrap();
+    setup.runtime.process_instruction(&ix, &[setup.authority])
+}
+
+fn finalize(setup: &mut Setup, blob: &[u8]) -> Result<(), RuntimeError> {
+    let hash = hash(blob).to_bytes();
+    let ix = instruction::finalize(setup.id, setup.buffer, setup.authority, hash).unwrap();
+    setup.runtime.process_instruction(&ix, &[setup.authority])
+}
+
+fn header(setup: &Setup) -> BufferHeader {
+    BufferHeader::read(&setup.runtime.account(&setup.buffer).unwrap().data).unwrap()
+}
+
+/// Chunks the buffer has not received yet, as a client resuming the upload sees them.
+fn missing_chunks(setup: &Setup) -> Vec<usize> {
+    let data = &setup.runtime.account(&setup.buffer).unwrap().data;
+    let bitmap = &data[BufferHeader::LEN..BufferHeader::LEN + bitmap_len(BLOB_LEN)];
+    (0..header(setup).chunk_count as usize)
+        .filter(|chunk| !is_chunk_written(bitmap, *chunk))
+        .collect()
+}
+
+fn assert_custom_error(setup: &Setup, result: Result<(), RuntimeError>, error: UploaderError) {
+    assert_eq!(
+        result,
+        Err(RuntimeError::Program(setup.id, ProgramError::from(error)))
+    );
+}
+
+#[test]
+fn test_initialize() {
+    let setup = setup();
+    let header = header(&setup);
+    assert_eq!(header.authority, setup.authority);
+    assert_eq!(header.data_len, BLOB_LEN as u64);
+    assert_eq!((header.chunk_count, header.finalized), (3, 0));
+    assert_eq!(missing_chunks(&setup), vec![0, 1, 2]);
+
+    // Initializing twice would reset the chunks that already landed
+    let mut setup = setup;
+    let ix =
+        instruction::initialize(setup.id, setup.buffer, setup.authority, BLOB_LEN as u64).unwrap();
+    assert_eq!(
+        setup.runtime.process_instruction(&ix, &[setup.authority]),
+        Err(RuntimeError::Program(
+            setup.id,
+            ProgramError::AccountAlreadyInitialized
+        ))
+    );
+
+    // Blobs too large for Finalize to hash are rejected, even if the buffer fits them
+    let buffer = Pubkey::new_unique();
+    let size = buffer_size(MAX_DATA_LEN).unwrap() + 1;
+    setup.runtime.add_rent_exempt_account(buffer, size, &setup.id);
+    for data_len in [MAX_DATA_LEN as u64 + 1, u64::MAX] {
+        let ix = instruction::initialize(setup.id, buffer, setup.authority, data_len).unwrap();
+        let result = setup.runtime.process_instruction(&ix, &[setup.authority]);
+        assert_custom_error(&setup, result, UploaderError::DataTooLarge);
+    }
+    let ix = instruction::initialize(setup.id, buffer, setup.authority, MAX_DATA_LEN as u64)
+        .unwrap();
+    setup
+        .runtime
+        .process_instruction(&ix, &[setup.authority])
+        .unwrap();
+}
+
+#[test]
+fn test_write_rejects_misaligned_chunks() {
+    let mut setup = setup();
+    let (id, buffer, authority) = (setup.id, setup.buffer, setup.authority);
+
+    let ix = instruction::write(id, buffer, authority, 10, vec![1; CHUNK_SIZE]).unwrap();
+    let result = setup.runtime.process_instruction(&ix, &[authority]);
+    assert_custom_error(&setup, result, UploaderError::InvalidChunk);
+
+    // Past the end, and a last chunk running over it
+    let ix = instruction::write(id, buffer, authority, 3 * CHUNK_SIZE as u64, vec![1]).unwrap();
+    let result = setup.runtime.process_instruction(&ix, &[authority]);
+    assert_custom_error(&setup, result, UploaderError::InvalidChunk);
+    let offset = 2 * CHUNK_SIZE as u64;
+    let ix = instruction::write(id, buffer, authority, offset, vec![1; CHUNK_SIZE]).unwrap();
+    let result = setup.runtime.process_instruction(&ix, &[authority]);
+    assert_custom_error(&setup, result, UploaderError::InvalidChunk);
+
+    assert_eq!(missing_chunks(&setup), vec![0, 1, 2]);
+}
+
+#[test]
+fn test_finalize() {
+    let mut setup = setup();
+    write_chunk(&mut setup, 0).unwrap();
+    write_chunk(&mut setup, 2).unwrap();
+    let blob = setup.blob.clone();
+    let result = finalize(&mut setup, &blob);
+    assert_custom_error(&setup, result, UploaderError::MissingChunks);
+
+    write_chunk(&mut setup, 1).unwrap();
+    let result = finalize(&mut setup, &blob[1..]);
+    assert_custom_error(&setup, result, UploaderError::HashMismatch);
+    assert_eq!(header(&setup).finalized, 0);
+
+    finalize(&mut setup, &blob).unwrap();
+    assert_eq!(header(&setup).finalized, 1);
+    let data = &setup.runtime.account(&setup.buffer).unwrap().data;
+    assert_eq!(&data[data.len() - BLOB_LEN..], &blob[..]);
+
+    // Finalized buffers are immutable
+    let result = write_chunk(&mut setup, 0);
+    assert_custom_error(&setup, result, UploaderError::AlreadyFinalized);
+    let result = finalize(&mut setup, &blob);
+    assert_custom_error(&setup, result, UploaderError::AlreadyFinalized);
+}
+
+#[test]
+fn test_resume_upload() {
+    let mut setup = setup();
+    // The upload is interrupted after the last chunk landed
+    write_chunk(&mut setup, 2).unwrap();
+    assert_eq!(missing_chunks(&setup), vec![0, 1]);
+
+    // The retry only sends what is missing, in any order
+    for chunk in missing_chunks(&setup).into_iter().rev() {
+        write_chunk(&mut setup, chunk).unwrap();
+    }
+    assert!(missing_chunks(&setup).is_empty());
+    let blob = setup.blob.clone();
+    finalize(&mut setup, &blob).unwrap();
+    assert_eq!(header(&setup).finalized, 1);
+}
@@ -0,0 +1,146 @@
+#![no_main]
+
+use {
+    authorized_counter::{
+        instruction::CounterInstruction,
+        processor::Processor,
+        state::{Counter, LegacyCounter, Shard},
+    },
+    bootcamp_accounts::BorshAccount,
+    bootcamp_fuzz::{FuzzInstruction, Harness},
+    bootcamp_runtime::{Account, Runtime},
+    borsh::{BorshDeserialize, BorshSerialize},
+    libfuzzer_sys::fuzz_target,
+    solana_program::{instruction::Instruction, pubkey::Pubkey},
+};
+
+fn counter(runtime: &Runtime, key: &Pubkey) -> Counter {
+    Counter::unpack_any(&runtime.account(key).unwrap().data).unwrap()
+}
+
+fn shard(runtime: &Runtime, key: &Pubkey) -> Shard {
+    Shard::unpack(&runtime.account(key).unwrap().data).unwrap()
+}
+
+fn data_len(runtime: &Runtime, key: &Pubkey) -> usize {
+    runtime.account(key).unwrap().data.len()
+}
+
+fn signed(instruction: &Instruction, key: &Pubkey) -> bool {
+    instruction
+        .accounts
+        .iter()
+        .any(|meta| meta.pubkey == *key && meta.is_signer)
+}
+
+/// Count of `counter` plus the counts of its shards in `keys`.
+fn total(runtime: &Runtime, keys: &[Pubkey], counter_key: &Pubkey) -> u128 {
+    let shards: u128 = keys
+        .iter()
+        .filter_map(|key| Shard::unpack(&runtime.account(key)?.data).ok())
+        .filter(|shard| shard.counter == *counter_key)
+        .map(|shard| shard.count as u128)
+        .sum();
+    counter(runtime, counter_key).count as u128 + shards
+}
+
+fuzz_target!(|instructions: Vec<FuzzInstruction>| {
+    let id = authorized_counter::id();
+    let mut harness = Harness::new(id, Processor::process_instruction);
+    // A fresh counter, one of the first user that is about to overflow and one of the
+    // second user that can be sharded
+    let counters = [
+        (Pubkey::default(), 0),
+        (harness.user(0), u64::MAX),
+        (harness.user(1), 1),
+    ];
+    let mut counter_keys = vec![];
+    for (i, (authority, count)) in counters.into_iter().enumerate() {
+        let key = Pubkey::new_from_array([0x10 + i as u8; 32]);
+        let data = Counter { authority, count }.try_to_vec().unwrap();
+        let lamports = harness.runtime.rent().minimum_balance(data.len());
+        let account = Account {
+            lamports,
+            data,
+            owner: id,
+            ..Account::default()
+        };
+        harness.runtime.add_account(key, account);
+        harness.add_key(key);
+        counter_keys.push(key);
+    }
+    // Addresses of the first shards, which InitializeShard creates
+    for counter_key in &counter_keys[1..] {
+        for index in 0..2 {
+            harness.add_key(Shard::find_address(&id, counter_key, index).0);
+        }
+    }
+
+    let keys = harness.keys.clone();
+    harness.run(&instructions, |instruction, before, after| {
+        let key = &instruction.accounts[0].pubkey;
+        // Counter whose total count goes up by one
+        let incremented = match CounterInstruction::try_from_slice(&instruction.data).unwrap() {
+            CounterInstruction::Increment => {
+                let (old, new) = (counter(before, key), counter(after, key));
+                let authority = instruction.accounts[1].pubkey;
+                assert_eq!(Some(new.count), old.count.checked_add(1));
+                // Only the authority increments, which claims the counter on the first
+                // increment
+                assert!(signed(instruction, &authority));
+                assert_eq!(new.authority, authority);
+                if old.count != 0 {
+                    assert_eq!(old.authority, authority);
+                }
+                let returned = (id, new.count.to_le_bytes().to_vec());
+                assert_eq!(after.return_data(), Some(&returned));
+                Some(*key)
+            }
+            // Only adds the header to a legacy counter
+            CounterInstruction::Migrate => {
+                let (old, new) = (counter(before, key), counter(after, key));
+                assert_eq!(data_len(before, key), LegacyCounter::LEN);
+                assert_eq!(data_len(after, key), Counter::LEN);
+                assert_eq!(new.authority, old.authority);
+                assert_eq!(new.count, old.count);
+                None
+            }
+            // Shards of claimed counters start empty with the authority of the counter
+            CounterInstruction::InitializeShard { .. } => {
+                let new = shard(after, key);
+                let counter = counter(after, &new.counter);
+                assert_ne!(counter.count, 0);
+                assert_eq!(new.authority, counter.authority);
+                assert_eq!(new.count, 0);
+                None
+            }
+            CounterInstruction::IncrementShard => {
+                let (old, new) = (shard(before, key), shard(after, key));
+                assert_eq!(Some(new.count), old.count.checked_add(1));
+                assert!(signed(instruction, &new.authority));
+                Some(new.counter)
+            }
+            CounterInstruction::Aggregate => {
+                for meta in &instruction.accounts[1..] {
+                    assert_eq!(shard(after, &meta.pubkey).count, 0);
+                }
+                None
+            }
+            // Only publishes the count
+            CounterInstruction::Get => {
+                let returned = (id, counter(after, key).count.to_le_bytes().to_vec());
+                assert_eq!(after.return_data(), Some(&returned));
+                None
+            }
+        };
+        // Counts are only ever moved between a counter and its shards
+        for counter_key in &counter_keys {
+            let increment = (incremented == Some(*counter_key)) as u128;
+            let (old, new) = (
+                total(before, &keys, counter_key),
+                total(after, &keys, counter_key),
+            );
+            assert_eq!(new, old + increment, "{:?}", instruction);
+        }
+    });
+});
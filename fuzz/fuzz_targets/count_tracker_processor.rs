@@ -0,0 +1,119 @@
+#![no_main]
+
+use {
+    authorized_counter::state::{Counter, Shard},
+    bootcamp_accounts::{header_version, BorshAccount, Discriminator},
+    bootcamp_fuzz::{FuzzInstruction, Harness, USERS},
+    bootcamp_runtime::{Account, Runtime},
+    borsh::BorshDeserialize,
+    count_tracker::{
+        instruction::{self, TrackerInstruction},
+        processor::Processor,
+        state::{Leaderboard, Tracker},
+    },
+    libfuzzer_sys::fuzz_target,
+    solana_program::pubkey::Pubkey,
+};
+
+fn global_count(runtime: &Runtime, key: &Pubkey) -> u64 {
+    Counter::try_from_slice(&runtime.account(key).unwrap().data)
+        .unwrap()
+        .count
+}
+
+fn tracker(runtime: &Runtime, key: &Pubkey) -> Tracker {
+    Tracker::unpack(&runtime.account(key).unwrap().data).unwrap()
+}
+
+fn shard_count(runtime: &Runtime, key: &Pubkey) -> u64 {
+    Shard::unpack(&runtime.account(key).unwrap().data)
+        .unwrap()
+        .count
+}
+
+fuzz_target!(|instructions: Vec<FuzzInstruction>| {
+    let id = count_tracker::id();
+    let mut harness = Harness::new(id, Processor::process_instruction);
+    harness.add_program(
+        authorized_counter::id(),
+        authorized_counter::processor::Processor::process_instruction,
+    );
+    // Two counters, of which only the first has a leaderboard and trackers of the
+    // first two users
+    for i in 0..2u8 {
+        let counter = Pubkey::new_from_array([0x10 + i; 32]);
+        let lamports = harness.runtime.rent().minimum_balance(40);
+        let account = Account::new(lamports, 40, &authorized_counter::id());
+        harness.runtime.add_account(counter, account);
+        harness.add_key(counter);
+        let (authority, _) = Pubkey::find_program_address(&[counter.as_ref()], &id);
+        harness.add_key(authority);
+        let seeds = &[b"leaderboard", counter.as_ref()];
+        let (leaderboard, _) = Pubkey::find_program_address(seeds, &id);
+        harness.add_key(leaderboard);
+        // Addresses of the first shards, which InitializeShard creates
+        for index in 0..2 {
+            harness.add_key(Shard::find_address(&authorized_counter::id(), &counter, index).0);
+        }
+        for user in harness.users.clone() {
+            let seeds = &[user.as_ref(), counter.as_ref()];
+            harness.add_key(Pubkey::find_program_address(seeds, &id).0);
+        }
+        if i > 0 {
+            continue;
+        }
+        let payer = harness.user(0);
+        let ix = instruction::initialize_leaderboard(id, leaderboard, payer, counter).unwrap();
+        harness.process(&ix, &[payer]).unwrap();
+        for &user in &harness.users.clone()[..USERS - 1] {
+            let tracker = Pubkey::find_program_address(&[user.as_ref(), counter.as_ref()], &id).0;
+            let ix = instruction::initialize(id, tracker, user, authority, counter).unwrap();
+            harness.process(&ix, &[user]).unwrap();
+        }
+    }
+
+    harness.run(&instructions, |ix, before, after| {
+        let key = |i: usize| ix.accounts[i].pubkey;
+        let (tracker_key, user, counter) = (key(0), key(1), key(3));
+        let leaderboard = match TrackerInstruction::try_from_slice(&ix.data).unwrap() {
+            TrackerInstruction::Increment => {
+                assert_eq!(
+                    global_count(after, &counter),
+                    global_count(before, &counter) + 1
+                );
+                // Trackers of older versions have no room for the snapshot
+                let data = &after.account(&tracker_key).unwrap().data;
+                if header_version::<Tracker>(data).unwrap() == Tracker::VERSION {
+                    let tracker = tracker(after, &tracker_key);
+                    assert_eq!(tracker.global_count, global_count(after, &counter));
+                }
+                key(5)
+            }
+            // Only the shard of the counter is incremented
+            TrackerInstruction::IncrementShard { index } => {
+                let shard = key(5);
+                let seeds = &[counter.as_ref(), &[index]];
+                let (expected, _) =
+                    Pubkey::find_program_address(seeds, &authorized_counter::id());
+                assert_eq!(shard, expected);
+                assert_eq!(shard_count(after, &shard), shard_count(before, &shard) + 1);
+                assert_eq!(global_count(after, &counter), global_count(before, &counter));
+                key(6)
+            }
+            _ => return,
+        };
+        let (tracker_before, tracker_after) =
+            (tracker(before, &tracker_key), tracker(after, &tracker_key));
+        assert_eq!(tracker_after.count, tracker_before.count + 1);
+        assert_eq!(tracker_after.counter, counter);
+        let seeds = &[user.as_ref(), counter.as_ref()];
+        assert_eq!(Pubkey::find_program_address(seeds, &id).0, tracker_key);
+        let leaderboard = Leaderboard::unpack(&after.account(&leaderboard).unwrap().data).unwrap();
+        assert_eq!(leaderboard.counter, counter);
+        let ranked = leaderboard.ranked();
+        assert!(ranked.windows(2).all(|pair| pair[0].count >= pair[1].count));
+        if let Some(entry) = ranked.iter().find(|entry| entry.user == user) {
+            assert_eq!(entry.count, tracker_after.count);
+        }
+    });
+});
@@ -0,0 +1,69 @@
+#![no_main]
+
+use {
+    bootcamp_fuzz::{FuzzInstruction, Harness},
+    bootcamp_runtime::{Account, Runtime},
+    borsh::{BorshDeserialize, BorshSerialize},
+    counter::{
+        instruction::CounterInstruction,
+        processor::Processor,
+        state::{Counter, RateLimit, MAX_RATE_LIMIT},
+    },
+    libfuzzer_sys::fuzz_target,
+    solana_program::pubkey::Pubkey,
+};
+
+fn unpack(runtime: &Runtime, key: &Pubkey) -> (Counter, Option<RateLimit>) {
+    Counter::unpack(&runtime.account(key).unwrap().data).unwrap()
+}
+
+fn count(runtime: &Runtime, key: &Pubkey) -> u64 {
+    unpack(runtime, key).0.count
+}
+
+fuzz_target!(|instructions: Vec<FuzzInstruction>| {
+    let program_id = Pubkey::new_from_array([u8::MAX; 32]);
+    let mut harness = Harness::new(program_id, Processor::process_instruction);
+    // The last counter has room for a rate limit
+    for (i, count) in [0, u64::MAX, 0].into_iter().enumerate() {
+        let key = Pubkey::new_from_array([0x10 + i as u8; 32]);
+        let mut data = Counter { count }.try_to_vec().unwrap();
+        if i == 2 {
+            data.resize(Counter::RATE_LIMITED_LEN, 0);
+        }
+        let lamports = harness.runtime.rent().minimum_balance(data.len());
+        let account = Account {
+            lamports,
+            data,
+            owner: program_id,
+            ..Account::default()
+        };
+        harness.runtime.add_account(key, account);
+        harness.add_key(key);
+        // Holding the keypair of the counter lets the fuzzer claim its rate limit
+        if i == 2 {
+            harness.users.push(key);
+        }
+    }
+
+    harness.run(&instructions, |instruction, before, after| {
+        let counter = &instruction.accounts[0].pubkey;
+        let expected = match CounterInstruction::try_from_slice(&instruction.data).unwrap() {
+            CounterInstruction::Increment => count(before, counter).checked_add(1),
+            CounterInstruction::Decrement => Some(count(before, counter).saturating_sub(1)),
+            CounterInstruction::SetRateLimit { .. } => {
+                // Only the counter keypair lets an authority claim the rate limit
+                let (_, old) = unpack(before, counter);
+                if old.map_or(false, |old| old.authority == Pubkey::default()) {
+                    assert!(instruction.accounts[0].is_signer);
+                }
+                Some(count(before, counter))
+            }
+        };
+        assert_eq!(Some(count(after, counter)), expected, "{:?}", instruction);
+        if let (_, Some(rate_limit)) = unpack(after, counter) {
+            assert!(rate_limit.max_increments as usize <= MAX_RATE_LIMIT);
+            assert!(rate_limit.len as usize <= MAX_RATE_LIMIT, "{:?}", rate_limit);
+        }
+    });
+});
@@ -0,0 +1,14 @@
+#![no_main]
+
+use {
+    bootcamp_fuzz::{FuzzInstruction, Harness},
+    echo::processor::Processor,
+    libfuzzer_sys::fuzz_target,
+    solana_program::pubkey::Pubkey,
+};
+
+fuzz_target!(|instructions: Vec<FuzzInstruction>| {
+    let program_id = Pubkey::new_from_array([u8::MAX; 32]);
+    let mut harness = Harness::new(program_id, Processor::process_instruction);
+    harness.run(&instructions, |_, _, _| {});
+});
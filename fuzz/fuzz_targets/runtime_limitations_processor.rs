@@ -0,0 +1,80 @@
+#![no_main]
+
+use {
+    bootcamp_accounts::ZeroCopy,
+    bootcamp_fuzz::{FuzzInstruction, Harness},
+    borsh::{BorshDeserialize, BorshSerialize},
+    libfuzzer_sys::fuzz_target,
+    marketplace::{
+        instruction::RuntimeLimitationInstruction,
+        processor::Processor,
+        state::{Marketplace, Offer},
+        vec_account::{VecAccount, VecHeader},
+    },
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+    },
+};
+
+/// Loop iterations that fit in the compute budget with room to spare. The native
+/// runtime has no budget, so longer loops would only time out.
+const MAX_ITER: usize = 200_000;
+
+fuzz_target!(|instructions: Vec<FuzzInstruction>| {
+    if instructions.iter().any(|instruction| {
+        matches!(
+            RuntimeLimitationInstruction::try_from_slice(&instruction.data),
+            Ok(RuntimeLimitationInstruction::Runtime { max_iter }) if max_iter > MAX_ITER
+        )
+    }) {
+        return;
+    }
+
+    let id = Pubkey::new_from_array([u8::MAX; 32]);
+    let mut harness = Harness::new(id, Processor::process_instruction);
+    let marketplace = Pubkey::new_from_array([0x30; 32]);
+    let offers = Pubkey::new_from_array([0x31; 32]);
+    let buffer = Pubkey::new_from_array([0x32; 32]);
+    harness
+        .runtime
+        .add_rent_exempt_account(marketplace, Marketplace::LEN, &id);
+    harness
+        .runtime
+        .add_rent_exempt_account(offers, VecAccount::<Offer>::space(0), &id);
+    harness.runtime.add_rent_exempt_account(buffer, 64, &id);
+    for key in [marketplace, offers, buffer] {
+        harness.add_key(key);
+    }
+    let user = harness.user(0);
+    let setup = [
+        (
+            RuntimeLimitationInstruction::ZeroCopy,
+            vec![marketplace, user],
+        ),
+        (
+            RuntimeLimitationInstruction::Push {
+                offers: vec![Offer::default(); 3],
+            },
+            vec![offers, user, solana_program::system_program::id()],
+        ),
+    ];
+    for (instruction, accounts) in setup {
+        let accounts = accounts
+            .into_iter()
+            .map(|key| AccountMeta::new(key, key == user))
+            .collect();
+        let ix = Instruction::new_with_bytes(id, &instruction.try_to_vec().unwrap(), accounts);
+        harness.process(&ix, &[user]).unwrap();
+    }
+
+    harness.run(&instructions, |instruction, _, after| {
+        let account = after.account(&offers).unwrap();
+        let header = VecHeader::read(&account.data).unwrap();
+        assert!(header.len <= header.capacity, "{:?}", instruction);
+        assert!(account.data.len() >= VecAccount::<Offer>::space(header.capacity as usize));
+        let account = after.account(&marketplace).unwrap();
+        assert_eq!(account.data.len(), Marketplace::LEN, "{:?}", instruction);
+        Marketplace::read(&account.data).unwrap();
+    });
+});
@@ -0,0 +1,89 @@
+#![no_main]
+
+use {
+    bootcamp_accounts::BorshAccount,
+    bootcamp_fuzz::{FuzzInstruction, Harness},
+    bootcamp_runtime::Runtime,
+    libfuzzer_sys::fuzz_target,
+    solana_program::pubkey::Pubkey,
+    std::collections::HashMap,
+    token::{
+        instruction,
+        processor::Processor,
+        state::{Mint, TokenAccount},
+    },
+};
+
+/// Supply of every mint minus the balances of its token accounts in the pool.
+fn imbalances(runtime: &Runtime, keys: &[Pubkey]) -> HashMap<Pubkey, i128> {
+    let mut imbalances = HashMap::new();
+    for (key, account) in keys
+        .iter()
+        .filter_map(|key| Some((key, runtime.account(key)?)))
+    {
+        if let Ok(mint) = Mint::unpack(&account.data) {
+            *imbalances.entry(*key).or_default() += mint.supply as i128;
+        } else if let Ok(token_account) = TokenAccount::unpack(&account.data) {
+            *imbalances.entry(token_account.mint).or_default() -= token_account.amount as i128;
+        }
+    }
+    imbalances.retain(|_, imbalance| *imbalance != 0);
+    imbalances
+}
+
+fuzz_target!(|instructions: Vec<FuzzInstruction>| {
+    let id = Pubkey::new_from_array([u8::MAX; 32]);
+    let mut harness = Harness::new(id, Processor::process_instruction);
+    let accounts: Vec<Pubkey> = (0..6)
+        .map(|i| Pubkey::new_from_array([0x20 + i; 32]))
+        .collect();
+    for key in &accounts {
+        harness
+            .runtime
+            .add_rent_exempt_account(*key, TokenAccount::LEN, &id);
+        harness.add_key(*key);
+    }
+    // Two mints, three token accounts with a balance and an uninitialized account
+    let [user0, user1, user2] = [0, 1, 2].map(|i| harness.user(i));
+    let setup = [
+        (instruction::initialize_mint(id, accounts[0], user0), user0),
+        (instruction::initialize_mint(id, accounts[1], user1), user1),
+        (
+            instruction::initialize_token_account(id, accounts[2], accounts[0], user0),
+            user0,
+        ),
+        (
+            instruction::initialize_token_account(id, accounts[3], accounts[0], user1),
+            user1,
+        ),
+        (
+            instruction::initialize_token_account(id, accounts[4], accounts[1], user2),
+            user2,
+        ),
+        (
+            instruction::mint(id, accounts[2], accounts[0], user0, 1_000),
+            user0,
+        ),
+        (
+            instruction::mint(id, accounts[3], accounts[0], user0, u64::MAX - 1_000),
+            user0,
+        ),
+        (
+            instruction::mint(id, accounts[4], accounts[1], user1, 1),
+            user1,
+        ),
+    ];
+    for (ix, signer) in setup {
+        harness.process(&ix.unwrap(), &[signer]).unwrap();
+    }
+
+    let keys = harness.keys.clone();
+    harness.run(&instructions, |instruction, before, after| {
+        assert_eq!(
+            imbalances(before, &keys),
+            imbalances(after, &keys),
+            "supply is not conserved by {:?}",
+            instruction
+        );
+    });
+});
@@ -0,0 +1,192 @@
+//! Shared harness of the fuzz targets.
+//!
+//! An input is a short sequence of instructions with arbitrary data and accounts, each
+//! processed as its own transaction by a `Runtime` the target seeded with valid state.
+//! Accounts are picked from a small pool of keys, so they alias each other and hit the
+//! seeded accounts, and accounts that don't exist yet can be created with arbitrary
+//! contents. Panics are crashes; targets check their own invariants on top.
+//!
+//! ```text
+//! cargo +nightly fuzz run token_processor
+//! ```
+
+use {
+    arbitrary::Arbitrary,
+    bootcamp_runtime::{Account, Runtime, RuntimeError},
+    solana_program::{
+        entrypoint::ProcessInstruction,
+        instruction::{AccountMeta, Instruction},
+        native_token::LAMPORTS_PER_SOL,
+        pubkey::Pubkey,
+        system_program, sysvar,
+    },
+};
+
+/// Instructions of one input past this are ignored.
+pub const MAX_INSTRUCTIONS: usize = 8;
+
+/// Users the fuzzer can sign for, each funded with 10 SOL.
+pub const USERS: usize = 3;
+
+#[derive(Arbitrary, Debug)]
+pub struct FuzzInstruction {
+    pub data: Vec<u8>,
+    pub accounts: Vec<FuzzAccount>,
+}
+
+#[derive(Arbitrary, Debug)]
+pub struct FuzzAccount {
+    /// Index into the key pool
+    pub key: u8,
+    /// Ignored unless the fuzzer holds the key
+    pub is_signer: bool,
+    pub is_writable: bool,
+    /// Contents of the account if it doesn't exist yet
+    pub state: Option<FuzzState>,
+}
+
+#[derive(Arbitrary, Debug)]
+pub struct FuzzState {
+    pub lamports: u32,
+    pub data: Vec<u8>,
+    /// Index into the registered programs
+    pub owner: u8,
+}
+
+pub struct Harness {
+    pub runtime: Runtime,
+    pub program_id: Pubkey,
+    /// Keys the accounts of an instruction are picked from
+    pub keys: Vec<Pubkey>,
+    pub users: Vec<Pubkey>,
+    programs: Vec<Pubkey>,
+}
+
+impl Harness {
+    /// A pool of `program_id`, the system program, spl-token, the rent and clock sysvars
+    /// and `USERS` users.
+    pub fn new(program_id: Pubkey, process_instruction: ProcessInstruction) -> Self {
+        let mut harness = Self {
+            runtime: Runtime::new(),
+            program_id,
+            keys: vec![sysvar::rent::id(), sysvar::clock::id()],
+            users: vec![],
+            programs: vec![system_program::id()],
+        };
+        harness.add_program(program_id, process_instruction);
+        harness.add_key(system_program::id());
+        harness.add_key(spl_token::id());
+        for i in 0..USERS {
+            let user = Pubkey::new_from_array([i as u8 + 1; 32]);
+            let account = Account::new(10 * LAMPORTS_PER_SOL, 0, &system_program::id());
+            harness.runtime.add_account(user, account);
+            harness.users.push(user);
+            harness.add_key(user);
+        }
+        harness
+    }
+
+    pub fn add_program(&mut self, program_id: Pubkey, process_instruction: ProcessInstruction) {
+        self.runtime.add_program(program_id, process_instruction);
+        self.programs.push(program_id);
+        self.add_key(program_id);
+    }
+
+    /// Adds a key to the pool, e.g. a seeded account or a PDA.
+    pub fn add_key(&mut self, key: Pubkey) {
+        if !self.keys.contains(&key) {
+            self.keys.push(key);
+        }
+    }
+
+    pub fn key(&self, index: u8) -> Pubkey {
+        self.keys[index as usize % self.keys.len()]
+    }
+
+    pub fn user(&self, index: usize) -> Pubkey {
+        self.users[index]
+    }
+
+    /// Lamports held by all the accounts in the pool.
+    pub fn total_lamports(&self) -> u128 {
+        self.keys
+            .iter()
+            .filter_map(|key| self.runtime.account(key))
+            .map(|account| account.lamports as u128)
+            .sum()
+    }
+
+    /// Builds the instruction for `fuzz_instruction`, creating the accounts that don't
+    /// exist yet, and returns it with its signers.
+    pub fn instruction(
+        &mut self,
+        fuzz_instruction: &FuzzInstruction,
+    ) -> (Instruction, Vec<Pubkey>) {
+        let mut accounts = Vec::with_capacity(fuzz_instruction.accounts.len());
+        let mut signers = vec![];
+        for fuzz_account in &fuzz_instruction.accounts {
+            let key = self.key(fuzz_account.key);
+            match &fuzz_account.state {
+                Some(state) if self.runtime.account(&key).is_none() => {
+                    let owner = self.programs[state.owner as usize % self.programs.len()];
+                    let account = Account {
+                        lamports: state.lamports as u64,
+                        data: state.data.clone(),
+                        owner,
+                        ..Account::default()
+                    };
+                    self.runtime.add_account(key, account);
+                }
+                _ => {}
+            }
+            let is_signer = fuzz_account.is_signer && self.users.contains(&key);
+            if is_signer {
+                signers.push(key);
+            }
+            accounts.push(AccountMeta {
+                pubkey: key,
+                is_signer,
+                is_writable: fuzz_account.is_writable,
+            });
+        }
+        let instruction = Instruction {
+            program_id: self.program_id,
+            accounts,
+            data: fuzz_instruction.data.clone(),
+        };
+        (instruction, signers)
+    }
+
+    /// Processes `instruction` and checks the invariants that hold for every program.
+    pub fn process(
+        &mut self,
+        instruction: &Instruction,
+        signers: &[Pubkey],
+    ) -> Result<(), RuntimeError> {
+        let lamports = self.total_lamports();
+        let result = self.runtime.process_instruction(instruction, signers);
+        assert_eq!(
+            lamports,
+            self.total_lamports(),
+            "lamports were created or destroyed by {:?}",
+            instruction
+        );
+        result
+    }
+
+    /// Processes up to `MAX_INSTRUCTIONS` of `instructions` and hands every successful
+    /// one to `check`, along with the state before and after it.
+    pub fn run(
+        &mut self,
+        instructions: &[FuzzInstruction],
+        mut check: impl FnMut(&Instruction, &Runtime, &Runtime),
+    ) {
+        for fuzz_instruction in instructions.iter().take(MAX_INSTRUCTIONS) {
+            let (instruction, signers) = self.instruction(fuzz_instruction);
+            let before = self.runtime.clone();
+            if self.process(&instruction, &signers).is_ok() {
+                check(&instruction, &before, &self.runtime);
+            }
+        }
+    }
+}
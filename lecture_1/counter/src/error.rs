@@ -1,10 +1,23 @@
-use solana_program::program_error::ProgramError;
+use num_derive::FromPrimitive;
+use solana_program::{
+    decode_error::DecodeError,
+    msg,
+    program_error::{PrintProgramError, ProgramError},
+};
 use thiserror::Error;
 
-#[derive(Error, Debug, Copy, Clone, PartialEq)]
+#[derive(Error, Debug, Copy, Clone, FromPrimitive, PartialEq)]
 pub enum CounterError {
     #[error("Instruction not implemented.")]
     NotImplemented,
+    #[error("The count would overflow.")]
+    Overflow,
+    #[error("Too many increments in the rate limit window.")]
+    RateLimited,
+    #[error("Counter has no room for a rate limit.")]
+    NoRateLimit,
+    #[error("Rate limit must fit the recorded increments and have a positive window.")]
+    InvalidRateLimit,
 }
 
 impl From<CounterError> for ProgramError {
@@ -12,3 +25,22 @@ impl From<CounterError> for ProgramError {
         ProgramError::Custom(e as u32)
     }
 }
+
+impl<T> DecodeError<T> for CounterError {
+    fn type_of() -> &'static str {
+        "CounterError"
+    }
+}
+
+impl PrintProgramError for CounterError {
+    fn print<E>(&self)
+    where
+        E: 'static
+            + std::error::Error
+            + DecodeError<E>
+            + PrintProgramError
+            + num_traits::FromPrimitive,
+    {
+        msg!("Error: {}", self);
+    }
+}
@@ -1,7 +1,85 @@
+use bootcamp_idl_derive::IdlInstruction;
 use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
 
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+#[derive(BorshSerialize, BorshDeserialize, IdlInstruction, Debug, Clone)]
 pub enum CounterInstruction {
+    #[account(0, writable, name = "counter", desc = "Counter owned by the program")]
     Increment, // unsigned byte
+    #[account(0, writable, name = "counter", desc = "Counter owned by the program")]
     Decrement, // unsigned byte
+    /// Limits the increments of a counter created with room for a `RateLimit`, a
+    /// `max_increments` of 0 removes the limit. The counter keypair must sign the first
+    /// call, which claims the rate limit for `authority`.
+    #[account(
+        0,
+        writable,
+        name = "counter",
+        desc = "Counter owned by the program, signs the first call"
+    )]
+    #[account(
+        1,
+        signer,
+        name = "authority",
+        desc = "Claims the rate limit on first use"
+    )]
+    SetRateLimit { max_increments: u8, window: i64 },
+}
+
+fn counter_instruction(
+    program_id: Pubkey,
+    counter: Pubkey,
+    instruction: CounterInstruction,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction {
+        accounts: vec![AccountMeta::new(counter, false)],
+        data: instruction.try_to_vec()?,
+        program_id,
+    })
+}
+
+pub fn increment(program_id: Pubkey, counter: Pubkey) -> Result<Instruction, ProgramError> {
+    counter_instruction(program_id, counter, CounterInstruction::Increment)
+}
+
+pub fn decrement(program_id: Pubkey, counter: Pubkey) -> Result<Instruction, ProgramError> {
+    counter_instruction(program_id, counter, CounterInstruction::Decrement)
+}
+
+pub fn set_rate_limit(
+    program_id: Pubkey,
+    counter: Pubkey,
+    authority: Pubkey,
+    max_increments: u8,
+    window: i64,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction {
+        accounts: vec![
+            AccountMeta::new(counter, false),
+            AccountMeta::new_readonly(authority, true),
+        ],
+        data: CounterInstruction::SetRateLimit {
+            max_increments,
+            window,
+        }
+        .try_to_vec()?,
+        program_id,
+    })
+}
+
+/// Same as `set_rate_limit`, signed by the counter keypair to claim the rate limit.
+pub fn claim_rate_limit(
+    program_id: Pubkey,
+    counter: Pubkey,
+    authority: Pubkey,
+    max_increments: u8,
+    window: i64,
+) -> Result<Instruction, ProgramError> {
+    let mut instruction = set_rate_limit(program_id, counter, authority, max_increments, window)?;
+    instruction.accounts[0].is_signer = true;
+    Ok(instruction)
 }
@@ -3,3 +3,13 @@ pub mod error;
 pub mod instruction;
 pub mod processor;
 pub mod state;
+
+/// Anchor compatible IDL of the program. Counters have no account header, so they
+/// are listed as a type.
+#[cfg(feature = "idl")]
+pub fn idl() -> bootcamp_idl::Idl {
+    bootcamp_idl::Idl::new("counter", env!("CARGO_PKG_VERSION"))
+        .instructions::<instruction::CounterInstruction>()
+        .ty::<state::Counter>()
+        .ty::<state::RateLimit>()
+}
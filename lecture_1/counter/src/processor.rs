@@ -1,43 +1,96 @@
 use borsh::{BorshDeserialize, BorshSerialize};
+use bootcamp_program_utils::{assert_with_msg, expect_owner, expect_signer, expect_writable};
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
+    clock::Clock,
     entrypoint::ProgramResult,
     msg,
     program_error::ProgramError,
     pubkey::Pubkey,
+    sysvar::Sysvar,
 };
 
+use crate::error::CounterError;
 use crate::instruction::CounterInstruction;
-use crate::state::Counter;
+use crate::state::{Counter, MAX_RATE_LIMIT};
 
 pub struct Processor {}
 
 impl Processor {
     pub fn process_instruction(
-        _program_id: &Pubkey,
+        program_id: &Pubkey,
         accounts: &[AccountInfo],
         instruction_data: &[u8],
     ) -> ProgramResult {
         let instruction = CounterInstruction::try_from_slice(instruction_data)
             .map_err(|_| ProgramError::InvalidInstructionData)?;
 
+        let accounts_iter = &mut accounts.iter();
+        let counter_ai = next_account_info(accounts_iter)?;
+        expect_owner(counter_ai, program_id, "Counter must be owned by the program")?;
+        expect_writable(counter_ai, "Counter must be writable")?;
+
         match instruction {
             CounterInstruction::Increment => {
                 msg!("Instruction: Increment");
-                let accounts_iter = &mut accounts.iter();
-                let counter_ai = next_account_info(accounts_iter)?;
-                let mut counter = Counter::try_from_slice(&counter_ai.try_borrow_mut_data()?)?;
-                counter.count += 1;
+                let (mut counter, rate_limit) = Counter::unpack(&counter_ai.try_borrow_data()?)?;
+                if let Some(mut rate_limit) = rate_limit {
+                    assert_with_msg(
+                        rate_limit.record(Clock::get()?.unix_timestamp),
+                        CounterError::RateLimited.into(),
+                        "Too many increments in the rate limit window",
+                    )?;
+                    let mut data = counter_ai.try_borrow_mut_data()?;
+                    rate_limit.serialize(&mut &mut data[Counter::LEN..])?;
+                }
+                counter.count = counter.count.checked_add(1).ok_or(CounterError::Overflow)?;
                 counter.serialize(&mut *counter_ai.data.borrow_mut())?;
             }
             CounterInstruction::Decrement => {
                 msg!("Instruction: Decrement");
-                let accounts_iter = &mut accounts.iter();
-                let counter_ai = next_account_info(accounts_iter)?;
-                let mut counter = Counter::try_from_slice(&counter_ai.try_borrow_mut_data()?)?;
+                let (mut counter, _) = Counter::unpack(&counter_ai.try_borrow_data()?)?;
                 counter.count = counter.count.saturating_sub(1);
                 counter.serialize(&mut *counter_ai.data.borrow_mut())?;
             }
+            CounterInstruction::SetRateLimit {
+                max_increments,
+                window,
+            } => {
+                msg!("Instruction: SetRateLimit");
+                let authority = next_account_info(accounts_iter)?;
+                expect_signer(
+                    authority,
+                    ProgramError::MissingRequiredSignature,
+                    "Authority must sign",
+                )?;
+                let (_, rate_limit) = Counter::unpack(&counter_ai.try_borrow_data()?)?;
+                let mut rate_limit = rate_limit.ok_or(CounterError::NoRateLimit)?;
+                if rate_limit.authority == Pubkey::default() {
+                    // Only whoever created the counter can claim it, not the first caller
+                    expect_signer(
+                        counter_ai,
+                        ProgramError::MissingRequiredSignature,
+                        "Counter must sign to claim its rate limit",
+                    )?;
+                    rate_limit.authority = *authority.key;
+                }
+                assert_with_msg(
+                    rate_limit.authority == *authority.key,
+                    ProgramError::MissingRequiredSignature,
+                    "Attempted to set the rate limit with an invalid authority",
+                )?;
+                // A window of 0 would let every increment through
+                let in_bounds = max_increments as usize <= MAX_RATE_LIMIT;
+                assert_with_msg(
+                    in_bounds && (max_increments == 0 || window > 0),
+                    CounterError::InvalidRateLimit.into(),
+                    "Invalid rate limit",
+                )?;
+                rate_limit.max_increments = max_increments;
+                rate_limit.window = window;
+                let mut data = counter_ai.try_borrow_mut_data()?;
+                rate_limit.serialize(&mut &mut data[Counter::LEN..])?;
+            }
         }
         Ok(())
     }
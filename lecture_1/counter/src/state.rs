@@ -1,7 +1,72 @@
+use bootcamp_idl_derive::IdlType;
 use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
 
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+#[derive(BorshSerialize, BorshDeserialize, IdlType, Debug, Clone)]
 pub struct Counter {
     // TODO
     pub count: u64,
 }
+
+impl Counter {
+    pub const LEN: usize = 8;
+    /// Length of counters created with room for a `RateLimit` after the count
+    pub const RATE_LIMITED_LEN: usize = Self::LEN + RateLimit::LEN;
+
+    /// Decodes the count and, for counters with room for one, the rate limit.
+    pub fn unpack(data: &[u8]) -> Result<(Self, Option<RateLimit>), ProgramError> {
+        if data.len() != Self::RATE_LIMITED_LEN {
+            return Ok((Self::try_from_slice(data)?, None));
+        }
+        let (count, rate_limit) = data.split_at(Self::LEN);
+        let rate_limit = RateLimit::try_from_slice(rate_limit)?;
+        if rate_limit.len.max(rate_limit.max_increments) as usize > MAX_RATE_LIMIT {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok((Self::try_from_slice(count)?, Some(rate_limit)))
+    }
+}
+
+/// Most increments a `RateLimit` can allow per window.
+pub const MAX_RATE_LIMIT: usize = 16;
+
+/// Limits the increments of a counter to `max_increments` in any `window` seconds.
+/// Increments aren't signed, so the window holds the increments of everyone rather than
+/// those of one authority. `authority` only configures the limit, it is claimed by the
+/// first `SetRateLimit`, which the counter keypair must sign.
+#[derive(BorshSerialize, BorshDeserialize, IdlType, Default, Debug, Clone)]
+pub struct RateLimit {
+    pub authority: Pubkey,
+    pub max_increments: u8, // 0 disables the limit
+    pub window: i64,        // seconds
+    pub len: u8,            // number of recorded increments
+    /// Unix timestamps of the increments in the last window, oldest first
+    pub timestamps: [i64; MAX_RATE_LIMIT],
+}
+
+impl RateLimit {
+    pub const LEN: usize = 32 + 1 + 8 + 1 + MAX_RATE_LIMIT * 8;
+
+    /// Records an increment at `now`, unless the window before it already holds
+    /// `max_increments` increments.
+    pub fn record(&mut self, now: i64) -> bool {
+        if self.max_increments == 0 {
+            return true;
+        }
+        // Slide the window past the increments that are too old to count
+        let len = self.len as usize;
+        let expired = self.timestamps[..len]
+            .iter()
+            .take_while(|timestamp| now.saturating_sub(**timestamp) >= self.window)
+            .count();
+        self.timestamps.copy_within(expired..len, 0);
+        let len = len - expired;
+        self.len = len as u8;
+        if len >= self.max_increments as usize {
+            return false;
+        }
+        self.timestamps[len] = now;
+        self.len += 1;
+        true
+    }
+}
@@ -0,0 +1,126 @@
+#![cfg(feature = "test-bpf")]
+//! Build the program with `cargo build-bpf`, then run `cargo test-bpf`.
+
+use {
+    counter::{
+        error::CounterError,
+        instruction,
+        state::{Counter, RateLimit},
+    },
+    solana_program::{
+        clock::Clock,
+        instruction::{Instruction, InstructionError},
+        pubkey::Pubkey,
+    },
+    solana_program_test::{ProgramTest, ProgramTestContext},
+    solana_sdk::{
+        account::Account,
+        signature::{Keypair, Signer},
+        transaction::{Transaction, TransactionError},
+        transport::TransportError,
+    },
+};
+
+const WINDOW: i64 = 60;
+
+async fn process(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<(), TransportError> {
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let blockhash = context.banks_client.get_latest_blockhash().await.unwrap();
+    let mut tx = Transaction::new_with_payer(instructions, Some(&context.payer.pubkey()));
+    tx.sign(&all_signers, blockhash);
+    context.banks_client.process_transaction(tx).await
+}
+
+async fn clock(context: &mut ProgramTestContext) -> Clock {
+    context.banks_client.get_sysvar().await.unwrap()
+}
+
+/// Warps until the clock reaches `unix_timestamp`. The clock follows the slots, which
+/// are about 400ms apart.
+async fn warp_to_timestamp(context: &mut ProgramTestContext, unix_timestamp: i64) {
+    for _ in 0..100 {
+        let clock = clock(context).await;
+        if clock.unix_timestamp >= unix_timestamp {
+            return;
+        }
+        let slots = (unix_timestamp - clock.unix_timestamp) as u64 * 5 / 2;
+        context.warp_to_slot(clock.slot + slots.max(1)).unwrap();
+    }
+    panic!("Clock did not reach {}", unix_timestamp);
+}
+
+fn assert_custom_error(result: Result<(), TransportError>, error: CounterError) {
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::Custom(error as u32))
+    );
+}
+
+#[tokio::test]
+async fn test_rate_limit() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::default();
+    program_test.add_program("counter", program_id, None);
+    let counter_keypair = Keypair::new();
+    let counter = counter_keypair.pubkey();
+    let account = Account {
+        lamports: 1_000_000_000,
+        data: vec![0; Counter::RATE_LIMITED_LEN],
+        owner: program_id,
+        ..Account::default()
+    };
+    program_test.add_account(counter, account);
+    let mut context = program_test.start_with_context().await;
+
+    // Only the counter keypair lets an authority claim the rate limit
+    let authority = Keypair::new();
+    let ix = instruction::set_rate_limit(program_id, counter, authority.pubkey(), 3, WINDOW);
+    let result = process(&mut context, &[ix.unwrap()], &[&authority]).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature)
+    );
+    let ix = instruction::claim_rate_limit(program_id, counter, authority.pubkey(), 3, WINDOW);
+    process(&mut context, &[ix.unwrap()], &[&authority, &counter_keypair])
+        .await
+        .unwrap();
+    let start = clock(&mut context).await.unix_timestamp;
+    let increment = || instruction::increment(program_id, counter).unwrap();
+    process(&mut context, &[increment(), increment(), increment()], &[])
+        .await
+        .unwrap();
+    let result = process(&mut context, &[increment()], &[]).await;
+    assert_custom_error(result, CounterError::RateLimited);
+
+    // The increments leave the window as the clock moves on
+    warp_to_timestamp(&mut context, start + WINDOW).await;
+    process(&mut context, &[increment()], &[]).await.unwrap();
+    let data = context
+        .banks_client
+        .get_account(counter)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let (state, rate_limit) = Counter::unpack(&data).unwrap();
+    let RateLimit { authority: owner, len, .. } = rate_limit.unwrap();
+    assert_eq!((state.count, owner, len), (4, authority.pubkey(), 1));
+
+    // Only the authority that claimed the rate limit changes it, even with the counter
+    // keypair
+    let other = Keypair::new();
+    let ix = instruction::claim_rate_limit(program_id, counter, other.pubkey(), 0, 0);
+    let result = process(&mut context, &[ix.unwrap()], &[&other, &counter_keypair]).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature)
+    );
+    let ix = instruction::set_rate_limit(program_id, counter, authority.pubkey(), 17, WINDOW);
+    let result = process(&mut context, &[ix.unwrap()], &[&authority]).await;
+    assert_custom_error(result, CounterError::InvalidRateLimit);
+}
@@ -22,7 +22,7 @@ const initialize = (tracker, user, authority, counter, trackerProgramId) => {
       {
         pubkey: user,
         isSigner: true,
-        isWritable: false,
+        isWritable: true,
       },
       {
         pubkey: authority,
@@ -45,7 +45,36 @@ const initialize = (tracker, user, authority, counter, trackerProgramId) => {
   });
 };
 
-const increment = (tracker, user, authority, counter, counterProgramId, trackerProgramId) => {
+const initializeLeaderboard = (leaderboard, payer, counter, trackerProgramId) => {
+  return new TransactionInstruction({
+    keys: [
+      {
+        pubkey: leaderboard,
+        isSigner: false,
+        isWritable: true,
+      },
+      {
+        pubkey: payer,
+        isSigner: true,
+        isWritable: true,
+      },
+      {
+        pubkey: counter,
+        isSigner: false,
+        isWritable: false,
+      },
+      {
+        pubkey: SystemProgram.programId,
+        isSigner: false,
+        isWritable: false,
+      },
+    ],
+    data: Buffer.from(new Uint8Array([2])),
+    programId: trackerProgramId,
+  });
+};
+
+const increment = (tracker, user, authority, counter, leaderboard, counterProgramId, trackerProgramId) => {
   return new TransactionInstruction({
     keys: [
       {
@@ -73,6 +102,11 @@ const increment = (tracker, user, authority, counter, counterProgramId, trackerP
         isSigner: false,
         isWritable: false,
       },
+      {
+        pubkey: leaderboard,
+        isSigner: false,
+        isWritable: true,
+      },
     ],
     data: Buffer.from(new Uint8Array([1])),
     programId: trackerProgramId,
@@ -123,9 +157,9 @@ const main = async () => {
       fromPubkey: feePayer.publicKey,
       newAccountPubkey: counterKey,
       /** Amount of lamports to transfer to the created account */
-      lamports: await connection.getMinimumBalanceForRentExemption(40),
+      lamports: await connection.getMinimumBalanceForRentExemption(56),
       /** Amount of space in bytes to allocate to the created account */
-      space: 40,
+      space: 56,
       /** Public key of the program to assign as the owner of the created account */
       programId: counterProgramId,
     });
@@ -142,6 +176,24 @@ const main = async () => {
     trackerProgramId
   ))[0];
 
+  const leaderboardKey = (await PublicKey.findProgramAddress(
+    [Buffer.from("leaderboard"), counterKey.toBuffer()],
+    trackerProgramId
+  ))[0];
+
+  let leaderboardData = await connection.getAccountInfo(leaderboardKey, "confirmed")
+  if (!leaderboardData) {
+    console.log("    -> No leaderboard account found. Creating new leaderboard account");
+    tx.add(
+      initializeLeaderboard(
+        leaderboardKey,
+        feePayer.publicKey,
+        counterKey,
+        trackerProgramId
+      )
+    );
+  }
+
   let trackerData = await connection.getAccountInfo(trackerKey, "confirmed")
   if (!trackerData) {
     console.log("    -> No tracker account found. Creating new tracker account");
@@ -161,6 +213,7 @@ const main = async () => {
     feePayer.publicKey,
     authKey,
     counterKey,
+    leaderboardKey,
     counterProgramId,
     trackerProgramId
   )
@@ -173,19 +226,33 @@ const main = async () => {
   });
   console.log(`https://explorer.solana.com/tx/${txid}?cluster=devnet`);
 
+  // Counter layout: header (16) | authority (32) | count (8)
   let data = (await connection.getAccountInfo(counterKey, "confirmed")).data;
-  const auth = new PublicKey(data.slice(0, 32));
-  const globalCount = new BN(data.slice(32, 36), "le");
+  const auth = new PublicKey(data.slice(16, 48));
+  const globalCount = new BN(data.slice(48, 56), "le");
   console.log("Global:")
   console.log("   Counter Key:", counterKey.toBase58());
   console.log("   Counter Authority Key:", auth.toBase58());
   console.log("   Global Count: ", globalCount.toNumber());
   
   data = (await connection.getAccountInfo(trackerKey, "confirmed")).data;
-  const trackerCount = new BN(data.slice(34, 42), "le");
+  // Tracker layout: header (16) | bump (1) | auth_bump (1) | user (32) | counter (32) | count (8) | global_count (8)
+  const trackerCount = new BN(data.slice(82, 90), "le");
+  const trackerGlobalCount = new BN(data.slice(90, 98), "le");
   console.log("User:")
   console.log("   User Key:", feePayer.publicKey.toBase58());
   console.log("   User Count: ", trackerCount.toNumber());
+  console.log("   Global Count (last increment): ", trackerGlobalCount.toNumber());
+
+  // Leaderboard layout: header (16) | bump (1) | counter (32) | len (1) | entries (user (32) | count (8))
+  data = (await connection.getAccountInfo(leaderboardKey, "confirmed")).data;
+  console.log("Leaderboard:")
+  for (let i = 0; i < data[49]; i += 1) {
+    const offset = 50 + i * 40;
+    const user = new PublicKey(data.slice(offset, offset + 32));
+    const count = new BN(data.slice(offset + 32, offset + 40), "le");
+    console.log(`   ${i + 1}. ${user.toBase58()}: ${count.toNumber()}`);
+  }
 };
 
 main()
@@ -1,10 +1,23 @@
-use solana_program::program_error::ProgramError;
+use num_derive::FromPrimitive;
+use solana_program::{
+    decode_error::DecodeError,
+    msg,
+    program_error::{PrintProgramError, ProgramError},
+};
 use thiserror::Error;
 
-#[derive(Error, Debug, Copy, Clone, PartialEq)]
+#[derive(Error, Debug, Copy, Clone, FromPrimitive, PartialEq)]
 pub enum CounterError {
     #[error("Instruction not implemented.")]
     NotImplemented,
+    #[error("The count would overflow.")]
+    Overflow,
+    #[error("Account is already on the latest version.")]
+    AlreadyMigrated,
+    #[error("Counter must be incremented once before it is sharded.")]
+    UnclaimedCounter,
+    #[error("Shard does not belong to the counter.")]
+    ShardMismatch,
 }
 
 impl From<CounterError> for ProgramError {
@@ -12,3 +25,22 @@ impl From<CounterError> for ProgramError {
         ProgramError::Custom(e as u32)
     }
 }
+
+impl<T> DecodeError<T> for CounterError {
+    fn type_of() -> &'static str {
+        "CounterError"
+    }
+}
+
+impl PrintProgramError for CounterError {
+    fn print<E>(&self)
+    where
+        E: 'static
+            + std::error::Error
+            + DecodeError<E>
+            + PrintProgramError
+            + num_traits::FromPrimitive,
+    {
+        msg!("Error: {}", self);
+    }
+}
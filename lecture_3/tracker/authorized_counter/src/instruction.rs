@@ -1,13 +1,58 @@
+use bootcamp_idl_derive::IdlInstruction;
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
     instruction::{AccountMeta, Instruction},
     program_error::ProgramError,
     pubkey::Pubkey,
+    system_program,
 };
 
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+#[derive(BorshSerialize, BorshDeserialize, IdlInstruction, Debug, Clone)]
 pub enum CounterInstruction {
+    /// Publishes the new count as return data, a little-endian u64.
+    #[account(0, writable, name = "counter", desc = "Counter owned by the program")]
+    #[account(
+        1,
+        signer,
+        name = "authority",
+        desc = "Claims the counter on the first increment"
+    )]
     Increment,
+    /// Adds the account header to a counter created before counters had one. Counters
+    /// without a header keep working until they are migrated.
+    #[account(0, writable, name = "counter", desc = "Counter owned by the program")]
+    #[account(
+        1,
+        writable,
+        signer,
+        name = "payer",
+        desc = "Pays for the larger counter"
+    )]
+    #[account(2, name = "system_program", desc = "Used to top up the rent")]
+    Migrate,
+    /// Creates shard `index` of a counter. Increments through its shards don't write-lock
+    /// the counter.
+    #[account(0, writable, name = "shard", desc = "PDA of [counter, [index]]")]
+    #[account(
+        1,
+        name = "counter",
+        desc = "Counter that was incremented at least once"
+    )]
+    #[account(2, signer, name = "authority", desc = "Authority of the counter")]
+    #[account(3, writable, signer, name = "payer", desc = "Pays for the shard")]
+    #[account(4, name = "system_program", desc = "Used to allocate the shard")]
+    InitializeShard { index: u8 },
+    #[account(0, writable, name = "shard", desc = "Shard of the counter")]
+    #[account(1, signer, name = "authority", desc = "Authority of the counter")]
+    IncrementShard,
+    /// Moves the counts of the shards, passed in as writable accounts after the counter,
+    /// into the counter. Anyone can aggregate, the total count doesn't change.
+    #[account(0, writable, name = "counter", desc = "Counter owned by the program")]
+    Aggregate,
+    /// Publishes the count of a counter as return data, like `Increment` does. Only reads
+    /// the counter, so clients can simulate it.
+    #[account(0, name = "counter", desc = "Counter owned by the program")]
+    Get,
 }
 
 pub fn increment(
@@ -26,3 +71,77 @@ pub fn increment(
         program_id,
     })
 }
+
+pub fn migrate(
+    program_id: Pubkey,
+    counter: Pubkey,
+    payer: Pubkey,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction {
+        accounts: vec![
+            AccountMeta::new(counter, false),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: CounterInstruction::Migrate.try_to_vec()?,
+        program_id,
+    })
+}
+
+pub fn initialize_shard(
+    program_id: Pubkey,
+    shard: Pubkey,
+    counter: Pubkey,
+    authority: Pubkey,
+    payer: Pubkey,
+    index: u8,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction {
+        accounts: vec![
+            AccountMeta::new(shard, false),
+            AccountMeta::new_readonly(counter, false),
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: CounterInstruction::InitializeShard { index }.try_to_vec()?,
+        program_id,
+    })
+}
+
+pub fn increment_shard(
+    program_id: Pubkey,
+    shard: Pubkey,
+    authority: Pubkey,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction {
+        accounts: vec![
+            AccountMeta::new(shard, false),
+            AccountMeta::new_readonly(authority, true),
+        ],
+        data: CounterInstruction::IncrementShard.try_to_vec()?,
+        program_id,
+    })
+}
+
+pub fn aggregate(
+    program_id: Pubkey,
+    counter: Pubkey,
+    shards: &[Pubkey],
+) -> Result<Instruction, ProgramError> {
+    let mut accounts = vec![AccountMeta::new(counter, false)];
+    accounts.extend(shards.iter().map(|shard| AccountMeta::new(*shard, false)));
+    Ok(Instruction {
+        accounts,
+        data: CounterInstruction::Aggregate.try_to_vec()?,
+        program_id,
+    })
+}
+
+pub fn get(program_id: Pubkey, counter: Pubkey) -> Result<Instruction, ProgramError> {
+    Ok(Instruction {
+        accounts: vec![AccountMeta::new_readonly(counter, false)],
+        data: CounterInstruction::Get.try_to_vec()?,
+        program_id,
+    })
+}
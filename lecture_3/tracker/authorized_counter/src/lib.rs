@@ -3,3 +3,15 @@ pub mod error;
 pub mod instruction;
 pub mod processor;
 pub mod state;
+
+solana_program::declare_id!("EnDET6JzFbb9uAaskPmAde6ooYXzsiRM224ZJ7w2Qw6r");
+
+/// Anchor compatible IDL of the program.
+#[cfg(feature = "idl")]
+pub fn idl() -> bootcamp_idl::Idl {
+    bootcamp_idl::Idl::new("authorized_counter", env!("CARGO_PKG_VERSION"))
+        .address(id())
+        .instructions::<instruction::CounterInstruction>()
+        .account::<state::Counter>()
+        .account::<state::Shard>()
+}
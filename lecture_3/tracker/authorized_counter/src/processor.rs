@@ -1,29 +1,27 @@
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::BorshDeserialize;
+use bootcamp_accounts::BorshAccount;
+use bootcamp_program_utils::{
+    assert_with_msg, create_pda_account, expect_owner, expect_pda, expect_program,
+    expect_signer, expect_writable, resize_account, set_return_data,
+};
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint::ProgramResult,
     msg,
     program_error::ProgramError,
     pubkey::Pubkey,
+    system_program,
 };
 
+use crate::error::CounterError;
 use crate::instruction::CounterInstruction;
-use crate::state::Counter;
-
-pub fn assert_with_msg(statement: bool, err: ProgramError, msg: &str) -> ProgramResult {
-    if !statement {
-        msg!(msg);
-        Err(err)
-    } else {
-        Ok(())
-    }
-}
+use crate::state::{Counter, LegacyCounter, Shard};
 
 pub struct Processor {}
 
 impl Processor {
     pub fn process_instruction(
-        _program_id: &Pubkey,
+        program_id: &Pubkey,
         accounts: &[AccountInfo],
         instruction_data: &[u8],
     ) -> ProgramResult {
@@ -37,13 +35,15 @@ impl Processor {
                 let accounts_iter = &mut accounts.iter();
                 let counter_ai = next_account_info(accounts_iter)?;
                 let authority = next_account_info(accounts_iter)?;
-                assert_with_msg(
-                    authority.is_signer,
+                expect_owner(counter_ai, program_id, "Counter must be owned by the program")?;
+                expect_writable(counter_ai, "Counter must be writable")?;
+                expect_signer(
+                    authority,
                     ProgramError::MissingRequiredSignature,
                     "Authority must sign",
                 )?;
                 // Deserialize account data
-                let mut counter = Counter::try_from_slice(&counter_ai.data.borrow())?;
+                let mut counter = Counter::unpack_any(&counter_ai.try_borrow_data()?)?;
                 if counter.count == 0 {
                     // Set the authority if this is the first time the counter has been used. 
                     counter.authority = *authority.key;
@@ -54,10 +54,156 @@ impl Processor {
                     "Attempted to increment with an invalid authority",
                 )?;
                 // Update account data
-                counter.count += 1;
+                counter.count = counter.count.checked_add(1).ok_or(CounterError::Overflow)?;
                 msg!("Global count: {}", counter.count);
                 // Serialize account
-                counter.serialize(&mut *counter_ai.data.borrow_mut())?;
+                counter.save_any(counter_ai, program_id)?;
+                // Lets callers read the new count after the CPI
+                set_return_data(&counter.count.to_le_bytes());
+            }
+            CounterInstruction::Migrate => {
+                msg!("Instruction: Migrate");
+                let accounts_iter = &mut accounts.iter();
+                let counter_ai = next_account_info(accounts_iter)?;
+                let payer = next_account_info(accounts_iter)?;
+                let system_program = next_account_info(accounts_iter)?;
+                expect_owner(counter_ai, program_id, "Counter must be owned by the program")?;
+                expect_writable(counter_ai, "Counter must be writable")?;
+                expect_signer(payer, ProgramError::MissingRequiredSignature, "Payer must sign")?;
+                expect_writable(payer, "Payer must be writable to pay for the migration")?;
+                expect_program(
+                    system_program,
+                    &system_program::id(),
+                    ProgramError::InvalidArgument,
+                    "Invalid passed in for system program",
+                )?;
+                assert_with_msg(
+                    counter_ai.data_len() == LegacyCounter::LEN,
+                    CounterError::AlreadyMigrated.into(),
+                    "Counter already has an account header",
+                )?;
+
+                let counter = Counter::unpack_any(&counter_ai.try_borrow_data()?)?;
+                resize_account(counter_ai, payer, system_program, Counter::LEN)?;
+                // The header takes the place of the old data
+                counter_ai.try_borrow_mut_data()?.fill(0);
+                counter.init(counter_ai, program_id)?;
+            }
+            CounterInstruction::InitializeShard { index } => {
+                msg!("Instruction: InitializeShard");
+                let accounts_iter = &mut accounts.iter();
+                let shard_ai = next_account_info(accounts_iter)?;
+                let counter_ai = next_account_info(accounts_iter)?;
+                let authority = next_account_info(accounts_iter)?;
+                let payer = next_account_info(accounts_iter)?;
+                let system_program = next_account_info(accounts_iter)?;
+                expect_owner(counter_ai, program_id, "Counter must be owned by the program")?;
+                expect_writable(shard_ai, "Shard must be writable")?;
+                expect_signer(
+                    authority,
+                    ProgramError::MissingRequiredSignature,
+                    "Authority must sign",
+                )?;
+                expect_signer(payer, ProgramError::MissingRequiredSignature, "Payer must sign")?;
+                expect_writable(payer, "Payer must be writable to pay for the shard")?;
+                expect_program(
+                    system_program,
+                    &system_program::id(),
+                    ProgramError::InvalidArgument,
+                    "Invalid passed in for system program",
+                )?;
+                let counter = Counter::unpack_any(&counter_ai.try_borrow_data()?)?;
+                // The first increment claims the counter, shards copy the final authority
+                assert_with_msg(
+                    counter.count > 0,
+                    CounterError::UnclaimedCounter.into(),
+                    "Counter must be incremented before it is sharded",
+                )?;
+                assert_with_msg(
+                    counter.authority == *authority.key,
+                    ProgramError::MissingRequiredSignature,
+                    "Attempted to shard with an invalid authority",
+                )?;
+                let bump = expect_pda(
+                    shard_ai,
+                    &[counter_ai.key.as_ref(), &[index]],
+                    program_id,
+                    "Invalid PDA seeds for shard",
+                )?;
+
+                // Lets PDAs of other programs, which can't pay, own sharded counters
+                create_pda_account(
+                    payer,
+                    shard_ai,
+                    system_program,
+                    program_id,
+                    Shard::LEN,
+                    &[counter_ai.key.as_ref(), &[index], &[bump]],
+                )?;
+                let shard = Shard {
+                    bump,
+                    index,
+                    counter: *counter_ai.key,
+                    authority: *authority.key,
+                    count: 0,
+                };
+                shard.init(shard_ai, program_id)?;
+            }
+            CounterInstruction::IncrementShard => {
+                msg!("Instruction: IncrementShard");
+                let accounts_iter = &mut accounts.iter();
+                let shard_ai = next_account_info(accounts_iter)?;
+                let authority = next_account_info(accounts_iter)?;
+                expect_writable(shard_ai, "Shard must be writable")?;
+                expect_signer(
+                    authority,
+                    ProgramError::MissingRequiredSignature,
+                    "Authority must sign",
+                )?;
+                let mut shard = Shard::load(shard_ai, program_id)?;
+                assert_with_msg(
+                    shard.authority == *authority.key,
+                    ProgramError::MissingRequiredSignature,
+                    "Attempted to increment with an invalid authority",
+                )?;
+                shard.count = shard.count.checked_add(1).ok_or(CounterError::Overflow)?;
+                msg!("Shard {} count: {}", shard.index, shard.count);
+                shard.save(shard_ai)?;
+            }
+            CounterInstruction::Aggregate => {
+                msg!("Instruction: Aggregate");
+                let accounts_iter = &mut accounts.iter();
+                let counter_ai = next_account_info(accounts_iter)?;
+                expect_owner(counter_ai, program_id, "Counter must be owned by the program")?;
+                expect_writable(counter_ai, "Counter must be writable")?;
+                let mut counter = Counter::unpack_any(&counter_ai.try_borrow_data()?)?;
+                // Every shard is drained before the next one is read, so a shard passed in
+                // twice is only counted once
+                for shard_ai in accounts_iter {
+                    expect_writable(shard_ai, "Shard must be writable")?;
+                    let mut shard = Shard::load(shard_ai, program_id)?;
+                    assert_with_msg(
+                        shard.counter == *counter_ai.key,
+                        CounterError::ShardMismatch.into(),
+                        "Shard does not belong to the counter",
+                    )?;
+                    counter.count = counter
+                        .count
+                        .checked_add(shard.count)
+                        .ok_or(CounterError::Overflow)?;
+                    shard.count = 0;
+                    shard.save(shard_ai)?;
+                }
+                msg!("Global count: {}", counter.count);
+                counter.save_any(counter_ai, program_id)?;
+            }
+            CounterInstruction::Get => {
+                msg!("Instruction: Get");
+                let accounts_iter = &mut accounts.iter();
+                let counter_ai = next_account_info(accounts_iter)?;
+                expect_owner(counter_ai, program_id, "Counter must be owned by the program")?;
+                let counter = Counter::unpack_any(&counter_ai.try_borrow_data()?)?;
+                set_return_data(&counter.count.to_le_bytes());
             }
         }
         Ok(())
@@ -1,8 +1,104 @@
-use solana_program::{pubkey::Pubkey};
+use bootcamp_accounts::{BorshAccount, HEADER_LEN};
+use bootcamp_idl_derive::IdlType;
 use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+    pubkey::Pubkey,
+};
 
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+#[derive(BorshSerialize, BorshDeserialize, BorshAccount, IdlType, Default, Debug, Clone)]
 pub struct Counter {
     pub authority: Pubkey,
     pub count: u64,
 }
+
+impl Counter {
+    pub const LEN: usize = HEADER_LEN + 32 + 8;
+
+    /// Decodes a counter with or without an account header. Zeroed accounts are new
+    /// counters.
+    pub fn unpack_any(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() == LegacyCounter::LEN {
+            return Ok(LegacyCounter::try_from_slice(data)?.into());
+        }
+        match Self::unpack(data) {
+            Err(ProgramError::UninitializedAccount) => Ok(Self::default()),
+            result => result,
+        }
+    }
+
+    /// Writes the counter in the layout `account` is on, new counters get a header.
+    pub fn save_any(&self, account: &AccountInfo, program_id: &Pubkey) -> ProgramResult {
+        if account.data_len() == LegacyCounter::LEN {
+            let legacy = LegacyCounter::from(self.clone());
+            return Ok(legacy.serialize(&mut *account.try_borrow_mut_data()?)?);
+        }
+        match self.save(account) {
+            Err(ProgramError::UninitializedAccount) => self.init(account, program_id),
+            result => result,
+        }
+    }
+}
+
+/// One of the shards of a counter. Increments through a shard leave the counter alone,
+/// so incrementers only contend for a write lock when they pick the same shard.
+/// `Aggregate` moves the counts of the shards into the counter.
+/// PDA seeds: [counter, [index]]
+#[derive(BorshSerialize, BorshDeserialize, BorshAccount, IdlType, Debug, Clone)]
+pub struct Shard {
+    pub bump: u8,
+    pub index: u8,
+    pub counter: Pubkey,
+    pub authority: Pubkey, // copied from the counter, which can't change once claimed
+    pub count: u64,        // increments since the last aggregation
+}
+
+impl Shard {
+    pub const LEN: usize = HEADER_LEN + 1 + 1 + 32 + 32 + 8;
+
+    pub fn find_address(program_id: &Pubkey, counter: &Pubkey, index: u8) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[counter.as_ref(), &[index]], program_id)
+    }
+}
+
+/// Count of a sharded counter, including the increments its shards hold until the next
+/// aggregation. `shards` must belong to `counter`.
+pub fn total_count(counter: &Counter, shards: &[Shard]) -> Option<u64> {
+    shards
+        .iter()
+        .try_fold(counter.count, |total, shard| total.checked_add(shard.count))
+}
+
+/// Decodes the count that `Increment` and `Get` publish as return data.
+pub fn returned_count(data: &[u8]) -> Option<u64> {
+    data.try_into().ok().map(u64::from_le_bytes)
+}
+
+/// Layout of counters created before account headers, which `Migrate` converts.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct LegacyCounter {
+    pub authority: Pubkey,
+    pub count: u64,
+}
+
+impl LegacyCounter {
+    pub const LEN: usize = 32 + 8;
+}
+
+impl From<LegacyCounter> for Counter {
+    fn from(counter: LegacyCounter) -> Self {
+        Self {
+            authority: counter.authority,
+            count: counter.count,
+        }
+    }
+}
+
+impl From<Counter> for LegacyCounter {
+    fn from(counter: Counter) -> Self {
+        Self {
+            authority: counter.authority,
+            count: counter.count,
+        }
+    }
+}
@@ -1,10 +1,25 @@
-use solana_program::program_error::ProgramError;
+use num_derive::FromPrimitive;
+use solana_program::{
+    decode_error::DecodeError,
+    msg,
+    program_error::{PrintProgramError, ProgramError},
+};
 use thiserror::Error;
 
-#[derive(Error, Debug, Copy, Clone, PartialEq)]
+#[derive(Error, Debug, Copy, Clone, FromPrimitive, PartialEq)]
 pub enum CounterError {
-    #[error("Instruction not implemented.")]
-    NotImplemented,
+    #[error("Counter program does not match the authorized_counter program id.")]
+    InvalidCounterProgram,
+    #[error("User must sign.")]
+    MissingUserSignature,
+    #[error("Counter does not match the counter stored in the tracker.")]
+    CounterMismatch,
+    #[error("The same account was passed in more than once.")]
+    DuplicateAccount,
+    #[error("Account is already on the latest version.")]
+    AlreadyMigrated,
+    #[error("Counter program did not return the global count.")]
+    InvalidReturnData,
 }
 
 impl From<CounterError> for ProgramError {
@@ -12,3 +27,22 @@ impl From<CounterError> for ProgramError {
         ProgramError::Custom(e as u32)
     }
 }
+
+impl<T> DecodeError<T> for CounterError {
+    fn type_of() -> &'static str {
+        "CounterError"
+    }
+}
+
+impl PrintProgramError for CounterError {
+    fn print<E>(&self)
+    where
+        E: 'static
+            + std::error::Error
+            + DecodeError<E>
+            + PrintProgramError
+            + num_traits::FromPrimitive,
+    {
+        msg!("Error: {}", self);
+    }
+}
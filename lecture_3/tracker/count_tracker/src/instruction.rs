@@ -1,7 +1,206 @@
+use authorized_counter::state::Shard;
+use bootcamp_idl_derive::IdlInstruction;
 use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+};
 
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+#[derive(BorshSerialize, BorshDeserialize, IdlInstruction, Debug, Clone)]
 pub enum TrackerInstruction {
+    #[account(0, writable, name = "tracker", desc = "PDA of [user, counter]")]
+    #[account(1, writable, signer, name = "user", desc = "Pays for the tracker")]
+    #[account(2, name = "authority", desc = "PDA of [counter], authority of counter")]
+    #[account(3, name = "counter", desc = "authorized_counter account")]
+    #[account(4, name = "system_program", desc = "Used to allocate the tracker")]
     Initialize,
+    /// Increments the counter through a CPI, stores the global count it returns in the
+    /// tracker and publishes the user's new count as return data.
+    #[account(0, writable, name = "tracker", desc = "PDA of [user, counter]")]
+    #[account(1, signer, name = "user", desc = "Owner of the tracker")]
+    #[account(2, name = "counter_program", desc = "authorized_counter program")]
+    #[account(3, writable, name = "counter", desc = "authorized_counter account")]
+    #[account(4, name = "authority", desc = "PDA of [counter], authority of counter")]
+    #[account(
+        5,
+        writable,
+        name = "leaderboard",
+        desc = "PDA of [\"leaderboard\", counter]"
+    )]
     Increment,
+    #[account(
+        0,
+        writable,
+        name = "leaderboard",
+        desc = "PDA of [\"leaderboard\", counter]"
+    )]
+    #[account(1, writable, signer, name = "payer", desc = "Pays for the leaderboard")]
+    #[account(2, name = "counter", desc = "authorized_counter account")]
+    #[account(3, name = "system_program", desc = "Used to allocate the leaderboard")]
+    InitializeLeaderboard,
+    /// Rewrites a tracker created by an older version of the program in the current
+    /// layout. Trackers of older versions keep working until they are migrated.
+    #[account(0, writable, name = "tracker", desc = "PDA of [user, counter]")]
+    #[account(1, name = "user", desc = "Owner of the tracker")]
+    #[account(
+        2,
+        writable,
+        signer,
+        name = "payer",
+        desc = "Pays for the larger tracker"
+    )]
+    #[account(3, name = "system_program", desc = "Used to top up the rent")]
+    Migrate,
+    /// Creates shard `index` of the counter, with the authority PDA as its authority. The
+    /// counter must have been incremented through a tracker first.
+    #[account(0, writable, name = "shard", desc = "authorized_counter PDA of [counter, [index]]")]
+    #[account(1, writable, signer, name = "payer", desc = "Pays for the shard")]
+    #[account(2, name = "counter_program", desc = "authorized_counter program")]
+    #[account(3, name = "counter", desc = "authorized_counter account")]
+    #[account(4, name = "authority", desc = "PDA of [counter], authority of counter")]
+    #[account(5, name = "system_program", desc = "Used to allocate the shard")]
+    InitializeShard { index: u8 },
+    /// Increments shard `index` of the counter instead of the counter, so trackers of the
+    /// same counter only contend for a write lock when they pick the same shard. The
+    /// global count in the tracker is only updated by `Increment`.
+    #[account(0, writable, name = "tracker", desc = "PDA of [user, counter]")]
+    #[account(1, signer, name = "user", desc = "Owner of the tracker")]
+    #[account(2, name = "counter_program", desc = "authorized_counter program")]
+    #[account(3, name = "counter", desc = "authorized_counter account")]
+    #[account(4, name = "authority", desc = "PDA of [counter], authority of counter")]
+    #[account(5, writable, name = "shard", desc = "authorized_counter PDA of [counter, [index]]")]
+    #[account(
+        6,
+        writable,
+        name = "leaderboard",
+        desc = "PDA of [\"leaderboard\", counter]"
+    )]
+    IncrementShard { index: u8 },
+}
+
+pub fn initialize(
+    program_id: Pubkey,
+    tracker: Pubkey,
+    user: Pubkey,
+    authority: Pubkey,
+    counter: Pubkey,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction {
+        accounts: vec![
+            AccountMeta::new(tracker, false),
+            AccountMeta::new(user, true),
+            AccountMeta::new_readonly(authority, false),
+            AccountMeta::new_readonly(counter, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: TrackerInstruction::Initialize.try_to_vec()?,
+        program_id,
+    })
+}
+
+pub fn increment(
+    program_id: Pubkey,
+    tracker: Pubkey,
+    user: Pubkey,
+    counter: Pubkey,
+    authority: Pubkey,
+    leaderboard: Pubkey,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction {
+        accounts: vec![
+            AccountMeta::new(tracker, false),
+            AccountMeta::new_readonly(user, true),
+            AccountMeta::new_readonly(authorized_counter::id(), false),
+            AccountMeta::new(counter, false),
+            AccountMeta::new_readonly(authority, false),
+            AccountMeta::new(leaderboard, false),
+        ],
+        data: TrackerInstruction::Increment.try_to_vec()?,
+        program_id,
+    })
+}
+
+pub fn initialize_leaderboard(
+    program_id: Pubkey,
+    leaderboard: Pubkey,
+    payer: Pubkey,
+    counter: Pubkey,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction {
+        accounts: vec![
+            AccountMeta::new(leaderboard, false),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(counter, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: TrackerInstruction::InitializeLeaderboard.try_to_vec()?,
+        program_id,
+    })
+}
+
+pub fn migrate(
+    program_id: Pubkey,
+    tracker: Pubkey,
+    user: Pubkey,
+    payer: Pubkey,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction {
+        accounts: vec![
+            AccountMeta::new(tracker, false),
+            AccountMeta::new_readonly(user, false),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: TrackerInstruction::Migrate.try_to_vec()?,
+        program_id,
+    })
+}
+
+pub fn initialize_shard(
+    program_id: Pubkey,
+    payer: Pubkey,
+    counter: Pubkey,
+    authority: Pubkey,
+    index: u8,
+) -> Result<Instruction, ProgramError> {
+    let (shard, _) = Shard::find_address(&authorized_counter::id(), &counter, index);
+    Ok(Instruction {
+        accounts: vec![
+            AccountMeta::new(shard, false),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(authorized_counter::id(), false),
+            AccountMeta::new_readonly(counter, false),
+            AccountMeta::new_readonly(authority, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: TrackerInstruction::InitializeShard { index }.try_to_vec()?,
+        program_id,
+    })
+}
+
+pub fn increment_shard(
+    program_id: Pubkey,
+    tracker: Pubkey,
+    user: Pubkey,
+    counter: Pubkey,
+    authority: Pubkey,
+    leaderboard: Pubkey,
+    index: u8,
+) -> Result<Instruction, ProgramError> {
+    let (shard, _) = Shard::find_address(&authorized_counter::id(), &counter, index);
+    Ok(Instruction {
+        accounts: vec![
+            AccountMeta::new(tracker, false),
+            AccountMeta::new_readonly(user, true),
+            AccountMeta::new_readonly(authorized_counter::id(), false),
+            AccountMeta::new_readonly(counter, false),
+            AccountMeta::new_readonly(authority, false),
+            AccountMeta::new(shard, false),
+            AccountMeta::new(leaderboard, false),
+        ],
+        data: TrackerInstruction::IncrementShard { index }.try_to_vec()?,
+        program_id,
+    })
 }
@@ -3,3 +3,16 @@ pub mod error;
 pub mod instruction;
 pub mod processor;
 pub mod state;
+
+solana_program::declare_id!("CeAA9rP7xNJZWgrsPp84TSiQr32KGjh5VYvGSuYk7iSt");
+
+/// Anchor compatible IDL of the program.
+#[cfg(feature = "idl")]
+pub fn idl() -> bootcamp_idl::Idl {
+    bootcamp_idl::Idl::new("count_tracker", env!("CARGO_PKG_VERSION"))
+        .address(id())
+        .instructions::<instruction::TrackerInstruction>()
+        .account::<state::Tracker>()
+        .account::<state::Leaderboard>()
+        .ty::<state::LeaderboardEntry>()
+}
@@ -1,28 +1,23 @@
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::BorshDeserialize;
+use bootcamp_accounts::{header_version, BorshAccount, Discriminator};
+use bootcamp_program_utils::{
+    assert_with_msg, create_pda_account, expect_pda, expect_pda_with_bump, expect_program,
+    expect_signer, expect_unique, expect_writable, resize_account, set_return_data,
+};
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint::ProgramResult,
     msg,
-    program::{invoke_signed, invoke},
+    program::{get_return_data, invoke_signed},
     program_error::ProgramError,
     pubkey::Pubkey,
-    system_instruction,
     system_program::ID as SYSTEM_PROGRAM_ID,
-    sysvar::{rent::Rent, Sysvar},
 };
-use authorized_counter::instruction::CounterInstruction;
+use authorized_counter::{instruction::CounterInstruction, state::returned_count};
 
+use crate::error::CounterError;
 use crate::instruction::TrackerInstruction;
-use crate::state::Tracker;
-
-pub fn assert_with_msg(statement: bool, err: ProgramError, msg: &str) -> ProgramResult {
-    if !statement {
-        msg!(msg);
-        Err(err)
-    } else {
-        Ok(())
-    }
-}
+use crate::state::{Leaderboard, LegacyTracker, Tracker};
 
 pub struct Processor {}
 
@@ -45,50 +40,57 @@ impl Processor {
                 let counter = next_account_info(accounts_iter)?;
                 let system_program = next_account_info(accounts_iter)?;
 
-                let (authority_key, auth_bump) =
-                    Pubkey::find_program_address(&[counter.key.as_ref()], program_id);
-
-                let (tracker_key, bump) = Pubkey::find_program_address(
-                    &[user.key.as_ref(), counter.key.as_ref()],
-                    program_id,
-                );
-                // invoke: use when no PDAs
-                // invoke_signed: use when PDAs sign
-                invoke_signed(
-                    &system_instruction::create_account(
-                        user.key,
-                        tracker_ai.key,
-                        Rent::get()?.minimum_balance(42),
-                        42,
-                        program_id,
-                    ),
-                    // making sure downstream program has all necessary data
-                    &[user.clone(), tracker_ai.clone(), system_program.clone()],
-                    &[&[user.key.as_ref(), counter.key.as_ref(), &[bump]]], // signature
+                // Validate every account before the CPI
+                expect_unique(
+                    &[tracker_ai, user, authority, counter, system_program],
+                    CounterError::DuplicateAccount.into(),
                 )?;
-                assert_with_msg(
-                    *system_program.key == SYSTEM_PROGRAM_ID,
+                expect_signer(
+                    user,
+                    CounterError::MissingUserSignature.into(),
+                    "User must sign",
+                )?;
+                expect_writable(user, "User must be writable to pay for the tracker")?;
+                expect_writable(tracker_ai, "Tracker must be writable")?;
+                expect_program(
+                    system_program,
+                    &SYSTEM_PROGRAM_ID,
                     ProgramError::InvalidArgument,
                     "Invalid passed in for system program",
                 )?;
-                assert_with_msg(
-                    tracker_key == *tracker_ai.key,
-                    ProgramError::InvalidArgument,
+                let bump = expect_pda(
+                    tracker_ai,
+                    &[user.key.as_ref(), counter.key.as_ref()],
+                    program_id,
                     "Invalid PDA seeds for tracker",
                 )?;
-                assert_with_msg(
-                    authority_key == *authority.key,
-                    ProgramError::InvalidArgument,
+                let auth_bump = expect_pda(
+                    authority,
+                    &[counter.key.as_ref()],
+                    program_id,
                     "Invalid PDA seeds for authority",
                 )?;
 
-                let mut tracker = Tracker::try_from_slice(&tracker_ai.data.borrow())?;
-                tracker.bump = bump;
-                tracker.auth_bump = auth_bump;
-                // Not necessary but potentially useful for client side queries
-                tracker.counter = *counter.key;
-                tracker.count = 0;
-                tracker.serialize(&mut *tracker_ai.data.borrow_mut())?;
+                // The tracker PDA signs for its own creation
+                create_pda_account(
+                    user,
+                    tracker_ai,
+                    system_program,
+                    program_id,
+                    Tracker::LEN,
+                    &[user.key.as_ref(), counter.key.as_ref(), &[bump]],
+                )?;
+
+                let tracker = Tracker {
+                    bump,
+                    auth_bump,
+                    // Not necessary but potentially useful for client side queries
+                    user: *user.key,
+                    counter: *counter.key,
+                    count: 0,
+                    global_count: 0,
+                };
+                tracker.init(tracker_ai, program_id)?;
             }
             TrackerInstruction::Increment => {
                 msg!("Instruction: Increment");
@@ -98,22 +100,58 @@ impl Processor {
                 let counter_program = next_account_info(accounts_iter)?;
                 let counter = next_account_info(accounts_iter)?;
                 let authority = next_account_info(accounts_iter)?;
+                let leaderboard_ai = next_account_info(accounts_iter)?;
+
+                expect_unique(
+                    &[tracker_ai, user, counter_program, counter, authority, leaderboard_ai],
+                    CounterError::DuplicateAccount.into(),
+                )?;
+                expect_signer(
+                    user,
+                    CounterError::MissingUserSignature.into(),
+                    "User must sign",
+                )?;
+                expect_program(
+                    counter_program,
+                    &authorized_counter::id(),
+                    CounterError::InvalidCounterProgram.into(),
+                    "Invalid passed in for counter program",
+                )?;
+                expect_writable(tracker_ai, "Tracker must be writable")?;
+                expect_writable(counter, "Counter must be writable")?;
+                expect_writable(leaderboard_ai, "Leaderboard must be writable")?;
 
                 // Deserialize account data
-                let mut tracker = Tracker::try_from_slice(&tracker_ai.data.borrow())?;
-                // Validate tracker seeds
-                let tracker_seeds = &[user.key.as_ref(), counter.key.as_ref(), &[tracker.bump]];
-                let tracker_key = Pubkey::create_program_address(tracker_seeds, program_id)?;
+                let mut tracker = Tracker::load_any(tracker_ai, program_id)?;
                 assert_with_msg(
-                    tracker_key == *tracker_ai.key,
-                    ProgramError::InvalidArgument,
+                    tracker.counter == *counter.key,
+                    CounterError::CounterMismatch.into(),
+                    "Counter does not match the tracker's counter",
+                )?;
+                // Validate PDA seeds
+                expect_pda_with_bump(
+                    tracker_ai,
+                    &[user.key.as_ref(), counter.key.as_ref(), &[tracker.bump]],
+                    program_id,
                     "Invalid PDA seeds for tracker",
                 )?;
+                expect_pda_with_bump(
+                    authority,
+                    &[counter.key.as_ref(), &[tracker.auth_bump]],
+                    program_id,
+                    "Invalid PDA seeds for authority",
+                )?;
+                let mut leaderboard = Leaderboard::load(leaderboard_ai, program_id)?;
+                expect_pda_with_bump(
+                    leaderboard_ai,
+                    &[b"leaderboard", counter.key.as_ref(), &[leaderboard.bump]],
+                    program_id,
+                    "Invalid PDA seeds for leaderboard",
+                )?;
 
-                // TODO CPI to authorized_counter
                 invoke_signed(
                     &authorized_counter::instruction::increment(
-                        *counter_program.key,
+                        authorized_counter::id(),
                         *counter.key,
                         *authority.key,
                         CounterInstruction::Increment)?,
@@ -124,9 +162,246 @@ impl Processor {
                     ],
                     &[&[counter.key.as_ref(), &[tracker.auth_bump]]],
                 )?;
+                // Snapshot the global count without reading the counter again
+                let global_count = get_return_data()
+                    .filter(|(program_id, _)| *program_id == authorized_counter::id())
+                    .and_then(|(_, data)| returned_count(&data))
+                    .ok_or(CounterError::InvalidReturnData)?;
+                tracker.count += 1;
+                tracker.global_count = global_count;
+                msg!("User Count {}", tracker.count);
+                tracker.save_any(tracker_ai)?;
+                set_return_data(&tracker.count.to_le_bytes());
+
+                leaderboard.record(*user.key, tracker.count);
+                leaderboard.save(leaderboard_ai)?;
+            }
+            TrackerInstruction::InitializeLeaderboard => {
+                msg!("Instruction: InitializeLeaderboard");
+                let leaderboard_ai = next_account_info(accounts_iter)?;
+                let payer = next_account_info(accounts_iter)?;
+                let counter = next_account_info(accounts_iter)?;
+                let system_program = next_account_info(accounts_iter)?;
+
+                expect_unique(
+                    &[leaderboard_ai, payer, counter, system_program],
+                    CounterError::DuplicateAccount.into(),
+                )?;
+                expect_signer(
+                    payer,
+                    ProgramError::MissingRequiredSignature,
+                    "Payer must sign",
+                )?;
+                expect_writable(payer, "Payer must be writable to pay for the leaderboard")?;
+                expect_writable(leaderboard_ai, "Leaderboard must be writable")?;
+                expect_program(
+                    system_program,
+                    &SYSTEM_PROGRAM_ID,
+                    ProgramError::InvalidArgument,
+                    "Invalid passed in for system program",
+                )?;
+                let bump = expect_pda(
+                    leaderboard_ai,
+                    &[b"leaderboard", counter.key.as_ref()],
+                    program_id,
+                    "Invalid PDA seeds for leaderboard",
+                )?;
+
+                create_pda_account(
+                    payer,
+                    leaderboard_ai,
+                    system_program,
+                    program_id,
+                    Leaderboard::LEN,
+                    &[b"leaderboard", counter.key.as_ref(), &[bump]],
+                )?;
+
+                Leaderboard::new(bump, *counter.key).init(leaderboard_ai, program_id)?;
+            }
+            TrackerInstruction::Migrate => {
+                msg!("Instruction: Migrate");
+                let tracker_ai = next_account_info(accounts_iter)?;
+                let user = next_account_info(accounts_iter)?;
+                let payer = next_account_info(accounts_iter)?;
+                let system_program = next_account_info(accounts_iter)?;
+
+                expect_unique(
+                    &[tracker_ai, user, payer, system_program],
+                    CounterError::DuplicateAccount.into(),
+                )?;
+                expect_signer(
+                    payer,
+                    ProgramError::MissingRequiredSignature,
+                    "Payer must sign",
+                )?;
+                expect_writable(payer, "Payer must be writable to pay for the migration")?;
+                expect_writable(tracker_ai, "Tracker must be writable")?;
+                expect_program(
+                    system_program,
+                    &SYSTEM_PROGRAM_ID,
+                    ProgramError::InvalidArgument,
+                    "Invalid passed in for system program",
+                )?;
+
+                // Reads trackers of every version, including those without a header
+                let mut tracker = Tracker::load_any(tracker_ai, program_id)?;
+                let legacy = tracker_ai.data_len() == LegacyTracker::LEN;
+                if !legacy {
+                    let version = header_version::<Tracker>(&tracker_ai.try_borrow_data()?)?;
+                    assert_with_msg(
+                        version < Tracker::VERSION,
+                        CounterError::AlreadyMigrated.into(),
+                        "Tracker is already on the latest version",
+                    )?;
+                }
+                // Older trackers don't store the user, the PDA seeds prove it
+                expect_pda_with_bump(
+                    tracker_ai,
+                    &[user.key.as_ref(), tracker.counter.as_ref(), &[tracker.bump]],
+                    program_id,
+                    "Invalid PDA seeds for tracker",
+                )?;
+                tracker.user = *user.key;
+
+                resize_account(tracker_ai, payer, system_program, Tracker::LEN)?;
+                if legacy {
+                    // The header takes the place of the old data
+                    tracker_ai.try_borrow_mut_data()?.fill(0);
+                    tracker.init(tracker_ai, program_id)?;
+                } else {
+                    tracker.upgrade(tracker_ai)?;
+                }
+            }
+            TrackerInstruction::InitializeShard { index } => {
+                msg!("Instruction: InitializeShard");
+                let shard = next_account_info(accounts_iter)?;
+                let payer = next_account_info(accounts_iter)?;
+                let counter_program = next_account_info(accounts_iter)?;
+                let counter = next_account_info(accounts_iter)?;
+                let authority = next_account_info(accounts_iter)?;
+                let system_program = next_account_info(accounts_iter)?;
+
+                expect_unique(
+                    &[shard, payer, counter_program, counter, authority, system_program],
+                    CounterError::DuplicateAccount.into(),
+                )?;
+                expect_signer(
+                    payer,
+                    ProgramError::MissingRequiredSignature,
+                    "Payer must sign",
+                )?;
+                expect_program(
+                    counter_program,
+                    &authorized_counter::id(),
+                    CounterError::InvalidCounterProgram.into(),
+                    "Invalid passed in for counter program",
+                )?;
+                let auth_bump = expect_pda(
+                    authority,
+                    &[counter.key.as_ref()],
+                    program_id,
+                    "Invalid PDA seeds for authority",
+                )?;
+
+                // The counter program checks the rest, the authority PDA only signs
+                invoke_signed(
+                    &authorized_counter::instruction::initialize_shard(
+                        authorized_counter::id(),
+                        *shard.key,
+                        *counter.key,
+                        *authority.key,
+                        *payer.key,
+                        index,
+                    )?,
+                    &[
+                        counter_program.clone(),
+                        shard.clone(),
+                        counter.clone(),
+                        authority.clone(),
+                        payer.clone(),
+                        system_program.clone(),
+                    ],
+                    &[&[counter.key.as_ref(), &[auth_bump]]],
+                )?;
+            }
+            TrackerInstruction::IncrementShard { index } => {
+                msg!("Instruction: IncrementShard");
+                let tracker_ai = next_account_info(accounts_iter)?;
+                let user = next_account_info(accounts_iter)?;
+                let counter_program = next_account_info(accounts_iter)?;
+                let counter = next_account_info(accounts_iter)?;
+                let authority = next_account_info(accounts_iter)?;
+                let shard = next_account_info(accounts_iter)?;
+                let leaderboard_ai = next_account_info(accounts_iter)?;
+
+                expect_unique(
+                    &[tracker_ai, user, counter_program, counter, authority, shard, leaderboard_ai],
+                    CounterError::DuplicateAccount.into(),
+                )?;
+                expect_signer(
+                    user,
+                    CounterError::MissingUserSignature.into(),
+                    "User must sign",
+                )?;
+                expect_program(
+                    counter_program,
+                    &authorized_counter::id(),
+                    CounterError::InvalidCounterProgram.into(),
+                    "Invalid passed in for counter program",
+                )?;
+                expect_writable(tracker_ai, "Tracker must be writable")?;
+                expect_writable(shard, "Shard must be writable")?;
+                expect_writable(leaderboard_ai, "Leaderboard must be writable")?;
+
+                let mut tracker = Tracker::load_any(tracker_ai, program_id)?;
+                assert_with_msg(
+                    tracker.counter == *counter.key,
+                    CounterError::CounterMismatch.into(),
+                    "Counter does not match the tracker's counter",
+                )?;
+                expect_pda_with_bump(
+                    tracker_ai,
+                    &[user.key.as_ref(), counter.key.as_ref(), &[tracker.bump]],
+                    program_id,
+                    "Invalid PDA seeds for tracker",
+                )?;
+                expect_pda_with_bump(
+                    authority,
+                    &[counter.key.as_ref(), &[tracker.auth_bump]],
+                    program_id,
+                    "Invalid PDA seeds for authority",
+                )?;
+                // Only shards of the tracker's counter count towards it
+                expect_pda(
+                    shard,
+                    &[counter.key.as_ref(), &[index]],
+                    &authorized_counter::id(),
+                    "Invalid PDA seeds for shard",
+                )?;
+                let mut leaderboard = Leaderboard::load(leaderboard_ai, program_id)?;
+                expect_pda_with_bump(
+                    leaderboard_ai,
+                    &[b"leaderboard", counter.key.as_ref(), &[leaderboard.bump]],
+                    program_id,
+                    "Invalid PDA seeds for leaderboard",
+                )?;
+
+                invoke_signed(
+                    &authorized_counter::instruction::increment_shard(
+                        authorized_counter::id(),
+                        *shard.key,
+                        *authority.key,
+                    )?,
+                    &[counter_program.clone(), shard.clone(), authority.clone()],
+                    &[&[counter.key.as_ref(), &[tracker.auth_bump]]],
+                )?;
                 tracker.count += 1;
                 msg!("User Count {}", tracker.count);
-                tracker.serialize(&mut *tracker_ai.data.borrow_mut())?;
+                tracker.save_any(tracker_ai)?;
+                set_return_data(&tracker.count.to_le_bytes());
+
+                leaderboard.record(*user.key, tracker.count);
+                leaderboard.save(leaderboard_ai)?;
             }
         }
         Ok(())
@@ -1,10 +1,222 @@
 use borsh::{BorshDeserialize, BorshSerialize};
-use solana_program::pubkey::Pubkey;
+use bootcamp_accounts::{check_account, BorshAccount, HEADER_LEN};
+use bootcamp_idl_derive::IdlType;
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+    pubkey::Pubkey,
+};
 
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+#[derive(BorshSerialize, BorshDeserialize, BorshAccount, IdlType, Debug, Clone)]
+#[account(version = 3, previous = "TrackerV2")]
 pub struct Tracker {
     pub bump: u8, // bump seed of tracker
     pub auth_bump: u8, // bump seed of the auth
+    pub user: Pubkey, // lets clients filter the trackers of a user
     pub counter: Pubkey,
     pub count: u64, // tracked value
+    pub global_count: u64, // count of the counter after the user's last increment
+}
+
+impl Tracker {
+    pub const LEN: usize = HEADER_LEN + 1 + 1 + 32 + 32 + 8 + 8;
+
+    /// Same as `load`, but also reads trackers created before account headers.
+    pub fn load_any(account: &AccountInfo, program_id: &Pubkey) -> Result<Self, ProgramError> {
+        check_account(account, program_id, LegacyTracker::LEN)?;
+        Self::unpack_any(&account.try_borrow_data()?)
+    }
+
+    /// Same as `unpack`, but also decodes trackers without an account header.
+    pub fn unpack_any(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() != LegacyTracker::LEN {
+            return Self::unpack(data);
+        }
+        Ok(LegacyTracker::try_from_slice(data)?.into())
+    }
+
+    /// Writes the tracker in the layout `account` is on.
+    pub fn save_any(&self, account: &AccountInfo) -> ProgramResult {
+        if account.data_len() != LegacyTracker::LEN {
+            return self.save(account);
+        }
+        let legacy = LegacyTracker::from(self.clone());
+        Ok(legacy.serialize(&mut *account.try_borrow_mut_data()?)?)
+    }
+}
+
+/// Layout of trackers created before version 3, which did not snapshot the global count.
+#[derive(BorshSerialize, BorshDeserialize, BorshAccount, Debug, Clone)]
+#[account(name = "Tracker", version = 2, previous = "TrackerV1")]
+pub struct TrackerV2 {
+    pub bump: u8,
+    pub auth_bump: u8,
+    pub user: Pubkey,
+    pub counter: Pubkey,
+    pub count: u64,
+}
+
+impl TrackerV2 {
+    pub const LEN: usize = HEADER_LEN + 1 + 1 + 32 + 32 + 8;
+}
+
+impl From<TrackerV2> for Tracker {
+    fn from(tracker: TrackerV2) -> Self {
+        Self {
+            bump: tracker.bump,
+            auth_bump: tracker.auth_bump,
+            user: tracker.user,
+            counter: tracker.counter,
+            count: tracker.count,
+            // Only known after the next increment
+            global_count: 0,
+        }
+    }
+}
+
+impl From<Tracker> for TrackerV2 {
+    fn from(tracker: Tracker) -> Self {
+        Self {
+            bump: tracker.bump,
+            auth_bump: tracker.auth_bump,
+            user: tracker.user,
+            counter: tracker.counter,
+            count: tracker.count,
+        }
+    }
+}
+
+/// Layout of trackers created before version 2, which did not store the user.
+#[derive(BorshSerialize, BorshDeserialize, BorshAccount, Debug, Clone)]
+#[account(name = "Tracker", version = 1)]
+pub struct TrackerV1 {
+    pub bump: u8,
+    pub auth_bump: u8,
+    pub counter: Pubkey,
+    pub count: u64,
+}
+
+impl TrackerV1 {
+    pub const LEN: usize = HEADER_LEN + 1 + 1 + 32 + 8;
+}
+
+impl From<TrackerV1> for TrackerV2 {
+    fn from(tracker: TrackerV1) -> Self {
+        Self {
+            bump: tracker.bump,
+            auth_bump: tracker.auth_bump,
+            // Only known once the tracker is migrated
+            user: Pubkey::default(),
+            counter: tracker.counter,
+            count: tracker.count,
+        }
+    }
+}
+
+impl From<TrackerV2> for TrackerV1 {
+    fn from(tracker: TrackerV2) -> Self {
+        Self {
+            bump: tracker.bump,
+            auth_bump: tracker.auth_bump,
+            counter: tracker.counter,
+            count: tracker.count,
+        }
+    }
+}
+
+/// Layout of trackers created before account headers, which `Migrate` converts. Same
+/// fields as `TrackerV1`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct LegacyTracker {
+    pub bump: u8,
+    pub auth_bump: u8,
+    pub counter: Pubkey,
+    pub count: u64,
+}
+
+impl LegacyTracker {
+    pub const LEN: usize = 1 + 1 + 32 + 8;
+}
+
+impl From<LegacyTracker> for Tracker {
+    fn from(tracker: LegacyTracker) -> Self {
+        Self {
+            bump: tracker.bump,
+            auth_bump: tracker.auth_bump,
+            // Only known once the tracker is migrated
+            user: Pubkey::default(),
+            counter: tracker.counter,
+            count: tracker.count,
+            global_count: 0,
+        }
+    }
+}
+
+impl From<Tracker> for LegacyTracker {
+    fn from(tracker: Tracker) -> Self {
+        Self {
+            bump: tracker.bump,
+            auth_bump: tracker.auth_bump,
+            counter: tracker.counter,
+            count: tracker.count,
+        }
+    }
+}
+
+/// Number of users ranked by a `Leaderboard`.
+pub const LEADERBOARD_SIZE: usize = 10;
+
+#[derive(BorshSerialize, BorshDeserialize, IdlType, Default, Debug, Clone, Copy, PartialEq)]
+pub struct LeaderboardEntry {
+    pub user: Pubkey,
+    pub count: u64,
+}
+
+/// Top `LEADERBOARD_SIZE` users of a counter, sorted by count in descending order.
+/// PDA seeds: ["leaderboard", counter]
+#[derive(BorshSerialize, BorshDeserialize, BorshAccount, IdlType, Debug, Clone)]
+pub struct Leaderboard {
+    pub bump: u8,
+    pub counter: Pubkey,
+    pub len: u8, // number of occupied entries
+    pub entries: [LeaderboardEntry; LEADERBOARD_SIZE],
+}
+
+impl Leaderboard {
+    pub const LEN: usize = HEADER_LEN + 1 + 32 + 1 + LEADERBOARD_SIZE * 40;
+
+    pub fn new(bump: u8, counter: Pubkey) -> Self {
+        Self {
+            bump,
+            counter,
+            len: 0,
+            entries: [LeaderboardEntry::default(); LEADERBOARD_SIZE],
+        }
+    }
+
+    /// Occupied entries, highest count first.
+    pub fn ranked(&self) -> &[LeaderboardEntry] {
+        &self.entries[..self.len as usize]
+    }
+
+    /// Records the latest count of `user`. This touches at most `LEADERBOARD_SIZE`
+    /// entries, so the cost does not grow with the number of users.
+    pub fn record(&mut self, user: Pubkey, count: u64) {
+        let len = self.len as usize;
+        let mut i = match self.entries[..len].iter().position(|e| e.user == user) {
+            Some(i) => i,
+            None if len < LEADERBOARD_SIZE => {
+                self.len += 1;
+                len
+            }
+            // Evict the last entry
+            None if count > self.entries[len - 1].count => len - 1,
+            None => return,
+        };
+        self.entries[i] = LeaderboardEntry { user, count };
+        // Counts only go up, so an entry can only move towards the front
+        while i > 0 && self.entries[i - 1].count < count {
+            self.entries.swap(i - 1, i);
+            i -= 1;
+        }
+    }
 }
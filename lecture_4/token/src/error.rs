@@ -1,10 +1,21 @@
-use solana_program::program_error::ProgramError;
+use num_derive::FromPrimitive;
+use solana_program::{
+    decode_error::DecodeError,
+    msg,
+    program_error::{PrintProgramError, ProgramError},
+};
 use thiserror::Error;
 
-#[derive(Error, Debug, Copy, Clone, PartialEq)]
+#[derive(Error, Debug, Copy, Clone, FromPrimitive, PartialEq)]
 pub enum TokenError {
     #[error("Instruction not implemented.")]
     NotImplemented,
+    #[error("The same account was passed in more than once.")]
+    DuplicateAccount,
+    #[error("Amount overflows the balance or supply.")]
+    Overflow,
+    #[error("Account is already on the latest version.")]
+    AlreadyMigrated,
 }
 
 impl From<TokenError> for ProgramError {
@@ -12,3 +23,22 @@ impl From<TokenError> for ProgramError {
         ProgramError::Custom(e as u32)
     }
 }
+
+impl<T> DecodeError<T> for TokenError {
+    fn type_of() -> &'static str {
+        "TokenError"
+    }
+}
+
+impl PrintProgramError for TokenError {
+    fn print<E>(&self)
+    where
+        E: 'static
+            + std::error::Error
+            + DecodeError<E>
+            + PrintProgramError
+            + num_traits::FromPrimitive,
+    {
+        msg!("Error: {}", self);
+    }
+}
@@ -1,10 +1,160 @@
+use bootcamp_idl_derive::IdlInstruction;
 use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+};
 
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+#[derive(BorshSerialize, BorshDeserialize, IdlInstruction, Debug, Clone)]
 pub enum TokenInstruction {
+    #[account(
+        0,
+        writable,
+        name = "mint",
+        desc = "Uninitialized mint owned by the program"
+    )]
+    #[account(1, signer, name = "authority", desc = "Mint authority")]
     InitializeMint,
+    #[account(
+        0,
+        writable,
+        name = "token_account",
+        desc = "Uninitialized token account"
+    )]
+    #[account(1, name = "mint", desc = "Mint of the tokens held by the account")]
+    #[account(2, name = "owner", desc = "Owner of the token account")]
     InitializeTokenAccount,
+    #[account(
+        0,
+        writable,
+        name = "token_account",
+        desc = "Receives the minted tokens"
+    )]
+    #[account(1, writable, name = "mint")]
+    #[account(2, signer, name = "authority", desc = "Mint authority")]
     Mint { amount: u64 },
+    #[account(0, writable, name = "token_account", desc = "Holds the burnt tokens")]
+    #[account(1, writable, name = "mint")]
+    #[account(2, signer, name = "owner", desc = "Owner of the token account")]
     Burn { amount: u64 },
+    #[account(0, writable, name = "src", desc = "Token account sending the tokens")]
+    #[account(1, writable, name = "dst", desc = "Token account of the same mint")]
+    #[account(2, signer, name = "owner", desc = "Owner of the source token account")]
     Transfer { amount: u64 },
+    /// Adds the account header to a token account created before token accounts had
+    /// one. Token accounts without a header keep working until they are migrated.
+    #[account(0, writable, name = "token_account", desc = "Token account to migrate")]
+    #[account(
+        1,
+        writable,
+        signer,
+        name = "payer",
+        desc = "Pays for the larger account"
+    )]
+    #[account(2, name = "system_program", desc = "Used to top up the rent")]
+    Migrate,
+}
+
+pub fn initialize_mint(
+    program_id: Pubkey,
+    mint: Pubkey,
+    authority: Pubkey,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction {
+        accounts: vec![
+            AccountMeta::new(mint, false),
+            AccountMeta::new_readonly(authority, true),
+        ],
+        data: TokenInstruction::InitializeMint.try_to_vec()?,
+        program_id,
+    })
+}
+
+pub fn initialize_token_account(
+    program_id: Pubkey,
+    token_account: Pubkey,
+    mint: Pubkey,
+    owner: Pubkey,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction {
+        accounts: vec![
+            AccountMeta::new(token_account, false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new_readonly(owner, false),
+        ],
+        data: TokenInstruction::InitializeTokenAccount.try_to_vec()?,
+        program_id,
+    })
+}
+
+pub fn mint(
+    program_id: Pubkey,
+    token_account: Pubkey,
+    mint: Pubkey,
+    authority: Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction {
+        accounts: vec![
+            AccountMeta::new(token_account, false),
+            AccountMeta::new(mint, false),
+            AccountMeta::new_readonly(authority, true),
+        ],
+        data: TokenInstruction::Mint { amount }.try_to_vec()?,
+        program_id,
+    })
+}
+
+pub fn burn(
+    program_id: Pubkey,
+    token_account: Pubkey,
+    mint: Pubkey,
+    owner: Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction {
+        accounts: vec![
+            AccountMeta::new(token_account, false),
+            AccountMeta::new(mint, false),
+            AccountMeta::new_readonly(owner, true),
+        ],
+        data: TokenInstruction::Burn { amount }.try_to_vec()?,
+        program_id,
+    })
+}
+
+pub fn transfer(
+    program_id: Pubkey,
+    src: Pubkey,
+    dst: Pubkey,
+    owner: Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction {
+        accounts: vec![
+            AccountMeta::new(src, false),
+            AccountMeta::new(dst, false),
+            AccountMeta::new_readonly(owner, true),
+        ],
+        data: TokenInstruction::Transfer { amount }.try_to_vec()?,
+        program_id,
+    })
+}
+
+pub fn migrate(
+    program_id: Pubkey,
+    token_account: Pubkey,
+    payer: Pubkey,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction {
+        accounts: vec![
+            AccountMeta::new(token_account, false),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: TokenInstruction::Migrate.try_to_vec()?,
+        program_id,
+    })
 }
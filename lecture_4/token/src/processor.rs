@@ -1,73 +1,82 @@
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::BorshDeserialize;
+use bootcamp_accounts::BorshAccount;
+use bootcamp_program_utils::{
+    assert_with_msg, expect_program, expect_signer, expect_unique, expect_writable,
+    resize_account,
+};
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint::ProgramResult,
     msg,
     program_error::ProgramError,
     pubkey::Pubkey,
+    system_program,
 };
 
-use crate::instructions::TokenInstruction;
-use crate::state::{Mint, AccountTag, TokenAccount};
-
-pub fn assert_with_msg(statement: bool, err: ProgramError, msg: &str) -> ProgramResult {
-    if !statement {
-        msg!(msg);
-        Err(err)
-    } else {
-        Ok(())
-    }
-}
+use crate::error::TokenError;
+use crate::instruction::TokenInstruction;
+use crate::state::{LegacyTokenAccount, Mint, TokenAccount};
 
 pub struct Processor {}
 
 impl Processor {
     pub fn process_instruction(
-        _program_id: &Pubkey,
+        program_id: &Pubkey,
         accounts: &[AccountInfo],
         instruction_data: &[u8],
     ) -> ProgramResult {
         let instruction = TokenInstruction::try_from_slice(instruction_data)
             .map_err(|_| ProgramError::InvalidInstructionData)?;
         let accounts_iter = &mut accounts.iter();
+        // Each account is loaded and saved separately, so an aliased account would have
+        // one of its writes silently dropped
         match instruction {
             TokenInstruction::InitializeMint => {
                 let mint_ai = next_account_info(accounts_iter)?;
                 let mint_authority = next_account_info(accounts_iter)?;
-                let mut mint = Mint::load_unchecked(mint_ai)?;
-                assert_with_msg(
-                    mint_authority.is_signer,
+                expect_unique(&[mint_ai, mint_authority], TokenError::DuplicateAccount.into())?;
+                expect_signer(
+                    mint_authority,
                     ProgramError::MissingRequiredSignature,
                     "Mint Authority must sign",
                 )?;
                 // TODO
-                mint.tag = AccountTag::Mint;
-                mint.authority = *mint_authority.key;
-                mint.supply = 0;
-                mint.save(mint_ai)?
+                let mint = Mint {
+                    authority: *mint_authority.key,
+                    supply: 0,
+                };
+                mint.init(mint_ai, program_id)?
             }
             TokenInstruction::InitializeTokenAccount => {
                 let token_account_ai = next_account_info(accounts_iter)?;
                 let mint_ai = next_account_info(accounts_iter)?;
-                let mint = Mint::load(mint_ai)?;
+                Mint::load(mint_ai, program_id)?;
                 let owner = next_account_info(accounts_iter)?;
-                let mut token_account = TokenAccount::load_unchecked(token_account_ai)?;
+                expect_unique(
+                    &[token_account_ai, mint_ai, owner],
+                    TokenError::DuplicateAccount.into(),
+                )?;
                 // TODO
-                token_account.tag = AccountTag::TokenAccount;
-                token_account.owner = *owner.key;
-                token_account.mint = *mint_ai.key;
-                token_account.amount = 0;
-                token_account.save(token_account_ai)?
+                let token_account = TokenAccount {
+                    owner: *owner.key,
+                    mint: *mint_ai.key,
+                    amount: 0,
+                };
+                token_account.init(token_account_ai, program_id)?
             }
             TokenInstruction::Mint { amount } => {
                 msg!("Instruction: Mint");
                 let token_account_ai = next_account_info(accounts_iter)?;
                 let mint_ai = next_account_info(accounts_iter)?;
                 let mint_authority = next_account_info(accounts_iter)?;
-                let mut token_account = TokenAccount::load(token_account_ai)?;
-                let mut mint = Mint::load(mint_ai)?;
-                assert_with_msg(
-                    mint_authority.is_signer,
+                expect_unique(
+                    &[token_account_ai, mint_ai, mint_authority],
+                    TokenError::DuplicateAccount.into(),
+                )?;
+                let mut token_account = TokenAccount::load_any(token_account_ai, program_id)?;
+                let mut mint = Mint::load(mint_ai, program_id)?;
+                expect_signer(
+                    mint_authority,
                     ProgramError::MissingRequiredSignature,
                     "Mint Authority must sign",
                 )?;
@@ -76,13 +85,19 @@ impl Processor {
                     ProgramError::MissingRequiredSignature,
                     "Mint Authority mismatch",
                 )?;
-                // TODO
-                // unsafe 
-                // amount = u64::max_value();
-                mint.supply += amount;
-                token_account.amount += amount;
+                assert_with_msg(
+                    token_account.mint == *mint_ai.key,
+                    ProgramError::InvalidAccountData,
+                    "Token account mint mismatch",
+                )?;
+                // amount = u64::max_value() would wrap the supply around
+                mint.supply = mint.supply.checked_add(amount).ok_or(TokenError::Overflow)?;
+                token_account.amount = token_account
+                    .amount
+                    .checked_add(amount)
+                    .ok_or(TokenError::Overflow)?;
 
-                token_account.save(token_account_ai)?;
+                token_account.save_any(token_account_ai)?;
                 mint.save(mint_ai)?;
             }
             TokenInstruction::Burn { amount } => {
@@ -90,10 +105,14 @@ impl Processor {
                 let token_account_ai = next_account_info(accounts_iter)?;
                 let mint_ai = next_account_info(accounts_iter)?;
                 let owner = next_account_info(accounts_iter)?;
-                let mut token_account = TokenAccount::load(token_account_ai)?;
-                let mut mint = Mint::load(mint_ai)?;
-                assert_with_msg(
-                    owner.is_signer,
+                expect_unique(
+                    &[token_account_ai, mint_ai, owner],
+                    TokenError::DuplicateAccount.into(),
+                )?;
+                let mut token_account = TokenAccount::load_any(token_account_ai, program_id)?;
+                let mut mint = Mint::load(mint_ai, program_id)?;
+                expect_signer(
+                    owner,
                     ProgramError::MissingRequiredSignature,
                     "Token owner must sign",
                 )?;
@@ -107,10 +126,14 @@ impl Processor {
                     ProgramError::InvalidAccountData,
                     "Attempting to burn more than account balance",
                 )?;
-                // TODO
-                mint.supply -= amount;
+                assert_with_msg(
+                    token_account.mint == *mint_ai.key,
+                    ProgramError::InvalidAccountData,
+                    "Token account mint mismatch",
+                )?;
+                mint.supply = mint.supply.checked_sub(amount).ok_or(TokenError::Overflow)?;
                 token_account.amount -= amount;
-                token_account.save(token_account_ai)?;
+                token_account.save_any(token_account_ai)?;
                 mint.save(mint_ai)?;
             }
             TokenInstruction::Transfer { amount } => {
@@ -118,10 +141,17 @@ impl Processor {
                 let src_token_account_ai = next_account_info(accounts_iter)?;
                 let dst_token_account_ai = next_account_info(accounts_iter)?;
                 let owner = next_account_info(accounts_iter)?;
-                let mut src_token_account = TokenAccount::load(src_token_account_ai)?;
-                let mut dst_token_account = TokenAccount::load(dst_token_account_ai)?;
-                assert_with_msg(
-                    owner.is_signer,
+                // Otherwise dst is saved last with `amount` added and tokens are minted
+                expect_unique(
+                    &[src_token_account_ai, dst_token_account_ai, owner],
+                    TokenError::DuplicateAccount.into(),
+                )?;
+                let mut src_token_account =
+                    TokenAccount::load_any(src_token_account_ai, program_id)?;
+                let mut dst_token_account =
+                    TokenAccount::load_any(dst_token_account_ai, program_id)?;
+                expect_signer(
+                    owner,
                     ProgramError::MissingRequiredSignature,
                     "Token owner must sign",
                 )?;
@@ -140,11 +170,47 @@ impl Processor {
                     ProgramError::InvalidAccountData,
                     "Token account mints do not match",
                 )?;
-                // TODO
                 src_token_account.amount -= amount;
-                dst_token_account.amount += amount;
-                src_token_account.save(src_token_account_ai)?;
-                dst_token_account.save(dst_token_account_ai)?;
+                dst_token_account.amount = dst_token_account
+                    .amount
+                    .checked_add(amount)
+                    .ok_or(TokenError::Overflow)?;
+                src_token_account.save_any(src_token_account_ai)?;
+                dst_token_account.save_any(dst_token_account_ai)?;
+            }
+            TokenInstruction::Migrate => {
+                msg!("Instruction: Migrate");
+                let token_account_ai = next_account_info(accounts_iter)?;
+                let payer = next_account_info(accounts_iter)?;
+                let system_program = next_account_info(accounts_iter)?;
+                expect_unique(
+                    &[token_account_ai, payer, system_program],
+                    TokenError::DuplicateAccount.into(),
+                )?;
+                expect_writable(token_account_ai, "Token account must be writable")?;
+                expect_signer(
+                    payer,
+                    ProgramError::MissingRequiredSignature,
+                    "Payer must sign",
+                )?;
+                expect_writable(payer, "Payer must be writable to pay for the migration")?;
+                expect_program(
+                    system_program,
+                    &system_program::id(),
+                    ProgramError::InvalidArgument,
+                    "Invalid passed in for system program",
+                )?;
+                let token_account = TokenAccount::load_any(token_account_ai, program_id)?;
+                assert_with_msg(
+                    token_account_ai.data_len() == LegacyTokenAccount::LEN,
+                    TokenError::AlreadyMigrated.into(),
+                    "Token account already has an account header",
+                )?;
+
+                resize_account(token_account_ai, payer, system_program, TokenAccount::LEN)?;
+                // The header takes the place of the old data
+                token_account_ai.try_borrow_mut_data()?.fill(0);
+                token_account.init(token_account_ai, program_id)?;
             }
         }
         Ok(())
@@ -1,73 +1,92 @@
 use borsh::{BorshDeserialize, BorshSerialize};
+use bootcamp_accounts::{check_account, BorshAccount, HEADER_LEN};
+use bootcamp_idl_derive::IdlType;
 use solana_program::{
     account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
     pubkey::Pubkey,
 };
 
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
-pub enum AccountTag {
-    Uninitialized,
-    Mint,
-    TokenAccount,
-}
-
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+#[derive(BorshSerialize, BorshDeserialize, BorshAccount, IdlType, Debug, Clone)]
 pub struct Mint {
-    pub tag: AccountTag,
     pub authority: Pubkey,
     pub supply: u64,
 }
 
 impl Mint {
-    pub fn load_unchecked(ai: &AccountInfo) -> Result<Self, ProgramError> {
-        Ok(Self::try_from_slice(&ai.data.borrow())?)
+    pub const LEN: usize = HEADER_LEN + 32 + 8;
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshAccount, IdlType, Debug, Clone)]
+pub struct TokenAccount {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+impl TokenAccount {
+    pub const LEN: usize = HEADER_LEN + 32 + 32 + 8;
+
+    /// Same as `load`, but also reads token accounts created before account headers.
+    pub fn load_any(account: &AccountInfo, program_id: &Pubkey) -> Result<Self, ProgramError> {
+        check_account(account, program_id, LegacyTokenAccount::LEN)?;
+        Self::unpack_any(&account.try_borrow_data()?)
     }
 
-    fn validate(&self) -> ProgramResult {
-        if self.tag != AccountTag::Mint {
+    /// Same as `unpack`, but also decodes token accounts without an account header.
+    pub fn unpack_any(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() != LegacyTokenAccount::LEN {
+            return Self::unpack(data);
+        }
+        let legacy = LegacyTokenAccount::try_from_slice(data)?;
+        if legacy.tag != LegacyTokenAccount::TAG {
             return Err(ProgramError::InvalidAccountData);
         }
-        Ok(())
-    }
-
-    pub fn load(ai: &AccountInfo) -> Result<Self, ProgramError> {
-        let mint = Self::try_from_slice(&ai.data.borrow())?;
-        mint.validate()?;
-        Ok(mint)
+        Ok(legacy.into())
     }
 
-    pub fn save(&self, ai: &AccountInfo) -> ProgramResult {
-        Ok(self.serialize(&mut *ai.data.borrow_mut())?)
+    /// Writes the token account in the layout `account` is on.
+    pub fn save_any(&self, account: &AccountInfo) -> ProgramResult {
+        if account.data_len() != LegacyTokenAccount::LEN {
+            return self.save(account);
+        }
+        let legacy = LegacyTokenAccount::from(self.clone());
+        Ok(legacy.serialize(&mut *account.try_borrow_mut_data()?)?)
     }
 }
 
+/// Layout of token accounts created before account headers, which `Migrate` converts.
+/// A tag told mints and token accounts apart.
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
-pub struct TokenAccount {
-    pub tag: AccountTag,
+pub struct LegacyTokenAccount {
+    pub tag: u8,
     pub owner: Pubkey,
     pub mint: Pubkey,
     pub amount: u64,
 }
 
-impl TokenAccount {
-    pub fn load_unchecked(ai: &AccountInfo) -> Result<Self, ProgramError> {
-        Ok(Self::try_from_slice(&ai.data.borrow())?)
-    }
+impl LegacyTokenAccount {
+    pub const LEN: usize = 1 + 32 + 32 + 8;
+    /// Tag of token accounts, mints had 1 and uninitialized accounts 0
+    pub const TAG: u8 = 2;
+}
 
-    fn validate(&self) -> ProgramResult {
-        if self.tag != AccountTag::TokenAccount {
-            return Err(ProgramError::InvalidAccountData);
+impl From<LegacyTokenAccount> for TokenAccount {
+    fn from(token_account: LegacyTokenAccount) -> Self {
+        Self {
+            owner: token_account.owner,
+            mint: token_account.mint,
+            amount: token_account.amount,
         }
-        Ok(())
-    }
-
-    pub fn load(ai: &AccountInfo) -> Result<Self, ProgramError> {
-        let account = Self::try_from_slice(&ai.data.borrow())?;
-        account.validate()?;
-        Ok(account)
     }
+}
 
-    pub fn save(&self, ai: &AccountInfo) -> ProgramResult {
-        Ok(self.serialize(&mut *ai.data.borrow_mut())?)
+impl From<TokenAccount> for LegacyTokenAccount {
+    fn from(token_account: TokenAccount) -> Self {
+        Self {
+            tag: Self::TAG,
+            owner: token_account.owner,
+            mint: token_account.mint,
+            amount: token_account.amount,
+        }
     }
 }
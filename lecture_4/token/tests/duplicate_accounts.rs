@@ -0,0 +1,110 @@
+#![cfg(feature = "test-bpf")]
+//! Regression tests for aliased accounts. Build the program with `cargo build-bpf`,
+//! then run `cargo test-bpf`.
+
+use {
+    bootcamp_accounts::BorshAccount,
+    solana_program::{
+        instruction::{Instruction, InstructionError},
+        pubkey::Pubkey,
+        system_program,
+    },
+    solana_program_test::{BanksClient, ProgramTest},
+    solana_sdk::{
+        account::Account,
+        signature::{Keypair, Signer},
+        transaction::{Transaction, TransactionError},
+        transport::TransportError,
+    },
+    token::{
+        error::TokenError,
+        instruction,
+        state::{Mint, TokenAccount},
+    },
+};
+
+fn program_account(owner: &Pubkey, len: usize) -> Account {
+    Account {
+        lamports: 1_000_000_000,
+        data: vec![0; len],
+        owner: *owner,
+        ..Account::default()
+    }
+}
+
+async fn process(
+    banks_client: &mut BanksClient,
+    instruction: Instruction,
+    signers: &[&Keypair],
+) -> Result<(), TransportError> {
+    let mut tx = Transaction::new_with_payer(&[instruction], Some(&signers[0].pubkey()));
+    tx.sign(signers, banks_client.get_latest_blockhash().await.unwrap());
+    banks_client.process_transaction(tx).await
+}
+
+async fn supply(banks_client: &mut BanksClient, mint: Pubkey) -> u64 {
+    let account = banks_client.get_account(mint).await.unwrap().unwrap();
+    Mint::unpack(&account.data).unwrap().supply
+}
+
+async fn amount(banks_client: &mut BanksClient, token_account: Pubkey) -> u64 {
+    let account = banks_client.get_account(token_account).await.unwrap().unwrap();
+    TokenAccount::unpack(&account.data).unwrap().amount
+}
+
+#[tokio::test]
+async fn test_aliased_accounts_conserve_supply() {
+    let token_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::default();
+    program_test.add_program("token", token_id, None);
+
+    let user = Keypair::new();
+    program_test.add_account(user.pubkey(), program_account(&system_program::id(), 0));
+    let mint = Pubkey::new_unique();
+    program_test.add_account(mint, program_account(&token_id, Mint::LEN));
+    let (src, dst) = (Pubkey::new_unique(), Pubkey::new_unique());
+    for token_account in [src, dst] {
+        program_test.add_account(token_account, program_account(&token_id, TokenAccount::LEN));
+    }
+
+    let (mut banks_client, payer, _) = program_test.start().await;
+    let signers = &[&payer, &user];
+    let ix = instruction::initialize_mint(token_id, mint, user.pubkey()).unwrap();
+    process(&mut banks_client, ix, signers).await.unwrap();
+    for token_account in [src, dst] {
+        let ix = instruction::initialize_token_account(token_id, token_account, mint, user.pubkey())
+            .unwrap();
+        process(&mut banks_client, ix, &[&payer]).await.unwrap();
+    }
+    let ix = instruction::mint(token_id, src, mint, user.pubkey(), 100).unwrap();
+    process(&mut banks_client, ix, signers).await.unwrap();
+
+    // Every instruction passes one account twice
+    let aliased = [
+        instruction::transfer(token_id, src, src, user.pubkey(), 60),
+        instruction::mint(token_id, mint, mint, user.pubkey(), 60),
+        instruction::burn(token_id, mint, mint, user.pubkey(), 60),
+    ];
+    for ix in aliased {
+        let err = process(&mut banks_client, ix.unwrap(), signers)
+            .await
+            .unwrap_err()
+            .unwrap();
+        assert_eq!(
+            err,
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::DuplicateAccount as u32)
+            )
+        );
+        assert_eq!(supply(&mut banks_client, mint).await, 100);
+        assert_eq!(amount(&mut banks_client, src).await, 100);
+        assert_eq!(amount(&mut banks_client, dst).await, 0);
+    }
+
+    let ix = instruction::transfer(token_id, src, dst, user.pubkey(), 60).unwrap();
+    process(&mut banks_client, ix, signers).await.unwrap();
+    let total = amount(&mut banks_client, src).await + amount(&mut banks_client, dst).await;
+    assert_eq!(total, supply(&mut banks_client, mint).await);
+    assert_eq!(amount(&mut banks_client, dst).await, 60);
+}
@@ -0,0 +1,380 @@
+//! Property tests for the token processor. Random sequences of instructions, most of
+//! them hostile, run against in-memory accounts. After every step the supply of each
+//! mint must equal the sum of its token accounts, and every successful instruction
+//! must have been allowed by a reference model of the authority rules.
+
+use {
+    bootcamp_accounts::BorshAccount,
+    borsh::BorshSerialize,
+    proptest::prelude::*,
+    solana_program::{account_info::AccountInfo, pubkey::Pubkey, system_program},
+    std::collections::HashMap,
+    token::{
+        instruction::TokenInstruction,
+        processor::Processor,
+        state::{Mint, TokenAccount},
+    },
+};
+
+// Every test starts with two mints, three token accounts and an uninitialized account,
+// all owned by the token program, followed by three users that own or mint them
+const MINTS: std::ops::Range<usize> = 0..2;
+const TOKEN_ACCOUNTS: std::ops::Range<usize> = 2..5;
+const PROGRAM_ACCOUNTS: usize = 6;
+const USERS: std::ops::Range<usize> = 6..9;
+const ACCOUNTS: usize = 9;
+
+#[derive(Clone, Debug)]
+struct Step {
+    instruction: TokenInstruction,
+    accounts: [usize; 3],
+    signers: [bool; 3],
+}
+
+#[derive(Clone)]
+struct StoredAccount {
+    key: Pubkey,
+    lamports: u64,
+    data: Vec<u8>,
+    owner: Pubkey,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum State {
+    Uninitialized,
+    Mint { authority: Pubkey, supply: u64 },
+    TokenAccount { owner: Pubkey, mint: Pubkey, amount: u64 },
+    User,
+}
+
+struct Bank {
+    program_id: Pubkey,
+    accounts: Vec<StoredAccount>,
+}
+
+impl Bank {
+    fn new() -> Self {
+        let program_id = Pubkey::new_unique();
+        let accounts = (0..ACCOUNTS)
+            .map(|i| {
+                let (len, owner) = if i < PROGRAM_ACCOUNTS {
+                    (TokenAccount::LEN, program_id)
+                } else {
+                    (0, system_program::id())
+                };
+                StoredAccount {
+                    key: Pubkey::new_unique(),
+                    lamports: 1_000_000,
+                    data: vec![0; len],
+                    owner,
+                }
+            })
+            .collect();
+        let mut bank = Self {
+            program_id,
+            accounts,
+        };
+        let setup = [
+            (TokenInstruction::InitializeMint, [0, 6, 6]),
+            (TokenInstruction::InitializeMint, [1, 7, 7]),
+            (TokenInstruction::InitializeTokenAccount, [2, 0, 6]),
+            (TokenInstruction::InitializeTokenAccount, [3, 0, 7]),
+            (TokenInstruction::InitializeTokenAccount, [4, 1, 8]),
+        ];
+        for (instruction, accounts) in setup {
+            let step = Step {
+                instruction,
+                accounts,
+                signers: [false, true, false],
+            };
+            assert!(bank.process(&step));
+        }
+        bank
+    }
+
+    fn key(&self, index: usize) -> Pubkey {
+        self.accounts[index].key
+    }
+
+    fn state(&self, index: usize) -> State {
+        let account = &self.accounts[index];
+        if account.owner != self.program_id {
+            State::User
+        } else if let Ok(mint) = Mint::unpack(&account.data) {
+            State::Mint {
+                authority: mint.authority,
+                supply: mint.supply,
+            }
+        } else if let Ok(token_account) = TokenAccount::unpack(&account.data) {
+            State::TokenAccount {
+                owner: token_account.owner,
+                mint: token_account.mint,
+                amount: token_account.amount,
+            }
+        } else {
+            State::Uninitialized
+        }
+    }
+
+    fn states(&self) -> Vec<State> {
+        (0..ACCOUNTS).map(|i| self.state(i)).collect()
+    }
+
+    /// Runs `step` like the runtime would: an account passed more than once shares its
+    /// data, privileges are merged, and a failed instruction leaves no changes behind.
+    fn process(&mut self, step: &Step) -> bool {
+        let snapshot = self.accounts.clone();
+        let data = step.instruction.try_to_vec().unwrap();
+        let program_id = self.program_id;
+        let infos: Vec<AccountInfo> = self
+            .accounts
+            .iter_mut()
+            .enumerate()
+            .map(|(i, account)| {
+                let is_signer = step
+                    .accounts
+                    .iter()
+                    .zip(step.signers)
+                    .any(|(&index, signer)| index == i && signer);
+                AccountInfo::new(
+                    &account.key,
+                    is_signer,
+                    true,
+                    &mut account.lamports,
+                    &mut account.data,
+                    &account.owner,
+                    false,
+                    0,
+                )
+            })
+            .collect();
+        let accounts: Vec<AccountInfo> = step.accounts.iter().map(|&i| infos[i].clone()).collect();
+        let success = Processor::process_instruction(&program_id, &accounts, &data).is_ok();
+        drop(accounts);
+        drop(infos);
+        if !success {
+            self.accounts = snapshot;
+        }
+        success
+    }
+}
+
+/// The accounts `step` is allowed to change and their new states, or `None` if it must
+/// fail. Only the rules that protect balances are modeled, so the processor may reject
+/// more than this allows.
+fn expected(bank: &Bank, step: &Step) -> Option<Vec<(usize, State)>> {
+    let [a, b, c] = step.accounts;
+    let key = |i| bank.key(i);
+    // Privileges are merged, so an account signs if it signs in any position
+    let signed = |position: usize| {
+        let index = step.accounts[position];
+        step.accounts.iter().zip(step.signers).any(|(&i, signer)| i == index && signer)
+    };
+    let distinct = a != b && b != c && a != c;
+    match step.instruction {
+        TokenInstruction::InitializeMint => match bank.state(a) {
+            State::Uninitialized if signed(1) && a != b => Some(vec![(
+                a,
+                State::Mint {
+                    authority: key(b),
+                    supply: 0,
+                },
+            )]),
+            _ => None,
+        },
+        TokenInstruction::InitializeTokenAccount => match (bank.state(a), bank.state(b)) {
+            (State::Uninitialized, State::Mint { .. }) if distinct => Some(vec![(
+                a,
+                State::TokenAccount {
+                    owner: key(c),
+                    mint: key(b),
+                    amount: 0,
+                },
+            )]),
+            _ => None,
+        },
+        TokenInstruction::Mint { amount } => match (bank.state(a), bank.state(b)) {
+            (
+                State::TokenAccount {
+                    owner,
+                    mint,
+                    amount: balance,
+                },
+                State::Mint { authority, supply },
+            ) if distinct && signed(2) && authority == key(c) && mint == key(b) => Some(vec![
+                (
+                    a,
+                    State::TokenAccount {
+                        owner,
+                        mint,
+                        amount: balance.checked_add(amount)?,
+                    },
+                ),
+                (
+                    b,
+                    State::Mint {
+                        authority,
+                        supply: supply.checked_add(amount)?,
+                    },
+                ),
+            ]),
+            _ => None,
+        },
+        TokenInstruction::Burn { amount } => match (bank.state(a), bank.state(b)) {
+            (
+                State::TokenAccount {
+                    owner,
+                    mint,
+                    amount: balance,
+                },
+                State::Mint { authority, supply },
+            ) if distinct && signed(2) && owner == key(c) && mint == key(b) => Some(vec![
+                (
+                    a,
+                    State::TokenAccount {
+                        owner,
+                        mint,
+                        amount: balance.checked_sub(amount)?,
+                    },
+                ),
+                (
+                    b,
+                    State::Mint {
+                        authority,
+                        supply: supply.checked_sub(amount)?,
+                    },
+                ),
+            ]),
+            _ => None,
+        },
+        TokenInstruction::Transfer { amount } => match (bank.state(a), bank.state(b)) {
+            (
+                State::TokenAccount {
+                    owner,
+                    mint,
+                    amount: src_balance,
+                },
+                State::TokenAccount {
+                    owner: dst_owner,
+                    mint: dst_mint,
+                    amount: dst_balance,
+                },
+            ) if distinct && signed(2) && owner == key(c) && mint == dst_mint => Some(vec![
+                (
+                    a,
+                    State::TokenAccount {
+                        owner,
+                        mint,
+                        amount: src_balance.checked_sub(amount)?,
+                    },
+                ),
+                (
+                    b,
+                    State::TokenAccount {
+                        owner: dst_owner,
+                        mint: dst_mint,
+                        amount: dst_balance.checked_add(amount)?,
+                    },
+                ),
+            ]),
+            _ => None,
+        },
+        // Needs a payer outside the bank and moves no tokens
+        TokenInstruction::Migrate => unreachable!("Migrate is not generated"),
+    }
+}
+
+fn assert_supply_conserved(bank: &Bank) {
+    let mut balances: HashMap<Pubkey, u128> = HashMap::new();
+    for state in bank.states() {
+        if let State::TokenAccount { mint, amount, .. } = state {
+            *balances.entry(mint).or_default() += amount as u128;
+        }
+    }
+    for i in 0..ACCOUNTS {
+        if let State::Mint { supply, .. } = bank.state(i) {
+            let total = balances.remove(&bank.key(i)).unwrap_or_default();
+            assert_eq!(supply as u128, total, "supply of mint {} is not conserved", i);
+        }
+    }
+    // Token accounts can only be created for an initialized mint
+    assert!(balances.is_empty());
+}
+
+fn amount() -> impl Strategy<Value = u64> {
+    prop_oneof![0..1_000u64, (u64::MAX - 1_000)..=u64::MAX, any::<u64>()]
+}
+
+fn instruction() -> impl Strategy<Value = TokenInstruction> {
+    prop_oneof![
+        1 => Just(TokenInstruction::InitializeMint),
+        2 => Just(TokenInstruction::InitializeTokenAccount),
+        3 => amount().prop_map(|amount| TokenInstruction::Mint { amount }),
+        2 => amount().prop_map(|amount| TokenInstruction::Burn { amount }),
+        4 => amount().prop_map(|amount| TokenInstruction::Transfer { amount }),
+    ]
+}
+
+/// Mostly an account of the kind the instruction expects in that position, sometimes any
+/// account, which covers aliased accounts, wrong mints and wrong owners.
+fn account(kind: std::ops::Range<usize>) -> impl Strategy<Value = usize> {
+    prop_oneof![4 => kind, 1 => 0..ACCOUNTS]
+}
+
+fn step() -> impl Strategy<Value = Step> {
+    instruction()
+        .prop_flat_map(|instruction| {
+            let accounts = match instruction {
+                TokenInstruction::InitializeMint => {
+                    [account(0..PROGRAM_ACCOUNTS), account(USERS), account(USERS)]
+                }
+                TokenInstruction::InitializeTokenAccount => {
+                    [account(0..PROGRAM_ACCOUNTS), account(MINTS), account(USERS)]
+                }
+                TokenInstruction::Mint { .. } | TokenInstruction::Burn { .. } => {
+                    [account(TOKEN_ACCOUNTS), account(MINTS), account(USERS)]
+                }
+                TokenInstruction::Transfer { .. } => {
+                    [account(TOKEN_ACCOUNTS), account(TOKEN_ACCOUNTS), account(USERS)]
+                }
+                TokenInstruction::Migrate => unreachable!("Migrate is not generated"),
+            };
+            // Missing signatures in one of four steps
+            let signed = || prop::bool::weighted(0.75);
+            (Just(instruction), accounts, [signed(), signed(), signed()])
+        })
+        .prop_map(|(instruction, accounts, signers)| Step {
+            instruction,
+            accounts,
+            signers,
+        })
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(512))]
+
+    #[test]
+    fn supply_is_conserved(steps in prop::collection::vec(step(), 1..64)) {
+        let mut bank = Bank::new();
+        for step in &steps {
+            let before = bank.states();
+            let expected = expected(&bank, step);
+            let success = bank.process(step);
+            let after = bank.states();
+
+            assert_supply_conserved(&bank);
+            match expected {
+                Some(changes) if success => {
+                    let mut states = before;
+                    for (i, state) in changes {
+                        states[i] = state;
+                    }
+                    prop_assert_eq!(after, states, "unexpected changes by {:?}", step);
+                }
+                None => prop_assert!(!success, "{:?} should have failed", step),
+                // The processor is stricter than the model
+                Some(_) => prop_assert_eq!(after, before),
+            }
+        }
+    }
+}
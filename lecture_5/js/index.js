@@ -10,10 +10,12 @@ const {
 
 const BN = require("bn.js");
 
-const MARKETPLACE_SIZE = 32 + 256 * 80;
+// header (16) | user | occupied bitmap | 256 offers (seller, offer_mint, buyer_mint, offer_amount, buyer_amount)
+const MARKETPLACE_SIZE = 16 + 32 + 32 + 256 * 112;
 
-const stack = (buffer, user, programId) => {
-  const idx = Buffer.from(new Uint8Array([0]));
+// mode: 0 = Borsh on the stack, 1 = Borsh boxed on the heap, 2 = zero-copy
+const stack = (buffer, user, mode, programId) => {
+  const idx = Buffer.from(new Uint8Array([0, mode]));
   return new TransactionInstruction({
     keys: [
       {
@@ -52,8 +54,12 @@ const zc = (buffer, user, programId) => {
   });
 };
 
-const echo = (buffer, size, programId) => {
+// Largest chunk that fits in a transaction with the rest of the instruction
+const ECHO_CHUNK_SIZE = 900;
+
+const echo = (buffer, offset, size, programId) => {
   const idx = Buffer.from(new Uint8Array([4]));
+  const off = Buffer.from(new Uint8Array(new BN(offset).toArray("le", 4)));
   const len = Buffer.from(new Uint8Array(new BN(size).toArray("le", 4)));
   let content = [];
   for (let i = 0; i < size; i += 1) {
@@ -68,7 +74,7 @@ const echo = (buffer, size, programId) => {
         isWritable: true,
       },
     ],
-    data: Buffer.concat([idx, len, content]),
+    data: Buffer.concat([idx, off, len, content]),
     programId: programId,
   });
 };
@@ -107,6 +113,51 @@ const cpi = (from, to, s, programId) => {
   });
 };
 
+const recursiveCpi = (depth, stackPayload, programId) => {
+  const payload = Buffer.from(new Uint8Array(new BN(stackPayload).toArray("le", 2)));
+  return new TransactionInstruction({
+    keys: [
+      {
+        pubkey: programId,
+        isSigner: false,
+        isWritable: false,
+      },
+    ],
+    data: Buffer.concat([Buffer.from(new Uint8Array([5, depth])), payload]),
+    programId: programId,
+  });
+};
+
+// header (16) | authority (32) | len (8) | capacity (8)
+const VEC_HEADER_SIZE = 16 + 32 + 8 + 8;
+const OFFER_SIZE = 112;
+
+const push = (buffer, authority, n, programId) => {
+  const idx = Buffer.from(new Uint8Array([9]));
+  const len = Buffer.from(new Uint8Array(new BN(n).toArray("le", 4)));
+  return new TransactionInstruction({
+    keys: [
+      {
+        pubkey: buffer,
+        isSigner: false,
+        isWritable: true,
+      },
+      {
+        pubkey: authority,
+        isSigner: true,
+        isWritable: true,
+      },
+      {
+        pubkey: SystemProgram.programId,
+        isSigner: false,
+        isWritable: false,
+      },
+    ],
+    data: Buffer.concat([idx, len, Buffer.alloc(n * OFFER_SIZE)]),
+    programId: programId,
+  });
+};
+
 const main = async () => {
   var args = process.argv.slice(2);
   const programId = new PublicKey(
@@ -146,7 +197,12 @@ const main = async () => {
     });
     signers.push(to);
     tx.add(createIx);
-    const stackIx = stack(to.publicKey, feePayer.publicKey, programId);
+    const stackIx = stack(
+      to.publicKey,
+      feePayer.publicKey,
+      parseInt(args[1] || "0"),
+      programId
+    );
     tx.add(stackIx);
   } else if (ix == 1) {
     const runtimeIx = runtime(parseInt(args[1]), programId);
@@ -191,8 +247,73 @@ const main = async () => {
     });
     signers.push(to);
     tx.add(createIx);
-    const echoIx = echo(to.publicKey, size, programId);
+    const echoIx = echo(
+      to.publicKey,
+      0,
+      Math.min(size, ECHO_CHUNK_SIZE),
+      programId
+    );
     tx.add(echoIx);
+    // Write the rest of the buffer in one transaction per chunk
+    if (size > ECHO_CHUNK_SIZE) {
+      await sendAndConfirmTransaction(connection, tx, signers, {
+        skipPreflight: true,
+        commitment: "confirmed",
+      });
+      for (let offset = ECHO_CHUNK_SIZE; offset < size; offset += ECHO_CHUNK_SIZE) {
+        const chunk = Math.min(size - offset, ECHO_CHUNK_SIZE);
+        console.log(`Writing ${chunk} bytes at offset ${offset}`);
+        tx = new Transaction().add(echo(to.publicKey, offset, chunk, programId));
+        signers = [feePayer];
+        if (offset + chunk < size) {
+          await sendAndConfirmTransaction(connection, tx, signers, {
+            skipPreflight: true,
+            commitment: "confirmed",
+          });
+        }
+      }
+    }
+  } else if (ix == 5) {
+    const recursiveCpiIx = recursiveCpi(
+      parseInt(args[1]),
+      parseInt(args[2] || "0"),
+      programId
+    );
+    tx.add(recursiveCpiIx);
+  } else if (ix == 6) {
+    // Pushes 8 offers per transaction until the account is past `args[1]` bytes
+    const target = parseInt(args[1] || "20000");
+    let createIx = SystemProgram.createAccount({
+      fromPubkey: feePayer.publicKey,
+      newAccountPubkey: to.publicKey,
+      lamports: await connection.getMinimumBalanceForRentExemption(
+        VEC_HEADER_SIZE
+      ),
+      space: VEC_HEADER_SIZE,
+      programId: programId,
+    });
+    signers.push(to);
+    tx.add(createIx);
+    tx.add(push(to.publicKey, feePayer.publicKey, 8, programId));
+    await sendAndConfirmTransaction(connection, tx, signers, {
+      skipPreflight: true,
+      commitment: "confirmed",
+    });
+    let size = (await connection.getAccountInfo(to.publicKey, "confirmed"))
+      .data.length;
+    while (size < target) {
+      console.log(`Account is ${size} bytes`);
+      await sendAndConfirmTransaction(
+        connection,
+        new Transaction().add(push(to.publicKey, feePayer.publicKey, 8, programId)),
+        [feePayer],
+        { skipPreflight: true, commitment: "confirmed" }
+      );
+      size = (await connection.getAccountInfo(to.publicKey, "confirmed")).data
+        .length;
+    }
+    console.log(`Account is ${size} bytes`);
+    return;
   } else {
     throw Error;
   }
@@ -0,0 +1,54 @@
+use num_derive::FromPrimitive;
+use solana_program::{
+    decode_error::DecodeError,
+    msg,
+    program_error::{PrintProgramError, ProgramError},
+};
+use thiserror::Error;
+
+#[derive(Error, Debug, Copy, Clone, FromPrimitive, PartialEq)]
+pub enum RuntimeLimitationError {
+    #[error("Every offer slot in the marketplace is taken.")]
+    MarketplaceFull,
+    #[error("No offer in this slot.")]
+    OfferNotFound,
+    #[error("Only the seller can cancel an offer.")]
+    SellerMismatch,
+    #[error("Payment account does not belong to the seller.")]
+    InvalidPaymentAccount,
+    #[error("Only the authority of the vector can modify it.")]
+    AuthorityMismatch,
+    #[error("Accounts can grow by at most 10KB per instruction.")]
+    GrowthLimitExceeded,
+    #[error("Not enough capacity for the pushed elements.")]
+    CapacityExceeded,
+    #[error("Index is out of bounds.")]
+    IndexOutOfBounds,
+    #[error("Offset is past the end of the buffer.")]
+    OffsetOutOfBounds,
+}
+
+impl From<RuntimeLimitationError> for ProgramError {
+    fn from(e: RuntimeLimitationError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+impl<T> DecodeError<T> for RuntimeLimitationError {
+    fn type_of() -> &'static str {
+        "RuntimeLimitationError"
+    }
+}
+
+impl PrintProgramError for RuntimeLimitationError {
+    fn print<E>(&self)
+    where
+        E: 'static
+            + std::error::Error
+            + DecodeError<E>
+            + PrintProgramError
+            + num_traits::FromPrimitive,
+    {
+        msg!("Error: {}", self);
+    }
+}
@@ -1,12 +1,93 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 
+use crate::state::Offer;
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq)]
+pub enum StackMode {
+    Borsh, // MarketplaceBorsh on the stack, overflows the 4KB frame
+    Boxed, // MarketplaceBorsh deserialized straight onto the 32KB heap
+    ZeroCopy, // Marketplace cast in place from the account data
+}
+
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub enum RuntimeLimitationInstruction {
-    Stack, // 4KB
+    Stack { mode: StackMode }, // 4KB
     Runtime { max_iter: usize }, // 200000 compute units
     // Do you accumulate more stack per CPI
     Cpi { size: u64 }, // Depth of 4. 
     // Max allocation of create_account = 10KB
     ZeroCopy,
-    Tx { data: Vec<u8> }, // 1232 bytes 
+    // Copies `data` into the buffer at `offset`, truncated to fit. Payloads larger
+    // than a transaction are written in chunks across several transactions.
+    Tx { offset: u32, data: Vec<u8> }, // 1232 bytes 
+    // The program invokes itself `depth` times, holding `stack_payload` bytes of
+    // stack in every frame. Accounts: [program]
+    RecursiveCpi { depth: u8, stack_payload: u16 }, // Depth of 4, 4KB frames
+    /// Escrows `offer_amount` of the offer mint in the vault and lists it for
+    /// `buyer_amount` of the buyer mint in the first free slot of the marketplace.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                          |
+    /// |-------|----------|--------|------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | marketplace: Zero-copy `Marketplace` account          |
+    /// | 1     | ✅       | ✅     | seller: Owner of the offered tokens, pays for vault   |
+    /// | 2     | ✅       | ❌     | seller_token_account: Source of the offered tokens    |
+    /// | 3     | ✅       | ❌     | vault: PDA of ["vault", marketplace, offer_mint]      |
+    /// | 4     | ❌       | ❌     | offer_mint: Mint of the offered tokens                |
+    /// | 5     | ❌       | ❌     | buyer_mint: Mint the seller wants to be paid in       |
+    /// | 6     | ❌       | ❌     | token_program: Used to escrow the offered tokens      |
+    /// | 7     | ❌       | ❌     | system_program: Used to allocate the vault            |
+    /// | 8     | ❌       | ❌     | rent: Rent sysvar, used to initialize the vault       |
+    PostOffer { offer_amount: u64, buyer_amount: u64 },
+    /// Returns the escrowed tokens of the offer in `slot` to the seller.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                          |
+    /// |-------|----------|--------|------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | marketplace: Zero-copy `Marketplace` account          |
+    /// | 1     | ❌       | ✅     | seller: Seller of the offer                           |
+    /// | 2     | ✅       | ❌     | seller_token_account: Receives the offered tokens     |
+    /// | 3     | ✅       | ❌     | vault: PDA of ["vault", marketplace, offer_mint]      |
+    /// | 4     | ❌       | ❌     | token_program: Used to release the offered tokens     |
+    CancelOffer { slot: u8 },
+    /// Pays `buyer_amount` of the buyer mint to the seller and releases the escrowed
+    /// tokens of the offer in `slot` to the buyer.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                          |
+    /// |-------|----------|--------|------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | marketplace: Zero-copy `Marketplace` account          |
+    /// | 1     | ❌       | ✅     | buyer: Owner of the payment tokens                    |
+    /// | 2     | ✅       | ❌     | buyer_payment_account: Pays `buyer_amount`            |
+    /// | 3     | ✅       | ❌     | seller_payment_account: Seller's buyer mint account   |
+    /// | 4     | ✅       | ❌     | buyer_token_account: Receives the offered tokens      |
+    /// | 5     | ✅       | ❌     | vault: PDA of ["vault", marketplace, offer_mint]      |
+    /// | 6     | ❌       | ❌     | token_program: Used to swap the tokens                |
+    FillOffer { slot: u8 },
+    /// Appends `offers` to a growable `VecAccount`, reallocating it when it is full.
+    /// The account is initialized with `authority` on the first push.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                          |
+    /// |-------|----------|--------|------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | offers: `VecAccount<Offer>` owned by this program     |
+    /// | 1     | ✅       | ✅     | authority: Authority of the vector, pays for growth   |
+    /// | 2     | ❌       | ❌     | system_program: Used to top up the rent exemption     |
+    Push { offers: Vec<Offer> },
+    /// Swaps the last offer into `index`.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                          |
+    /// |-------|----------|--------|------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | offers: `VecAccount<Offer>` owned by this program     |
+    /// | 1     | ❌       | ✅     | authority: Authority of the vector                    |
+    Remove { index: u64 },
+    /// Shrinks the account to its length and refunds the freed rent to the authority.
+    ///
+    /// Accounts:
+    /// | index | writable | signer | description                                          |
+    /// |-------|----------|--------|------------------------------------------------------|
+    /// | 0     | ✅       | ❌     | offers: `VecAccount<Offer>` owned by this program     |
+    /// | 1     | ✅       | ✅     | authority: Authority of the vector, receives the rent |
+    Compact,
 }
@@ -1,27 +1,154 @@
 use borsh::{BorshDeserialize, BorshSerialize};
+use bootcamp_accounts::{check_account, ZeroCopy, HEADER_LEN};
+use bootcamp_program_utils::{
+    assert_with_msg, create_pda_account, expect_owner, expect_pda, expect_program,
+    expect_signer, expect_writable,
+};
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
     msg,
     log::sol_log_compute_units,
     program_error::ProgramError,
-    program::invoke,
+    program::{invoke, invoke_signed},
+    program_pack::Pack,
     pubkey::Pubkey,
+    system_instruction,
+    system_program::ID as SYSTEM_PROGRAM_ID,
     sysvar::{rent::Rent, Sysvar},
 };
+use std::cell::RefMut;
 
-use crate::instruction::RuntimeLimitationInstruction;
-use crate::state::{MarketplaceBorsh, Marketplace, ZeroCopy};
+use crate::error::RuntimeLimitationError;
+use crate::instruction::{RuntimeLimitationInstruction, StackMode};
+use crate::state::{MarketplaceBorsh, Marketplace, Offer};
+use crate::vec_account::{VecAccount, VecHeader};
 
-pub fn assert_with_msg(statement: bool, err: ProgramError, msg: &str) -> ProgramResult {
-    if !statement {
-        msg!(msg);
-        Err(err)
+/// Loads the marketplace, writing its header first if the account is still zeroed, so the
+/// experiments can run again on the same account.
+fn load_or_init<'a>(
+    marketplace_ai: &'a AccountInfo,
+    program_id: &Pubkey,
+) -> Result<RefMut<'a, Marketplace>, ProgramError> {
+    let zeroed = marketplace_ai
+        .try_borrow_data()?
+        .get(..HEADER_LEN)
+        .map_or(false, |header| header.iter().all(|byte| *byte == 0));
+    if zeroed {
+        Marketplace::init(marketplace_ai, program_id)
     } else {
-        Ok(())
+        Marketplace::load_mut(marketplace_ai, program_id)
+    }
+}
+
+/// Checks that `authority` signed and is the authority of the vector in `vec_ai`.
+fn assert_vec_authority(
+    program_id: &Pubkey,
+    vec_ai: &AccountInfo,
+    authority: &AccountInfo,
+) -> Result<VecHeader, ProgramError> {
+    expect_signer(
+        authority,
+        ProgramError::MissingRequiredSignature,
+        "Authority must sign",
+    )?;
+    let header = VecAccount::<Offer>::header(vec_ai, program_id)?;
+    assert_with_msg(
+        header.authority == *authority.key,
+        RuntimeLimitationError::AuthorityMismatch.into(),
+        "Authority mismatch",
+    )?;
+    Ok(header)
+}
+
+/// Checks that `vault` escrows the offered tokens of `offer` and returns its bump seed.
+fn assert_vault(
+    program_id: &Pubkey,
+    marketplace_ai: &AccountInfo,
+    vault: &AccountInfo,
+    offer: &Offer,
+) -> Result<u8, ProgramError> {
+    expect_pda(
+        vault,
+        &[b"vault", marketplace_ai.key.as_ref(), offer.offer_mint.as_ref()],
+        program_id,
+        "Invalid PDA seeds for vault",
+    )
+}
+
+/// Hex encoding of the first 32 bytes of `data`, to log data that isn't valid UTF-8.
+fn hex_preview(data: &[u8]) -> String {
+    let mut preview: String = data.iter().take(32).map(|b| format!("{:02x}", b)).collect();
+    if data.len() > 32 {
+        preview.push_str("...");
+    }
+    preview
+}
+
+/// Bytes handed out by the bump allocator so far. The allocator stores its current
+/// position at the start of the heap and allocates downwards from the end.
+#[cfg(target_arch = "bpf")]
+fn heap_used() -> usize {
+    use solana_program::entrypoint::{HEAP_LENGTH, HEAP_START_ADDRESS};
+    let start = HEAP_START_ADDRESS as usize;
+    let pos = unsafe { *(start as *const usize) };
+    if pos == 0 {
+        0
+    } else {
+        start + HEAP_LENGTH - pos
     }
 }
 
+#[cfg(not(target_arch = "bpf"))]
+fn heap_used() -> usize {
+    0
+}
+
+/// Holds `N` bytes on the stack of the current frame while the program invokes itself
+/// with one less level of depth.
+fn recursive_cpi<const N: usize>(
+    program_id: &Pubkey,
+    program_ai: &AccountInfo,
+    depth: u8,
+    stack_payload: u16,
+) -> ProgramResult {
+    let mut payload = [0u8; N];
+    for (i, byte) in payload.iter_mut().enumerate() {
+        // Volatile writes stop the compiler from optimizing the buffer away
+        unsafe { std::ptr::write_volatile(byte, i as u8) };
+    }
+    msg!(
+        "Remaining depth {}: {} bytes of stack payload at {:p}",
+        depth,
+        N,
+        payload.as_ptr()
+    );
+    sol_log_compute_units();
+    if depth > 0 {
+        invoke(
+            &Instruction {
+                program_id: *program_id,
+                accounts: vec![AccountMeta::new_readonly(*program_id, false)],
+                data: RuntimeLimitationInstruction::RecursiveCpi {
+                    depth: depth - 1,
+                    stack_payload,
+                }
+                .try_to_vec()?,
+            },
+            std::slice::from_ref(program_ai),
+        )?;
+        msg!("Returned to depth {}", depth);
+        sol_log_compute_units();
+    }
+    // Read the payload back so it stays live across the CPI
+    let checksum = payload
+        .iter()
+        .fold(0u8, |acc, byte| acc.wrapping_add(unsafe { std::ptr::read_volatile(byte) }));
+    msg!("Payload checksum {}", checksum);
+    Ok(())
+}
+
 pub struct Processor {}
 
 impl Processor {
@@ -35,17 +162,45 @@ impl Processor {
             .map_err(|_| ProgramError::InvalidInstructionData)?;
         let accounts_iter = &mut accounts.iter();
         match instruction {
-            RuntimeLimitationInstruction::Stack => {
+            RuntimeLimitationInstruction::Stack { mode } => {
                 let marketplace_ai = next_account_info(accounts_iter)?;
                 let user = next_account_info(accounts_iter)?;
-                // let mut marketplace: Box<MarketplaceBorsh> = Box::<MarketplaceBorsh>::new(MarketplaceBorsh::try_from_slice(&marketplace_ai.data.borrow())?);
-                // marketplace.user = *user.key;
-                // marketplace.serialize(&mut *marketplace_ai.data.borrow_mut())?;
+                // The Borsh modes slice the data without going through `ZeroCopy`
+                check_account(marketplace_ai, program_id, Marketplace::LEN)?;
+                msg!("Stack mode {:?}: heap used {} bytes", mode, heap_used());
+                sol_log_compute_units();
+                match mode {
+                    // The Borsh modes share the zero-copy layout, past the account header
+                    StackMode::Borsh => {
+                        // ~28KB on a 4KB stack frame
+                        let mut marketplace = MarketplaceBorsh::try_from_slice(
+                            &marketplace_ai.data.borrow()[HEADER_LEN..Marketplace::LEN],
+                        )?;
+                        marketplace.user = *user.key;
+                        marketplace
+                            .serialize(&mut &mut marketplace_ai.data.borrow_mut()[HEADER_LEN..])?;
+                    }
+                    StackMode::Boxed => {
+                        let mut marketplace = MarketplaceBorsh::deserialize_boxed(
+                            &mut &marketplace_ai.data.borrow()[HEADER_LEN..],
+                        )?;
+                        marketplace.user = *user.key;
+                        marketplace
+                            .serialize(&mut &mut marketplace_ai.data.borrow_mut()[HEADER_LEN..])?;
+                    }
+                    StackMode::ZeroCopy => {
+                        let mut marketplace = load_or_init(marketplace_ai, program_id)?;
+                        marketplace.user = *user.key;
+                    }
+                }
+                msg!("Assigned marketplace for user {}", *user.key);
+                msg!("Stack mode {:?}: heap used {} bytes", mode, heap_used());
+                sol_log_compute_units();
             }
             RuntimeLimitationInstruction::ZeroCopy => {
                 let marketplace_ai = next_account_info(accounts_iter)?;
                 let user = next_account_info(accounts_iter)?;
-                let mut marketplace = Marketplace::load_mut(marketplace_ai)?;
+                let mut marketplace = load_or_init(marketplace_ai, program_id)?;
                 marketplace.user = *user.key;
                 msg!("Assigned marketplace for user {}", *user.key);
             }
@@ -60,6 +215,12 @@ impl Processor {
                 let from = next_account_info(accounts_iter)?;
                 let to = next_account_info(accounts_iter)?;
                 let system_program = next_account_info(accounts_iter)?;
+                // Rent for anything larger overflows
+                assert_with_msg(
+                    size <= system_instruction::MAX_PERMITTED_DATA_LENGTH,
+                    ProgramError::InvalidArgument,
+                    "Accounts are limited to 10MB",
+                )?;
                 invoke(
                     &solana_program::system_instruction::create_account(
                         from.key,
@@ -75,10 +236,336 @@ impl Processor {
                     ]
                 )?;
             }
-            RuntimeLimitationInstruction::Tx { data }=> {
-                let buffer = &mut next_account_info(accounts_iter)?.try_borrow_mut_data()?;
-                buffer.copy_from_slice(data.as_slice());
-                msg!("Echo {}", std::str::from_utf8(&buffer).unwrap());
+            RuntimeLimitationInstruction::RecursiveCpi { depth, stack_payload } => {
+                let program_ai = next_account_info(accounts_iter)?;
+                assert_with_msg(
+                    program_ai.key == program_id,
+                    ProgramError::IncorrectProgramId,
+                    "First account must be this program",
+                )?;
+                // Stack arrays need a size known at compile time, so round the payload
+                // up to the next supported size. Each frame is limited to 4KB.
+                match stack_payload {
+                    0 => recursive_cpi::<0>(program_id, program_ai, depth, stack_payload)?,
+                    1..=512 => recursive_cpi::<512>(program_id, program_ai, depth, stack_payload)?,
+                    513..=1024 => {
+                        recursive_cpi::<1024>(program_id, program_ai, depth, stack_payload)?
+                    }
+                    1025..=2048 => {
+                        recursive_cpi::<2048>(program_id, program_ai, depth, stack_payload)?
+                    }
+                    2049..=3072 => {
+                        recursive_cpi::<3072>(program_id, program_ai, depth, stack_payload)?
+                    }
+                    3073..=4096 => {
+                        recursive_cpi::<4096>(program_id, program_ai, depth, stack_payload)?
+                    }
+                    _ => {
+                        msg!("Stack payload must be at most 4096 bytes");
+                        return Err(ProgramError::InvalidArgument);
+                    }
+                }
+            }
+            RuntimeLimitationInstruction::PostOffer {
+                offer_amount,
+                buyer_amount,
+            } => {
+                msg!("Instruction: PostOffer");
+                let marketplace_ai = next_account_info(accounts_iter)?;
+                let seller = next_account_info(accounts_iter)?;
+                let seller_token_account = next_account_info(accounts_iter)?;
+                let vault = next_account_info(accounts_iter)?;
+                let offer_mint = next_account_info(accounts_iter)?;
+                let buyer_mint = next_account_info(accounts_iter)?;
+                let token_program = next_account_info(accounts_iter)?;
+                let system_program = next_account_info(accounts_iter)?;
+                let rent = next_account_info(accounts_iter)?;
+
+                expect_signer(
+                    seller,
+                    ProgramError::MissingRequiredSignature,
+                    "Seller must sign",
+                )?;
+                expect_program(
+                    token_program,
+                    &spl_token::id(),
+                    ProgramError::IncorrectProgramId,
+                    "Invalid passed in for token program",
+                )?;
+                expect_program(
+                    system_program,
+                    &SYSTEM_PROGRAM_ID,
+                    ProgramError::IncorrectProgramId,
+                    "Invalid passed in for system program",
+                )?;
+                let vault_bump = expect_pda(
+                    vault,
+                    &[b"vault", marketplace_ai.key.as_ref(), offer_mint.key.as_ref()],
+                    program_id,
+                    "Invalid PDA seeds for vault",
+                )?;
+
+                let mut marketplace = Marketplace::load_mut(marketplace_ai, program_id)?;
+                let slot = marketplace
+                    .first_free_slot()
+                    .ok_or(RuntimeLimitationError::MarketplaceFull)?;
+
+                let vault_signer_seeds: &[&[u8]] = &[
+                    b"vault",
+                    marketplace_ai.key.as_ref(),
+                    offer_mint.key.as_ref(),
+                    &[vault_bump],
+                ];
+                if vault.data_is_empty() {
+                    // One vault per offer mint, owned by itself so the program can sign for it
+                    create_pda_account(
+                        seller,
+                        vault,
+                        system_program,
+                        &spl_token::id(),
+                        spl_token::state::Account::LEN,
+                        vault_signer_seeds,
+                    )?;
+                    invoke(
+                        &spl_token::instruction::initialize_account(
+                            &spl_token::id(),
+                            vault.key,
+                            offer_mint.key,
+                            vault.key,
+                        )?,
+                        &[vault.clone(), offer_mint.clone(), vault.clone(), rent.clone()],
+                    )?;
+                }
+                invoke(
+                    &spl_token::instruction::transfer(
+                        &spl_token::id(),
+                        seller_token_account.key,
+                        vault.key,
+                        seller.key,
+                        &[],
+                        offer_amount,
+                    )?,
+                    &[seller_token_account.clone(), vault.clone(), seller.clone()],
+                )?;
+
+                marketplace.insert(
+                    slot,
+                    Offer {
+                        seller: *seller.key,
+                        offer_mint: *offer_mint.key,
+                        buyer_mint: *buyer_mint.key,
+                        offer_amount,
+                        buyer_amount,
+                    },
+                );
+                msg!("Posted offer in slot {}", slot);
+            }
+            RuntimeLimitationInstruction::CancelOffer { slot } => {
+                msg!("Instruction: CancelOffer");
+                let marketplace_ai = next_account_info(accounts_iter)?;
+                let seller = next_account_info(accounts_iter)?;
+                let seller_token_account = next_account_info(accounts_iter)?;
+                let vault = next_account_info(accounts_iter)?;
+                let token_program = next_account_info(accounts_iter)?;
+
+                expect_signer(
+                    seller,
+                    ProgramError::MissingRequiredSignature,
+                    "Seller must sign",
+                )?;
+                expect_program(
+                    token_program,
+                    &spl_token::id(),
+                    ProgramError::IncorrectProgramId,
+                    "Invalid passed in for token program",
+                )?;
+                let mut marketplace = Marketplace::load_mut(marketplace_ai, program_id)?;
+                let slot = slot as usize;
+                assert_with_msg(
+                    marketplace.is_occupied(slot),
+                    RuntimeLimitationError::OfferNotFound.into(),
+                    "No offer in this slot",
+                )?;
+                let offer = marketplace.offers[slot];
+                assert_with_msg(
+                    offer.seller == *seller.key,
+                    RuntimeLimitationError::SellerMismatch.into(),
+                    "Only the seller can cancel an offer",
+                )?;
+                let vault_bump = assert_vault(program_id, marketplace_ai, vault, &offer)?;
+
+                invoke_signed(
+                    &spl_token::instruction::transfer(
+                        &spl_token::id(),
+                        vault.key,
+                        seller_token_account.key,
+                        vault.key,
+                        &[],
+                        offer.offer_amount,
+                    )?,
+                    &[vault.clone(), seller_token_account.clone(), vault.clone()],
+                    &[&[
+                        b"vault",
+                        marketplace_ai.key.as_ref(),
+                        offer.offer_mint.as_ref(),
+                        &[vault_bump],
+                    ]],
+                )?;
+                marketplace.remove(slot);
+                msg!("Cancelled offer in slot {}", slot);
+            }
+            RuntimeLimitationInstruction::FillOffer { slot } => {
+                msg!("Instruction: FillOffer");
+                let marketplace_ai = next_account_info(accounts_iter)?;
+                let buyer = next_account_info(accounts_iter)?;
+                let buyer_payment_account = next_account_info(accounts_iter)?;
+                let seller_payment_account = next_account_info(accounts_iter)?;
+                let buyer_token_account = next_account_info(accounts_iter)?;
+                let vault = next_account_info(accounts_iter)?;
+                let token_program = next_account_info(accounts_iter)?;
+
+                expect_signer(
+                    buyer,
+                    ProgramError::MissingRequiredSignature,
+                    "Buyer must sign",
+                )?;
+                expect_program(
+                    token_program,
+                    &spl_token::id(),
+                    ProgramError::IncorrectProgramId,
+                    "Invalid passed in for token program",
+                )?;
+                let mut marketplace = Marketplace::load_mut(marketplace_ai, program_id)?;
+                let slot = slot as usize;
+                assert_with_msg(
+                    marketplace.is_occupied(slot),
+                    RuntimeLimitationError::OfferNotFound.into(),
+                    "No offer in this slot",
+                )?;
+                let offer = marketplace.offers[slot];
+                let vault_bump = assert_vault(program_id, marketplace_ai, vault, &offer)?;
+                // The token program checks the mints of the transfers, but not who
+                // receives the payment
+                expect_owner(
+                    seller_payment_account,
+                    &spl_token::id(),
+                    "Payment account must be a token account",
+                )?;
+                let seller_payment =
+                    spl_token::state::Account::unpack(&seller_payment_account.data.borrow())?;
+                assert_with_msg(
+                    seller_payment.owner == offer.seller && seller_payment.mint == offer.buyer_mint,
+                    RuntimeLimitationError::InvalidPaymentAccount.into(),
+                    "Payment account must be the seller's buyer mint account",
+                )?;
+
+                invoke(
+                    &spl_token::instruction::transfer(
+                        &spl_token::id(),
+                        buyer_payment_account.key,
+                        seller_payment_account.key,
+                        buyer.key,
+                        &[],
+                        offer.buyer_amount,
+                    )?,
+                    &[
+                        buyer_payment_account.clone(),
+                        seller_payment_account.clone(),
+                        buyer.clone(),
+                    ],
+                )?;
+                invoke_signed(
+                    &spl_token::instruction::transfer(
+                        &spl_token::id(),
+                        vault.key,
+                        buyer_token_account.key,
+                        vault.key,
+                        &[],
+                        offer.offer_amount,
+                    )?,
+                    &[vault.clone(), buyer_token_account.clone(), vault.clone()],
+                    &[&[
+                        b"vault",
+                        marketplace_ai.key.as_ref(),
+                        offer.offer_mint.as_ref(),
+                        &[vault_bump],
+                    ]],
+                )?;
+                marketplace.remove(slot);
+                msg!("Filled offer in slot {}", slot);
+            }
+            RuntimeLimitationInstruction::Push { offers } => {
+                msg!("Instruction: Push");
+                let offers_ai = next_account_info(accounts_iter)?;
+                let authority = next_account_info(accounts_iter)?;
+                let system_program = next_account_info(accounts_iter)?;
+                expect_program(
+                    system_program,
+                    &SYSTEM_PROGRAM_ID,
+                    ProgramError::IncorrectProgramId,
+                    "Invalid passed in for system program",
+                )?;
+                if let Err(ProgramError::UninitializedAccount) =
+                    VecAccount::<Offer>::header(offers_ai, program_id)
+                {
+                    VecAccount::<Offer>::init(offers_ai, program_id, authority.key)?;
+                }
+                assert_vec_authority(program_id, offers_ai, authority)?;
+                sol_log_compute_units();
+                VecAccount::<Offer>::reserve(
+                    offers_ai,
+                    authority,
+                    system_program,
+                    program_id,
+                    offers.len(),
+                )?;
+                VecAccount::<Offer>::push(offers_ai, program_id, &offers)?;
+                let header = VecAccount::<Offer>::header(offers_ai, program_id)?;
+                msg!(
+                    "{} of {} offers used, account is {} bytes",
+                    header.len,
+                    header.capacity,
+                    offers_ai.data_len()
+                );
+                sol_log_compute_units();
+            }
+            RuntimeLimitationInstruction::Remove { index } => {
+                msg!("Instruction: Remove");
+                let offers_ai = next_account_info(accounts_iter)?;
+                let authority = next_account_info(accounts_iter)?;
+                assert_vec_authority(program_id, offers_ai, authority)?;
+                let offer =
+                    VecAccount::<Offer>::swap_remove(offers_ai, program_id, index as usize)?;
+                msg!("Removed offer of {} {}", offer.offer_amount, offer.offer_mint);
+            }
+            RuntimeLimitationInstruction::Compact => {
+                msg!("Instruction: Compact");
+                let offers_ai = next_account_info(accounts_iter)?;
+                let authority = next_account_info(accounts_iter)?;
+                assert_vec_authority(program_id, offers_ai, authority)?;
+                VecAccount::<Offer>::compact(offers_ai, authority, program_id)?;
+                msg!("Compacted to {} bytes", offers_ai.data_len());
+            }
+            RuntimeLimitationInstruction::Tx { offset, data }=> {
+                let buffer_ai = next_account_info(accounts_iter)?;
+                expect_writable(buffer_ai, "Buffer must be writable")?;
+                let mut buffer = buffer_ai.try_borrow_mut_data()?;
+                let offset = offset as usize;
+                assert_with_msg(
+                    offset <= buffer.len(),
+                    RuntimeLimitationError::OffsetOutOfBounds.into(),
+                    "Offset is past the end of the buffer",
+                )?;
+                let len = data.len().min(buffer.len() - offset);
+                buffer[offset..offset + len].copy_from_slice(&data[..len]);
+                if len < data.len() {
+                    msg!("Truncated {} bytes that did not fit", data.len() - len);
+                }
+                let written = &buffer[offset..offset + len];
+                match std::str::from_utf8(written) {
+                    Ok(echo) => msg!("Echo {}", echo),
+                    Err(_) => msg!("Echo (hex) {}", hex_preview(written)),
+                }
                 sol_log_compute_units();
             }
         }
@@ -1,61 +1,83 @@
 use borsh::{BorshDeserialize, BorshSerialize};
-use solana_program::{
-    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
-    pubkey::Pubkey,
-};
+use bootcamp_accounts::ZeroCopy;
+use solana_program::pubkey::Pubkey;
 
-use bytemuck::{from_bytes, from_bytes_mut, Zeroable, Pod};
+use bytemuck::{Zeroable, Pod};
 
-use std::{
-    cell::{Ref, RefMut},
-    mem,
-};
+use std::{io, mem};
+
+/// Number of slots in `Marketplace::offers`.
+pub const MAX_OFFERS: usize = 256;
 
 #[derive(BorshSerialize, BorshDeserialize, Default, Debug, Copy, Clone, Zeroable, Pod)]
 #[repr(C)]
 pub struct Offer {
+    pub seller: Pubkey, // receives buyer_amount of buyer_mint on fill
     pub offer_mint: Pubkey, // NFT
     pub buyer_mint: Pubkey, // USDC, SOL, BTC
     pub offer_amount: u64, // 1
     pub buyer_amount: u64, // price the user is asking
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy)]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, Zeroable)]
 pub struct MarketplaceBorsh {
     pub user: Pubkey,
+    pub occupied: [u64; 4],
     pub offers: [Offer; 256]
 }
 
+impl MarketplaceBorsh {
+    /// Deserializes directly into a heap allocation. `Box::new(Self::deserialize(buf)?)`
+    /// would build the whole struct on the stack first.
+    pub fn deserialize_boxed(buf: &mut &[u8]) -> io::Result<Box<Self>> {
+        let mut marketplace = bytemuck::zeroed_box::<Self>();
+        marketplace.user = Pubkey::deserialize(buf)?;
+        marketplace.occupied = <[u64; 4]>::deserialize(buf)?;
+        for offer in marketplace.offers.iter_mut() {
+            *offer = Offer::deserialize(buf)?;
+        }
+        Ok(marketplace)
+    }
+}
+
 impl Default for MarketplaceBorsh {
     fn default() -> Self {
         Self {
             user: Pubkey::default(),
+            occupied: [0; 4],
             offers: [Offer::default(); 256]
         }    
     }
 }
 
-#[derive(Debug, Clone, Copy, Zeroable, Pod)]
+#[derive(Debug, Clone, Copy, Zeroable, Pod, ZeroCopy)]
 #[repr(C)]
 pub struct Marketplace {
     pub user: Pubkey,
+    pub occupied: [u64; 4], // bitmap of the slots in `offers` that hold an offer
     pub offers: [Offer; 256]
 }
 
-impl ZeroCopy for Marketplace {}
+impl Marketplace {
+    pub fn is_occupied(&self, slot: usize) -> bool {
+        self.occupied[slot / 64] & (1 << (slot % 64)) != 0
+    }
+
+    pub fn first_free_slot(&self) -> Option<usize> {
+        self.occupied
+            .iter()
+            .enumerate()
+            .find(|(_, word)| **word != u64::MAX)
+            .map(|(i, word)| i * 64 + word.trailing_ones() as usize)
+    }
 
-pub trait ZeroCopy: Pod {
-    fn load<'a>(account: &'a AccountInfo) -> Result<Ref<'a, Self>, ProgramError> {
-        let size = mem::size_of::<Self>();
-        Ok(Ref::map(account.try_borrow_data()?, |data| {
-            from_bytes(&data[..size])
-        }))
+    pub fn insert(&mut self, slot: usize, offer: Offer) {
+        self.occupied[slot / 64] |= 1 << (slot % 64);
+        self.offers[slot] = offer;
     }
 
-    fn load_mut<'a>(account: &'a AccountInfo) -> Result<RefMut<'a, Self>, ProgramError> {
-        let size = mem::size_of::<Self>();
-        Ok(RefMut::map(account.try_borrow_mut_data()?, |data| {
-            from_bytes_mut(&mut data[..size])
-        }))
+    pub fn remove(&mut self, slot: usize) -> Offer {
+        self.occupied[slot / 64] &= !(1 << (slot % 64));
+        mem::take(&mut self.offers[slot])
     }
-}
\ No newline at end of file
+}
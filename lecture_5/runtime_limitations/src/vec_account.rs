@@ -0,0 +1,155 @@
+use bootcamp_accounts::{check_account, check_header, write_header, ZeroCopy, HEADER_LEN};
+use bytemuck::{cast_slice_mut, from_bytes_mut, Pod, Zeroable};
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::{ProgramResult, MAX_PERMITTED_DATA_INCREASE},
+    program::invoke,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction,
+    sysvar::{rent::Rent, Sysvar},
+};
+
+use std::{marker::PhantomData, mem};
+
+use crate::error::RuntimeLimitationError;
+
+/// Sits in front of the elements of a `VecAccount`.
+#[derive(Debug, Clone, Copy, Zeroable, Pod, ZeroCopy)]
+#[repr(C)]
+pub struct VecHeader {
+    pub authority: Pubkey,
+    pub len: u64,
+    pub capacity: u64,
+}
+
+/// A growable array of `T` stored in a single account: `VecHeader` followed by
+/// `capacity` slots of `T`, of which the first `len` are in use.
+///
+/// Accounts can only grow by `MAX_PERMITTED_DATA_INCREASE` (10KB) per instruction, so
+/// large vectors are built up over several transactions.
+pub struct VecAccount<T: Pod>(PhantomData<T>);
+
+impl<T: Pod> VecAccount<T> {
+    /// Account size needed to hold `capacity` elements.
+    pub fn space(capacity: usize) -> usize {
+        VecHeader::LEN + capacity * mem::size_of::<T>()
+    }
+
+    fn split(data: &mut [u8]) -> (&mut VecHeader, &mut [T]) {
+        let (header, elements) = data.split_at_mut(VecHeader::LEN);
+        let header: &mut VecHeader = from_bytes_mut(&mut header[HEADER_LEN..]);
+        let end = header.capacity as usize * mem::size_of::<T>();
+        (header, cast_slice_mut(&mut elements[..end]))
+    }
+
+    pub fn init(account: &AccountInfo, program_id: &Pubkey, authority: &Pubkey) -> ProgramResult {
+        check_account(account, program_id, VecHeader::LEN)?;
+        let mut data = account.try_borrow_mut_data()?;
+        write_header::<VecHeader>(&mut data)?;
+        let capacity = (data.len() - VecHeader::LEN) / mem::size_of::<T>();
+        let (header, _) = Self::split(&mut data);
+        header.authority = *authority;
+        header.len = 0;
+        header.capacity = capacity as u64;
+        Ok(())
+    }
+
+    pub fn header(account: &AccountInfo, program_id: &Pubkey) -> Result<VecHeader, ProgramError> {
+        Ok(*VecHeader::load(account, program_id)?)
+    }
+
+    /// Grows the account so that `additional` more elements fit, doubling the capacity
+    /// where the per-instruction limit allows. `payer` tops up the rent exemption.
+    pub fn reserve<'a>(
+        account: &AccountInfo<'a>,
+        payer: &AccountInfo<'a>,
+        system_program: &AccountInfo<'a>,
+        program_id: &Pubkey,
+        additional: usize,
+    ) -> ProgramResult {
+        let header = Self::header(account, program_id)?;
+        let required = header.len as usize + additional;
+        if required <= header.capacity as usize {
+            return Ok(());
+        }
+        let max_len = account.data_len() + MAX_PERMITTED_DATA_INCREASE;
+        let max_capacity = (max_len - VecHeader::LEN) / mem::size_of::<T>();
+        if required > max_capacity {
+            return Err(RuntimeLimitationError::GrowthLimitExceeded.into());
+        }
+        let capacity = (header.capacity as usize * 2).clamp(required, max_capacity);
+        let new_len = Self::space(capacity);
+
+        let lamports = Rent::get()?
+            .minimum_balance(new_len)
+            .saturating_sub(account.lamports());
+        if lamports > 0 {
+            invoke(
+                &system_instruction::transfer(payer.key, account.key, lamports),
+                &[payer.clone(), account.clone(), system_program.clone()],
+            )?;
+        }
+        account.realloc(new_len, true)?;
+        let mut data = account.try_borrow_mut_data()?;
+        let header: &mut VecHeader = from_bytes_mut(&mut data[HEADER_LEN..VecHeader::LEN]);
+        header.capacity = capacity as u64;
+        Ok(())
+    }
+
+    /// Appends `items`, which must fit in the current capacity.
+    pub fn push(account: &AccountInfo, program_id: &Pubkey, items: &[T]) -> ProgramResult {
+        check_account(account, program_id, VecHeader::LEN)?;
+        let mut data = account.try_borrow_mut_data()?;
+        check_header::<VecHeader>(&data)?;
+        let (header, elements) = Self::split(&mut data);
+        let len = header.len as usize;
+        if len + items.len() > elements.len() {
+            return Err(RuntimeLimitationError::CapacityExceeded.into());
+        }
+        elements[len..len + items.len()].copy_from_slice(items);
+        header.len += items.len() as u64;
+        Ok(())
+    }
+
+    /// Removes the element at `index` in O(1) by moving the last element into its place.
+    pub fn swap_remove(
+        account: &AccountInfo,
+        program_id: &Pubkey,
+        index: usize,
+    ) -> Result<T, ProgramError> {
+        check_account(account, program_id, VecHeader::LEN)?;
+        let mut data = account.try_borrow_mut_data()?;
+        check_header::<VecHeader>(&data)?;
+        let (header, elements) = Self::split(&mut data);
+        let len = header.len as usize;
+        if index >= len {
+            return Err(RuntimeLimitationError::IndexOutOfBounds.into());
+        }
+        let removed = elements[index];
+        elements[index] = elements[len - 1];
+        elements[len - 1] = T::zeroed();
+        header.len -= 1;
+        Ok(removed)
+    }
+
+    /// Shrinks the account to fit its elements and refunds the freed rent to `receiver`.
+    pub fn compact(
+        account: &AccountInfo,
+        receiver: &AccountInfo,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let mut header = Self::header(account, program_id)?;
+        let new_len = Self::space(header.len as usize);
+        account.realloc(new_len, false)?;
+        header.capacity = header.len;
+        *VecHeader::load_mut(account, program_id)? = header;
+
+        let refund = account
+            .lamports()
+            .saturating_sub(Rent::get()?.minimum_balance(new_len));
+        **account.try_borrow_mut_lamports()? -= refund;
+        **receiver.try_borrow_mut_lamports()? += refund;
+        Ok(())
+    }
+}
@@ -1,6 +1,13 @@
+use bootcamp_idl_derive::IdlInstruction;
 use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+};
 
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+#[derive(BorshSerialize, BorshDeserialize, IdlInstruction, Debug, Clone)]
 pub enum EchoInstruction {
     /// The contents of the data vector that is provided to the instruction will be copied into the echo_buffer account.
     ///
@@ -8,71 +15,98 @@ pub enum EchoInstruction {
     /// first N bytes of data into `echo_buffer`.
     ///
     /// If `echo_buffer` has any non-zero data, the instruction will fail.
-    ///
-    /// Accounts:
-    /// | index | writable | signer | description                                  |
-    /// |-------|----------|--------|----------------------------------------------|
-    /// | 0     | ✅       | ❌     | echo_buffer: Destination account of the data  |
+    #[account(
+        0,
+        writable,
+        name = "echo_buffer",
+        desc = "Destination account of the data"
+    )]
     Echo { data: Vec<u8> },
     /// This instruction will allocate `buffer_size` bytes to the `authorized_buffer` account and assign it the Echo Program.
     ///
-    /// The first 9 bytes of authorized_buffer will be set with the following data:
-    ///     byte 0: bump_seed
-    ///     bytes 1-8: buffer_seed
-    ///
-    /// Accounts:
-    /// | index | writable | signer | description                                                              |
-    /// |-------|----------|--------|--------------------------------------------------------------------------|
-    /// | 0     | ✅       | ❌     | authorized_buffer: PDA of Echo Program that only `authority` can write to |
-    /// | 1     | ❌       | ✅     | authority: Pubkey with sole write access to `authorized_buffer`           |
-    /// | 2     | ❌       | ❌     | system_program: Used to allocate the buffer                               |
+    /// The first 25 bytes of authorized_buffer will be set with the following data:
+    ///     bytes 0-15: account header (discriminator and version of `AuthorizedBufferHeader`)
+    ///     byte 16: bump_seed
+    ///     bytes 17-24: buffer_seed
+    #[account(
+        0,
+        writable,
+        name = "authorized_buffer",
+        desc = "PDA of Echo Program that only `authority` can write to"
+    )]
+    #[account(
+        1,
+        writable,
+        signer,
+        name = "authority",
+        desc = "Pubkey with sole write access to `authorized_buffer`"
+    )]
+    #[account(2, name = "system_program", desc = "Used to allocate the buffer")]
     InitializeAuthorizedEcho {
         buffer_seed: u64,
         buffer_size: usize,
     },
     /// The contents of the data vector that is provided to the instruction will be copied into the `authorized_buffer` account
-    /// starting from index 9 (will NOT override the bump_seed and buffer_seed).
+    /// starting from index 25 (will NOT override the account header, bump_seed and buffer_seed).
     ///
     /// If the remaining `authorized_buffer` account length ( N ) is smaller than the length of `data`, copy the first N bytes
     /// of data into `authorized_buffer`.
     ///
-    /// Initially, if `authorized_buffer` has any non-zero data past index 9, you should should zero out all of the data outside
-    /// of the first 9 bytes.
+    /// Initially, if `authorized_buffer` has any non-zero data past index 25, you should should zero out all of the data outside
+    /// of the first 25 bytes.
     ///
     /// If any account besides the `authority` attempts to write to the `authorized_buffer`, the instruction will fail.
-    ///
-    /// Accounts:
-    /// | index | writable | signer | description                                                              |
-    /// |-------|----------|--------|--------------------------------------------------------------------------|
-    /// | 0     | ✅       | ❌     | authorized_buffer: PDA of Echo Program that only `authority` can write to |
-    /// | 1     | ❌       | ✅     | authority: Pubkey with sole write access to `authorized_buffer`           |
+    #[account(
+        0,
+        writable,
+        name = "authorized_buffer",
+        desc = "PDA of Echo Program that only `authority` can write to"
+    )]
+    #[account(
+        1,
+        signer,
+        name = "authority",
+        desc = "Pubkey with sole write access to `authorized_buffer`"
+    )]
     AuthorizedEcho { data: Vec<u8> },
     /// This instruction will allocate `buffer_size` bytes to the `vending_machine_buffer` account and assign it the Echo Program.
     ///
-    /// The first 9 bytes of `vending_machine_buffer` will be set with the following data:
-    ///     byte 0: bump_seed
-    ///     bytes 1-8: price
-    ///
-    /// Accounts:
-    /// | index | writable | signer | description                                                                                         |
-    /// |-------|----------|--------|-----------------------------------------------------------------------------------------------------|
-    /// | 0     | ✅       | ❌     | vending_machine_buffer: PDA of the Echo Program that only holders of a particular token can write to |
-    /// | 1     | ❌       | ❌     | vending_machine_mint: Pubkey with sole write access to `authorized_buffer`                           |
-    /// | 2     | ❌       | ✅     | payer: Pubkey that allocates the `vending_machine_buffer`                                            |
-    /// | 3     | ❌       | ❌     | system_program: Used to allocate the buffer                                                          |
+    /// The first 25 bytes of `vending_machine_buffer` will be set with the following data:
+    ///     bytes 0-15: account header (discriminator and version of `VendingMachineBufferHeader`)
+    ///     byte 16: bump_seed
+    ///     bytes 17-24: price
+    #[account(
+        0,
+        writable,
+        name = "vending_machine_buffer",
+        desc = "PDA of the Echo Program that only holders of a particular token can write to"
+    )]
+    #[account(
+        1,
+        name = "vending_machine_mint",
+        desc = "Token mint that is accepted by the `vending_machine_buffer`"
+    )]
+    #[account(
+        2,
+        writable,
+        signer,
+        name = "payer",
+        desc = "Pubkey that allocates the `vending_machine_buffer`"
+    )]
+    #[account(3, name = "system_program", desc = "Used to allocate the buffer")]
     InitializeVendingMachineEcho {
         // Number of tokens required change the buffer
         price: u64,
         buffer_size: usize,
     },
     /// The contents of the data vector that is provided to the instruction should be copied into the account starting from
-    /// index 9 (you do NOT want to override the bump_seed and price).
+    /// index 25 (you do NOT want to override the account header, bump_seed and price).
     ///
     /// If the remaining account length ( N ) is smaller than the length of data, copy the first N bytes of data into
     /// `vending_machine_buffer`.
     ///
-    /// Initially, if `vending_machine_buffer` has any non-zero data past index 9, you should should zero out all of the
-    /// data outside of the first 9 bytes.
+    /// Initially, if `vending_machine_buffer` has any non-zero data past index 25, you should should zero out all of the
+    /// data outside of the first 25 bytes.
     ///
     /// Before any data is copied over, the user must burn a `price` amount of tokens from the `user_token_account`.
     /// This will require a cross program invocation to the Token Program. If this instruction succeed (verifies that the
@@ -81,14 +115,124 @@ pub enum EchoInstruction {
     /// This instruction should fail in the case that the mint of the `vending_machine_buffer` does not match the mint
     /// used to seed the PDA.  You can verify this by comparing the output of `Pubkey::create_program_address` with the correct
     /// seeds to the value of `vending_machine_buffer.key`.
-    ///
-    /// Accounts:
-    /// | index | writable | signer | description                                                                                         |
-    /// |-------|----------|--------|-----------------------------------------------------------------------------------------------------|
-    /// | 0     | ✅       | ❌     | vending_machine_buffer: PDA of the Echo Program that only holders of a particular token can write to |
-    /// | 1     | ❌       | ✅     | user: This is authority of the token account that is using the vending machine                       |
-    /// | 2     | ✅       | ❌     | user_token_account: This is the token account that will pay for the use of the vending machine       |
-    /// | 3     | ❌       | ❌     | vending_machine_mint: This is the token mint that is accepted by the `vending_machine_buffer`        |
-    /// | 3     | ❌       | ❌     | token_program: Used to burn the vending machine tokens                                               |
+    #[account(
+        0,
+        writable,
+        name = "vending_machine_buffer",
+        desc = "PDA of the Echo Program that only holders of a particular token can write to"
+    )]
+    #[account(
+        1,
+        signer,
+        name = "user",
+        desc = "Authority of the token account that is using the vending machine"
+    )]
+    #[account(
+        2,
+        writable,
+        name = "user_token_account",
+        desc = "Token account that will pay for the use of the vending machine"
+    )]
+    #[account(
+        3,
+        name = "vending_machine_mint",
+        desc = "Token mint that is accepted by the `vending_machine_buffer`"
+    )]
+    #[account(
+        4,
+        name = "token_program",
+        desc = "Used to burn the vending machine tokens"
+    )]
     VendingMachineEcho { data: Vec<u8> },
 }
+
+pub fn echo(
+    program_id: Pubkey,
+    echo_buffer: Pubkey,
+    data: Vec<u8>,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction {
+        accounts: vec![AccountMeta::new(echo_buffer, false)],
+        data: EchoInstruction::Echo { data }.try_to_vec()?,
+        program_id,
+    })
+}
+
+pub fn initialize_authorized_echo(
+    program_id: Pubkey,
+    authorized_buffer: Pubkey,
+    authority: Pubkey,
+    buffer_seed: u64,
+    buffer_size: usize,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction {
+        accounts: vec![
+            AccountMeta::new(authorized_buffer, false),
+            AccountMeta::new(authority, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: EchoInstruction::InitializeAuthorizedEcho {
+            buffer_seed,
+            buffer_size,
+        }
+        .try_to_vec()?,
+        program_id,
+    })
+}
+
+pub fn authorized_echo(
+    program_id: Pubkey,
+    authorized_buffer: Pubkey,
+    authority: Pubkey,
+    data: Vec<u8>,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction {
+        accounts: vec![
+            AccountMeta::new(authorized_buffer, false),
+            AccountMeta::new_readonly(authority, true),
+        ],
+        data: EchoInstruction::AuthorizedEcho { data }.try_to_vec()?,
+        program_id,
+    })
+}
+
+pub fn initialize_vending_machine_echo(
+    program_id: Pubkey,
+    vending_machine_buffer: Pubkey,
+    vending_machine_mint: Pubkey,
+    payer: Pubkey,
+    price: u64,
+    buffer_size: usize,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction {
+        accounts: vec![
+            AccountMeta::new(vending_machine_buffer, false),
+            AccountMeta::new_readonly(vending_machine_mint, false),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: EchoInstruction::InitializeVendingMachineEcho { price, buffer_size }.try_to_vec()?,
+        program_id,
+    })
+}
+
+pub fn vending_machine_echo(
+    program_id: Pubkey,
+    vending_machine_buffer: Pubkey,
+    user: Pubkey,
+    user_token_account: Pubkey,
+    vending_machine_mint: Pubkey,
+    data: Vec<u8>,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction {
+        accounts: vec![
+            AccountMeta::new(vending_machine_buffer, false),
+            AccountMeta::new_readonly(user, true),
+            AccountMeta::new(user_token_account, false),
+            AccountMeta::new_readonly(vending_machine_mint, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: EchoInstruction::VendingMachineEcho { data }.try_to_vec()?,
+        program_id,
+    })
+}
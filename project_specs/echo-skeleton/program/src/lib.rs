@@ -3,3 +3,12 @@ pub mod error;
 pub mod instruction;
 pub mod processor;
 pub mod state;
+
+/// Anchor compatible IDL of the program.
+#[cfg(feature = "idl")]
+pub fn idl() -> bootcamp_idl::Idl {
+    bootcamp_idl::Idl::new("echo", env!("CARGO_PKG_VERSION"))
+        .instructions::<instruction::EchoInstruction>()
+        .account::<state::AuthorizedBufferHeader>()
+        .account::<state::VendingMachineBufferHeader>()
+}
@@ -1,11 +1,17 @@
+use bootcamp_accounts::BorshAccount;
+use bootcamp_idl_derive::IdlType;
 use borsh::{BorshDeserialize, BorshSerialize};
 
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+/// Written after the account header, followed by the echoed data.
+#[derive(BorshSerialize, BorshDeserialize, BorshAccount, IdlType, Debug, Clone)]
 pub struct AuthorizedBufferHeader {
-    // TODO
+    pub bump_seed: u8,
+    pub buffer_seed: u64,
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+/// Written after the account header, followed by the echoed data.
+#[derive(BorshSerialize, BorshDeserialize, BorshAccount, IdlType, Debug, Clone)]
 pub struct VendingMachineBufferHeader {
-    // TODO
+    pub bump_seed: u8,
+    pub price: u64,
 }
@@ -93,7 +93,7 @@ pub struct InitializeTokenAccount<'info> {
 pub struct MintCtx<'info> {
     #[account(mut)]
     mint: Account<'info, Mint>,
-    #[account(mut)]
+    #[account(mut, constraint = dst.key() != mint.key() @ TokenError::DuplicateAccount)]
     dst: Account<'info, TokenAccount>,
     authority: Signer<'info>,
 }
@@ -102,7 +102,7 @@ pub struct MintCtx<'info> {
 pub struct Burn<'info> {
     #[account(mut)]
     mint: Account<'info, Mint>,
-    #[account(mut)]
+    #[account(mut, constraint = src.key() != mint.key() @ TokenError::DuplicateAccount)]
     src: Account<'info, TokenAccount>,
     owner: Signer<'info>,
 }
@@ -111,7 +111,9 @@ pub struct Burn<'info> {
 pub struct Transfer<'info> {
     #[account(mut)]
     src: Account<'info, TokenAccount>,
-    #[account(mut)]
+    // Both accounts are serialized on exit, dst last, so an aliased dst would keep
+    // its `amount` added on top of the untouched balance
+    #[account(mut, constraint = dst.key() != src.key() @ TokenError::DuplicateAccount)]
     dst: Account<'info, TokenAccount>,
     owner: Signer<'info>,
 }
@@ -129,3 +131,9 @@ struct TokenAccount {
     pub owner: Pubkey,
     pub amount: u64,
 }
+
+#[error_code]
+pub enum TokenError {
+    #[msg("The same account was passed in more than once")]
+    DuplicateAccount,
+}
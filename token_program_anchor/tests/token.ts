@@ -1,6 +1,7 @@
 import * as anchor from "@project-serum/anchor";
 import { Program } from "@project-serum/anchor";
 import { Token } from "../target/types/token";
+import { assert } from "chai";
 
 const logTx = async (provider, tx) => {
   await provider.connection.confirmTransaction(tx, "confirmed");
@@ -127,4 +128,38 @@ describe("token", () => {
     });
     await logTx(program.provider, txid);
   });
+
+  it("Rejects aliased accounts and conserves supply", async () => {
+    let mint = (await anchor.web3.PublicKey.findProgramAddress(
+      [mint_auth.publicKey.toBuffer()],
+      program.programId,
+    ))[0];
+    let user1TA = (await anchor.web3.PublicKey.findProgramAddress(
+      [user1.publicKey.toBuffer(), mint.toBuffer()], program.programId
+    ))[0];
+    let user2TA = (await anchor.web3.PublicKey.findProgramAddress(
+      [user2.publicKey.toBuffer(), mint.toBuffer()], program.programId
+    ))[0];
+
+    // Passing the same account as src and dst used to credit it `amount` out of thin air
+    try {
+      await program.rpc.transfer(new anchor.BN(12), {
+        accounts: {
+          src: user1TA,
+          dst: user1TA,
+          owner: user1.publicKey,
+        },
+        signers: [user1],
+      });
+      assert.fail("transfer to itself should fail");
+    } catch (e) {
+      assert.equal(e.msg, "The same account was passed in more than once");
+    }
+
+    const supply = (await program.account.mint.fetch(mint)).supply;
+    const user1Amount = (await program.account.tokenAccount.fetch(user1TA)).amount;
+    const user2Amount = (await program.account.tokenAccount.fetch(user2TA)).amount;
+    assert.equal(user1Amount.toNumber(), 12);
+    assert.equal(supply.toNumber(), user1Amount.add(user2Amount).toNumber());
+  });
 });
@@ -0,0 +1,131 @@
+//! Splits a blob into `uploader` instructions and sends them, resuming uploads whose
+//! buffer already holds some of the chunks.
+
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind},
+    rpc_client::RpcClient,
+};
+use solana_program::{
+    hash::hash, instruction::Instruction, program_error::ProgramError, pubkey::Pubkey,
+    system_instruction,
+};
+use solana_sdk::{
+    signature::{Keypair, Signature, Signer},
+    transaction::Transaction,
+};
+use uploader::{
+    error::UploaderError,
+    instruction,
+    state::{bitmap_len, buffer_size, chunk_count, is_chunk_written, BufferHeader, CHUNK_SIZE},
+};
+
+use bootcamp_accounts::ZeroCopy;
+
+/// Every instruction needed to upload `blob` into `buffer`.
+pub struct UploadPlan {
+    pub initialize: Instruction,
+    /// One `Write` per chunk, in order
+    pub writes: Vec<Instruction>,
+    pub finalize: Instruction,
+}
+
+pub fn plan(
+    program_id: Pubkey,
+    buffer: Pubkey,
+    authority: Pubkey,
+    blob: &[u8],
+) -> Result<UploadPlan, ProgramError> {
+    let writes = blob
+        .chunks(CHUNK_SIZE)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let offset = (i * CHUNK_SIZE) as u64;
+            instruction::write(program_id, buffer, authority, offset, chunk.to_vec())
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(UploadPlan {
+        initialize: instruction::initialize(program_id, buffer, authority, blob.len() as u64)?,
+        writes,
+        finalize: instruction::finalize(program_id, buffer, authority, hash(blob).to_bytes())?,
+    })
+}
+
+/// Indices of the chunks that have not landed in an initialized buffer account yet.
+pub fn missing_chunks(account_data: &[u8]) -> Result<Vec<usize>, ProgramError> {
+    let header = BufferHeader::read(account_data)?;
+    let data_len = header.data_len as usize;
+    let bitmap = account_data
+        .get(BufferHeader::LEN..BufferHeader::LEN + bitmap_len(data_len))
+        .ok_or(ProgramError::AccountDataTooSmall)?;
+    Ok((0..chunk_count(data_len))
+        .filter(|chunk| !is_chunk_written(bitmap, *chunk))
+        .collect())
+}
+
+fn program_error(e: ProgramError) -> ClientError {
+    ClientErrorKind::Custom(e.to_string()).into()
+}
+
+fn send(
+    client: &RpcClient,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<Signature, ClientError> {
+    let mut tx = Transaction::new_with_payer(instructions, Some(&signers[0].pubkey()));
+    tx.sign(signers, client.get_latest_blockhash()?);
+    client.send_and_confirm_transaction(&tx)
+}
+
+/// Uploads `blob` into `buffer` and finalizes it. If `buffer` already exists only the
+/// missing chunks are sent, so an interrupted upload can be retried with the same keypair.
+/// An existing buffer must have been initialized for a blob of the same length. `payer`
+/// is also the authority of the buffer.
+pub fn upload(
+    client: &RpcClient,
+    program_id: Pubkey,
+    payer: &Keypair,
+    buffer: &Keypair,
+    blob: &[u8],
+) -> Result<Signature, ClientError> {
+    let plan = plan(program_id, buffer.pubkey(), payer.pubkey(), blob)
+        .map_err(program_error)?;
+
+    let account = client
+        .get_account_with_commitment(&buffer.pubkey(), client.commitment())?
+        .value;
+    let missing = match account {
+        Some(account) => {
+            // Chunk indices only line up with a buffer initialized for this blob
+            let header = BufferHeader::read(&account.data).map_err(program_error)?;
+            if header.data_len != blob.len() as u64 {
+                let message = format!(
+                    "Buffer {} holds {} bytes, not the {} bytes of the blob",
+                    buffer.pubkey(),
+                    header.data_len,
+                    blob.len()
+                );
+                return Err(ClientErrorKind::Custom(message).into());
+            }
+            missing_chunks(&account.data).map_err(program_error)?
+        }
+        None => {
+            let space = buffer_size(blob.len())
+                .ok_or_else(|| program_error(UploaderError::DataTooLarge.into()))?;
+            let lamports = client.get_minimum_balance_for_rent_exemption(space)?;
+            let create = system_instruction::create_account(
+                &payer.pubkey(),
+                &buffer.pubkey(),
+                lamports,
+                space as u64,
+                &program_id,
+            );
+            send(client, &[create, plan.initialize], &[payer, buffer])?;
+            (0..plan.writes.len()).collect()
+        }
+    };
+
+    for chunk in missing {
+        send(client, &[plan.writes[chunk].clone()], &[payer])?;
+    }
+    send(client, &[plan.finalize], &[payer])
+}
@@ -0,0 +1,4 @@
+bootcamp_program_utils::entrypoint!(
+    crate::processor::Processor::process_instruction,
+    crate::error::UploaderError
+);
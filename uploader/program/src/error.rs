@@ -0,0 +1,48 @@
+use num_derive::FromPrimitive;
+use solana_program::{
+    decode_error::DecodeError,
+    msg,
+    program_error::{PrintProgramError, ProgramError},
+};
+use thiserror::Error;
+
+#[derive(Error, Debug, Copy, Clone, FromPrimitive, PartialEq)]
+pub enum UploaderError {
+    #[error("Only the authority of the buffer can modify it.")]
+    AuthorityMismatch,
+    #[error("Buffer is finalized and can no longer be written to.")]
+    AlreadyFinalized,
+    #[error("Chunk offset or length does not line up with the chunk boundaries.")]
+    InvalidChunk,
+    #[error("Not every chunk has been written.")]
+    MissingChunks,
+    #[error("Hash of the buffer does not match the expected hash.")]
+    HashMismatch,
+    #[error("Data length is larger than the maximum length of a buffer.")]
+    DataTooLarge,
+}
+
+impl From<UploaderError> for ProgramError {
+    fn from(e: UploaderError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+impl<T> DecodeError<T> for UploaderError {
+    fn type_of() -> &'static str {
+        "UploaderError"
+    }
+}
+
+impl PrintProgramError for UploaderError {
+    fn print<E>(&self)
+    where
+        E: 'static
+            + std::error::Error
+            + DecodeError<E>
+            + PrintProgramError
+            + num_traits::FromPrimitive,
+    {
+        msg!("Error: {}", self);
+    }
+}
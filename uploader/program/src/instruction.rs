@@ -0,0 +1,96 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub enum UploaderInstruction {
+    /// Sets up a buffer that was created by the client with `state::buffer_size(data_len)`
+    /// bytes and this program as owner. Buffers can be bigger than the 10KB a program is
+    /// allowed to allocate, so the program does not create them itself. `data_len` can be
+    /// at most `state::MAX_DATA_LEN`, so that `Finalize` can hash the data.
+    ///
+    /// | index | writable | signer | description                              |
+    /// |-------|----------|--------|------------------------------------------|
+    /// | 0     | ✅       | ❌     | buffer: uninitialized, owned by program  |
+    /// | 1     | ❌       | ✅     | authority: allowed to write and finalize |
+    Initialize { data_len: u64 },
+    /// Writes the chunk starting at `offset`. `offset` must be a multiple of
+    /// `state::CHUNK_SIZE` and `data` must fill the whole chunk (the last chunk may be
+    /// shorter). Chunks can land in any order and can be rewritten until finalized.
+    ///
+    /// | index | writable | signer | description       |
+    /// |-------|----------|--------|-------------------|
+    /// | 0     | ✅       | ❌     | buffer            |
+    /// | 1     | ❌       | ✅     | authority         |
+    Write { offset: u64, data: Vec<u8> },
+    /// Checks that every chunk landed and that the SHA-256 of the data equals `hash`,
+    /// then marks the buffer immutable.
+    ///
+    /// | index | writable | signer | description       |
+    /// |-------|----------|--------|-------------------|
+    /// | 0     | ✅       | ❌     | buffer            |
+    /// | 1     | ❌       | ✅     | authority         |
+    Finalize { hash: [u8; 32] },
+}
+
+fn uploader_instruction(
+    program_id: Pubkey,
+    buffer: Pubkey,
+    authority: Pubkey,
+    instruction: UploaderInstruction,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction {
+        accounts: vec![
+            AccountMeta::new(buffer, false),
+            AccountMeta::new_readonly(authority, true),
+        ],
+        data: instruction.try_to_vec()?,
+        program_id,
+    })
+}
+
+pub fn initialize(
+    program_id: Pubkey,
+    buffer: Pubkey,
+    authority: Pubkey,
+    data_len: u64,
+) -> Result<Instruction, ProgramError> {
+    uploader_instruction(
+        program_id,
+        buffer,
+        authority,
+        UploaderInstruction::Initialize { data_len },
+    )
+}
+
+pub fn write(
+    program_id: Pubkey,
+    buffer: Pubkey,
+    authority: Pubkey,
+    offset: u64,
+    data: Vec<u8>,
+) -> Result<Instruction, ProgramError> {
+    uploader_instruction(
+        program_id,
+        buffer,
+        authority,
+        UploaderInstruction::Write { offset, data },
+    )
+}
+
+pub fn finalize(
+    program_id: Pubkey,
+    buffer: Pubkey,
+    authority: Pubkey,
+    hash: [u8; 32],
+) -> Result<Instruction, ProgramError> {
+    uploader_instruction(
+        program_id,
+        buffer,
+        authority,
+        UploaderInstruction::Finalize { hash },
+    )
+}
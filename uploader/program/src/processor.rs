@@ -0,0 +1,120 @@
+use borsh::BorshDeserialize;
+use bootcamp_accounts::{check_account, write_header, ZeroCopy, HEADER_LEN};
+use bootcamp_program_utils::{assert_with_msg, expect_signer};
+use bytemuck::from_bytes_mut;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    hash::hash,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use crate::error::UploaderError;
+use crate::instruction::UploaderInstruction;
+use crate::state::{
+    buffer_size, chunk_count, is_chunk_written, Buffer, BufferHeader, CHUNK_SIZE,
+};
+
+/// Checks the authority and that the buffer can still be modified.
+fn assert_writable_buffer(header: &BufferHeader, authority: &AccountInfo) -> ProgramResult {
+    expect_signer(
+        authority,
+        ProgramError::MissingRequiredSignature,
+        "Authority must sign",
+    )?;
+    assert_with_msg(
+        header.authority == *authority.key,
+        UploaderError::AuthorityMismatch.into(),
+        "Authority does not match the buffer's authority",
+    )?;
+    assert_with_msg(
+        header.finalized == 0,
+        UploaderError::AlreadyFinalized.into(),
+        "Buffer is finalized",
+    )
+}
+
+pub struct Processor {}
+
+impl Processor {
+    pub fn process_instruction(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        instruction_data: &[u8],
+    ) -> ProgramResult {
+        let instruction = UploaderInstruction::try_from_slice(instruction_data)
+            .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+        let accounts_iter = &mut accounts.iter();
+        let buffer_ai = next_account_info(accounts_iter)?;
+        let authority = next_account_info(accounts_iter)?;
+        match instruction {
+            UploaderInstruction::Initialize { data_len } => {
+                msg!("Instruction: Initialize");
+                expect_signer(
+                    authority,
+                    ProgramError::MissingRequiredSignature,
+                    "Authority must sign",
+                )?;
+                let data_len =
+                    usize::try_from(data_len).map_err(|_| UploaderError::DataTooLarge)?;
+                let size = buffer_size(data_len).ok_or(UploaderError::DataTooLarge)?;
+                check_account(buffer_ai, program_id, size)?;
+                let mut data = buffer_ai.try_borrow_mut_data()?;
+                write_header::<BufferHeader>(&mut data)?;
+                let header: &mut BufferHeader =
+                    from_bytes_mut(&mut data[HEADER_LEN..BufferHeader::LEN]);
+                header.authority = *authority.key;
+                header.data_len = data_len as u64;
+                header.chunk_count = chunk_count(data_len) as u32;
+                msg!("Buffer of {} bytes in {} chunks", data_len, header.chunk_count);
+            }
+            UploaderInstruction::Write { offset, data } => {
+                msg!("Instruction: Write");
+                let mut buffer = Buffer::load_mut(buffer_ai, program_id)?;
+                let (header, bitmap, contents) = buffer.split();
+                assert_writable_buffer(header, authority)?;
+
+                let offset = offset as usize;
+                assert_with_msg(
+                    offset % CHUNK_SIZE == 0 && offset < contents.len(),
+                    UploaderError::InvalidChunk.into(),
+                    "Offset is not the start of a chunk",
+                )?;
+                let end = (offset + CHUNK_SIZE).min(contents.len());
+                assert_with_msg(
+                    data.len() == end - offset,
+                    UploaderError::InvalidChunk.into(),
+                    "Data does not fill the chunk",
+                )?;
+                contents[offset..end].copy_from_slice(&data);
+                let chunk = offset / CHUNK_SIZE;
+                bitmap[chunk / 8] |= 1 << (chunk % 8);
+                msg!("Wrote chunk {} of {}", chunk, header.chunk_count);
+            }
+            UploaderInstruction::Finalize { hash: expected } => {
+                msg!("Instruction: Finalize");
+                let mut buffer = Buffer::load_mut(buffer_ai, program_id)?;
+                let (header, bitmap, contents) = buffer.split();
+                assert_writable_buffer(header, authority)?;
+
+                let missing = (0..header.chunk_count as usize)
+                    .filter(|chunk| !is_chunk_written(bitmap, *chunk))
+                    .count();
+                if missing > 0 {
+                    msg!("{} chunks are missing", missing);
+                    return Err(UploaderError::MissingChunks.into());
+                }
+                assert_with_msg(
+                    hash(contents).to_bytes() == expected,
+                    UploaderError::HashMismatch.into(),
+                    "Hash of the buffer does not match",
+                )?;
+                header.finalized = 1;
+            }
+        }
+        Ok(())
+    }
+}
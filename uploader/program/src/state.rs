@@ -0,0 +1,71 @@
+use bootcamp_accounts::{check_account, check_header, ZeroCopy, HEADER_LEN};
+use bytemuck::{from_bytes_mut, Pod, Zeroable};
+use solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+
+use std::cell::RefMut;
+
+/// Chunks are 1KB (1000 bytes) so that a chunk and the rest of the `Write` instruction
+/// fit in a 1232 byte transaction.
+pub const CHUNK_SIZE: usize = 1000;
+
+/// Largest blob a buffer holds. `Finalize` hashes the whole blob in one instruction, at
+/// about one compute unit per two bytes, which has to fit in the 200k compute units of an
+/// instruction next to the bitmap check.
+pub const MAX_DATA_LEN: usize = 100 * CHUNK_SIZE;
+
+/// Layout of a buffer account: `BufferHeader` | bitmap of written chunks | data
+#[derive(Debug, Clone, Copy, Zeroable, Pod, ZeroCopy)]
+#[repr(C)]
+pub struct BufferHeader {
+    pub authority: Pubkey,
+    pub data_len: u64,
+    pub chunk_count: u32,
+    pub finalized: u8,
+    pub _padding: [u8; 3],
+}
+
+pub fn chunk_count(data_len: usize) -> usize {
+    (data_len + CHUNK_SIZE - 1) / CHUNK_SIZE
+}
+
+pub fn bitmap_len(data_len: usize) -> usize {
+    (chunk_count(data_len) + 7) / 8
+}
+
+/// Account size needed to upload `data_len` bytes, `None` past `MAX_DATA_LEN`.
+pub fn buffer_size(data_len: usize) -> Option<usize> {
+    if data_len > MAX_DATA_LEN {
+        return None;
+    }
+    Some(BufferHeader::LEN + bitmap_len(data_len) + data_len)
+}
+
+/// Header, bitmap and data of an initialized buffer account.
+pub struct Buffer<'a, 'b> {
+    data: RefMut<'a, &'b mut [u8]>,
+}
+
+impl<'a, 'b> Buffer<'a, 'b> {
+    pub fn load_mut(
+        account: &'a AccountInfo<'b>,
+        program_id: &Pubkey,
+    ) -> Result<Self, ProgramError> {
+        check_account(account, program_id, BufferHeader::LEN)?;
+        let data = account.try_borrow_mut_data()?;
+        check_header::<BufferHeader>(&data)?;
+        Ok(Self { data })
+    }
+
+    /// Splits the account into its header, chunk bitmap and uploaded data.
+    pub fn split(&mut self) -> (&mut BufferHeader, &mut [u8], &mut [u8]) {
+        let (header, rest) = self.data.split_at_mut(BufferHeader::LEN);
+        let header: &mut BufferHeader = from_bytes_mut(&mut header[HEADER_LEN..]);
+        let data_len = header.data_len as usize;
+        let (bitmap, data) = rest.split_at_mut(bitmap_len(data_len));
+        (header, bitmap, &mut data[..data_len])
+    }
+}
+
+pub fn is_chunk_written(bitmap: &[u8], chunk: usize) -> bool {
+    bitmap[chunk / 8] & (1 << (chunk % 8)) != 0
+}